@@ -0,0 +1,28 @@
+use dotenvy::dotenv;
+
+use crate::{
+    cli::ConfigCommand,
+    core::{config::AppConfig, result::AppResult},
+    database::surreal::migration::MigrationRunner,
+    storage::init_storage,
+    utils::{device::UserAgentParser, geoip::GeoIpResolver, password::PasswordHasher},
+};
+
+pub async fn execute(command: ConfigCommand) -> AppResult<()> {
+    dotenv().ok();
+    match command {
+        ConfigCommand::Check => {
+            // Builds the same pieces as the server does at startup, so any error shows up here.
+            let config = AppConfig::init()?;
+            init_storage(&config.storage)?;
+            PasswordHasher::new(&config.password_hashing)?;
+            UserAgentParser::new(&config.user_agent)?;
+            GeoIpResolver::new(&config.geoip)?;
+            let runner = MigrationRunner::new(&config.migration);
+            runner.schema_scripts()?;
+            runner.migration_scripts()?;
+            println!("Configuration is valid");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use dotenvy::dotenv;
+
+use crate::{
+    cli::MigrateCommand,
+    core::{config::AppConfig, result::AppResult},
+    database::surreal::{client::SurrealClient, migration::MigrationRunner},
+};
+
+/// Runs without Redis, so migrations can be applied before the rest of the stack is up.
+pub async fn execute(command: MigrateCommand) -> AppResult<()> {
+    dotenv().ok();
+    let config = AppConfig::init()?;
+    let surreal_client =
+        SurrealClient::new(config.surreal_server.clone(), &config.database).await?;
+    let runner = MigrationRunner::new(&config.migration);
+    match command {
+        MigrateCommand::Up { dry_run } => {
+            let names = runner.up(&surreal_client, dry_run).await?;
+            let verb = if dry_run { "Would apply" } else { "Applied" };
+            if names.is_empty() {
+                println!("No pending migrations");
+            }
+            for name in names {
+                println!("{} {}", verb, name);
+            }
+        }
+        MigrateCommand::Status => {
+            for status in runner.status(&surreal_client).await? {
+                match status.executed_at {
+                    Some(executed_at) => println!(
+                        "{:<8} {} ({})",
+                        status.state,
+                        status.name,
+                        executed_at.to_rfc3339()
+                    ),
+                    None => println!("{:<8} {}", status.state, status.name),
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,108 @@
+pub mod config;
+pub mod migrate;
+pub mod sessions;
+pub mod user;
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate::{core::result::AppResult, models::user::UserRole, run};
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Auth backend and its maintenance commands")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Start the HTTP server (default).
+    Serve,
+    /// Apply or inspect schema migrations.
+    #[command(subcommand)]
+    Migrate(MigrateCommand),
+    /// Manage user accounts.
+    #[command(subcommand)]
+    User(UserCommand),
+    /// Manage login sessions.
+    #[command(subcommand)]
+    Sessions(SessionsCommand),
+    /// Inspect the configuration.
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MigrateCommand {
+    /// Apply the schemas and every pending migration.
+    Up {
+        /// Only list the migrations that would be applied.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// List every migration and whether it was applied.
+    Status,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum UserCommand {
+    /// Create a verified, active account. The password is read from standard input, so it
+    /// doesn't show up in the process list or the shell history.
+    Create {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        email: String,
+        #[arg(long)]
+        admin: bool,
+    },
+    /// Change the role of an account.
+    SetRole {
+        #[arg(long)]
+        email: String,
+        #[arg(long, value_enum)]
+        role: RoleArg,
+    },
+    /// Reactivate a suspended account and forget its failed login attempts.
+    Unlock {
+        #[arg(long)]
+        email: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SessionsCommand {
+    /// Delete refresh tokens that have expired.
+    PurgeExpired,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Load the configuration and everything built from it, without starting the server.
+    Check,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum RoleArg {
+    Admin,
+    User,
+}
+
+impl From<RoleArg> for UserRole {
+    fn from(role: RoleArg) -> Self {
+        match role {
+            RoleArg::Admin => UserRole::Admin,
+            RoleArg::User => UserRole::User,
+        }
+    }
+}
+
+pub async fn execute(cli: Cli) -> AppResult<()> {
+    match cli.command.unwrap_or(Command::Serve) {
+        Command::Serve => run().await,
+        Command::Migrate(command) => migrate::execute(command).await,
+        Command::User(command) => user::execute(command).await,
+        Command::Sessions(command) => sessions::execute(command).await,
+        Command::Config(command) => config::execute(command).await,
+    }
+}
@@ -0,0 +1,22 @@
+use dotenvy::dotenv;
+
+use crate::{
+    cli::SessionsCommand,
+    core::{config::AppConfig, result::AppResult},
+    database::surreal::client::SurrealClient,
+    repositories::surreal::refresh_token::RefreshTokenRepository,
+};
+
+pub async fn execute(command: SessionsCommand) -> AppResult<()> {
+    dotenv().ok();
+    let config = AppConfig::init()?;
+    let surreal_client =
+        SurrealClient::new(config.surreal_server.clone(), &config.database).await?;
+    match command {
+        SessionsCommand::PurgeExpired => {
+            let count = surreal_client.delete_expired_refresh_tokens().await?;
+            println!("Deleted {} expired sessions", count);
+        }
+    }
+    Ok(())
+}
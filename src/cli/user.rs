@@ -0,0 +1,124 @@
+use std::io::BufRead;
+
+use dotenvy::dotenv;
+
+use crate::{
+    cli::UserCommand,
+    config::redis_server::CacheBackend,
+    core::{
+        config::AppConfig,
+        error::{external::ExternalError, user::UserErrorKind},
+        result::AppResult,
+    },
+    database::client::DBClient,
+    dto::request::auth::RegisterRequest,
+    models::user::{User, UserRole, UserStatus},
+    utils::{password::PasswordHasher, password_policy::PasswordPolicy},
+    validation::auth::validate_register_payload,
+};
+
+pub async fn execute(command: UserCommand) -> AppResult<()> {
+    dotenv().ok();
+    let config = AppConfig::init()?;
+    let db_client = DBClient::new(config.clone()).await?;
+    match command {
+        UserCommand::Create { name, email, admin } => {
+            let password = read_password()?;
+            let role = if admin {
+                UserRole::Admin
+            } else {
+                UserRole::User
+            };
+            let user = create_user(&config, &db_client, name, email, password, role).await?;
+            println!("Created {:?} {} ({})", user.role, user.email, user.id);
+        }
+        UserCommand::SetRole { email, role } => {
+            let user = find_user(&db_client, &email).await?;
+            let user = db_client.database.update_role(user.id, role.into()).await?;
+            db_client.cache.delete_user(&user.id).await?;
+            println!("{} is now {:?}", user.email, user.role);
+        }
+        UserCommand::Unlock { email } => {
+            let user = find_user(&db_client, &email).await?;
+            if user.status == UserStatus::Suspended {
+                db_client
+                    .database
+                    .update_status(user.id.clone(), UserStatus::Active)
+                    .await?;
+            }
+            match config.redis_server.cache_backend {
+                CacheBackend::Redis => {
+                    db_client.cache.clear_login_failures(&user.email).await?;
+                    db_client.cache.delete_user(&user.id).await?;
+                    println!("Unlocked {}", user.email);
+                }
+                // This process has a cache of its own, the server's can't be reached from here.
+                CacheBackend::Memory => eprintln!(
+                    "Failed login attempts of {} weren't cleared: with cache_backend = \"memory\" they are kept by the server, restart it to clear them",
+                    user.email
+                ),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Goes through the same validation and password policy as registration, but the account is
+/// verified right away since there is no one to send the email to yet.
+async fn create_user(
+    config: &AppConfig,
+    db_client: &DBClient,
+    name: String,
+    email: String,
+    password: String,
+    role: UserRole,
+) -> AppResult<User> {
+    validate_register_payload(&RegisterRequest {
+        name: name.clone(),
+        email: email.clone(),
+        password: password.clone(),
+        confirm_password: password.clone(),
+        challenge: None,
+    })?;
+    PasswordPolicy::new(config.password_policy.clone())
+        .check(&password, &[&name, &email])
+        .await?;
+    if db_client
+        .database
+        .find_user_by_email(&email)
+        .await?
+        .is_some()
+    {
+        return Err(UserErrorKind::UserAlreadyExists.into());
+    }
+    let password_hash = PasswordHasher::new(&config.password_hashing)?
+        .hash_password(&password)
+        .await?;
+    db_client
+        .database
+        .create_user(&name, &email, &password_hash, role)
+        .await?;
+    let user = find_user(db_client, &email).await?;
+    db_client
+        .database
+        .user_verified(user.id.clone(), UserStatus::Active)
+        .await?;
+    find_user(db_client, &email).await
+}
+
+async fn find_user(db_client: &DBClient, email: &str) -> AppResult<User> {
+    match db_client.database.find_user_by_email(email).await? {
+        Some(user) => Ok(user),
+        None => Err(UserErrorKind::UserNotFound.into()),
+    }
+}
+
+fn read_password() -> AppResult<String> {
+    eprint!("Password: ");
+    let mut password = String::new();
+    std::io::stdin()
+        .lock()
+        .read_line(&mut password)
+        .map_err(ExternalError::from)?;
+    Ok(password.trim_end_matches(['\r', '\n']).to_string())
+}
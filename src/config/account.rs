@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountConfig {
+    #[serde(default = "default_account_deletion_grace_days")]
+    pub account_deletion_grace_days: i64,
+    #[serde(default = "default_account_purge_interval_seconds")]
+    pub account_purge_interval_seconds: u64,
+    #[serde(default = "default_data_export_expires_hours")]
+    pub data_export_expires_hours: i64,
+}
+
+impl AccountConfig {
+    pub fn validate(&self, errors: &mut Vec<String>) {
+        if self.account_deletion_grace_days < 0 {
+            errors.push("account_deletion_grace_days must not be negative".to_string());
+        }
+        if self.account_purge_interval_seconds == 0 {
+            errors.push("account_purge_interval_seconds must be positive".to_string());
+        }
+        if self.data_export_expires_hours <= 0 {
+            errors.push("data_export_expires_hours must be positive".to_string());
+        }
+    }
+}
+
+fn default_account_deletion_grace_days() -> i64 {
+    30
+}
+
+fn default_account_purge_interval_seconds() -> u64 {
+    3600
+}
+
+fn default_data_export_expires_hours() -> i64 {
+    48
+}
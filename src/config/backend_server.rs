@@ -1,6 +1,28 @@
 use serde::{Deserialize, Serialize};
 
+use crate::config::is_http_url;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BackendServerConfig {
     pub backend_port: u16,
+    #[serde(default = "default_backend_address")]
+    pub backend_address: String,
+}
+
+impl BackendServerConfig {
+    pub fn validate(&self, errors: &mut Vec<String>) {
+        if self.backend_port == 0 {
+            errors.push("backend_port must not be 0".to_string());
+        }
+        if !is_http_url(&self.backend_address) {
+            errors.push(format!(
+                "backend_address must be an http(s) URL, got {:?}",
+                self.backend_address
+            ));
+        }
+    }
+}
+
+fn default_backend_address() -> String {
+    "http://localhost:7878".to_string()
 }
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChallengeProvider {
+    /// Server-issued proof of work, no external service involved.
+    #[default]
+    Pow,
+    /// hCaptcha, Turnstile or any verifier speaking the same `siteverify` protocol.
+    Captcha,
+    /// Challenges are never asked for.
+    None,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChallengeConfig {
+    #[serde(default)]
+    pub challenge_provider: ChallengeProvider,
+    #[serde(default = "default_challenge_ttl_seconds")]
+    pub challenge_ttl_seconds: u64,
+    /// Challenges one IP can get per minute, so unsolved ones can't pile up in the cache.
+    #[serde(default = "default_challenge_issue_limit_per_minute")]
+    pub challenge_issue_limit_per_minute: u64,
+    /// Leading zero bits the proof-of-work hash needs, each bit doubles the client's work.
+    #[serde(default = "default_challenge_pow_difficulty")]
+    pub challenge_pow_difficulty: u32,
+    /// e.g. `https://api.hcaptcha.com/siteverify` or
+    /// `https://challenges.cloudflare.com/turnstile/v0/siteverify`.
+    pub challenge_captcha_verify_url: Option<String>,
+    pub challenge_captcha_site_key: Option<String>,
+    pub challenge_captcha_secret: Option<String>,
+    #[serde(default = "default_true")]
+    pub challenge_on_register: bool,
+    #[serde(default = "default_true")]
+    pub challenge_on_forget_password: bool,
+    /// Failed attempts for an email, registered or not, after which its logins need a challenge.
+    #[serde(default = "default_challenge_login_failures")]
+    pub challenge_login_failures: u64,
+}
+
+impl ChallengeConfig {
+    pub fn validate(&self, errors: &mut Vec<String>) {
+        if self.challenge_provider == ChallengeProvider::Captcha {
+            if self.challenge_captcha_verify_url.is_none() {
+                errors.push(
+                    "challenge_captcha_verify_url is required when challenge_provider is captcha"
+                        .to_string(),
+                );
+            }
+            if self.challenge_captcha_secret.is_none() {
+                errors.push(
+                    "challenge_captcha_secret is required when challenge_provider is captcha"
+                        .to_string(),
+                );
+            }
+        }
+        if !(1..=32).contains(&self.challenge_pow_difficulty) {
+            errors.push("challenge_pow_difficulty must be between 1 and 32".to_string());
+        }
+        if self.challenge_ttl_seconds == 0 {
+            errors.push("challenge_ttl_seconds must be positive".to_string());
+        }
+        if self.challenge_issue_limit_per_minute == 0 {
+            errors.push("challenge_issue_limit_per_minute must be positive".to_string());
+        }
+    }
+}
+
+fn default_challenge_ttl_seconds() -> u64 {
+    300
+}
+
+fn default_challenge_issue_limit_per_minute() -> u64 {
+    30
+}
+
+fn default_challenge_pow_difficulty() -> u32 {
+    20
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_challenge_login_failures() -> u64 {
+    3
+}
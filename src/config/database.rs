@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+/// Connection handling shared by SurrealDB and Redis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseConfig {
+    /// How long a single query may take before it fails with a 504.
+    #[serde(default = "default_db_query_timeout_ms")]
+    pub db_query_timeout_ms: u64,
+    /// How long a single connection attempt may take.
+    #[serde(default = "default_db_connect_timeout_ms")]
+    pub db_connect_timeout_ms: u64,
+    /// Connection attempts after the first one, with exponential backoff between them.
+    #[serde(default = "default_db_connect_retries")]
+    pub db_connect_retries: usize,
+    /// Upper bound of the backoff between connection attempts.
+    #[serde(default = "default_db_reconnect_max_delay_ms")]
+    pub db_reconnect_max_delay_ms: u64,
+    /// Consecutive timeouts or connection errors after which queries fail fast with a 503.
+    #[serde(default = "default_circuit_breaker_failure_threshold")]
+    pub circuit_breaker_failure_threshold: u32,
+    /// How long queries fail fast before the next one is let through to probe the server.
+    #[serde(default = "default_circuit_breaker_reset_seconds")]
+    pub circuit_breaker_reset_seconds: u64,
+}
+
+impl DatabaseConfig {
+    pub fn validate(&self, errors: &mut Vec<String>) {
+        for (name, value) in [
+            ("db_query_timeout_ms", self.db_query_timeout_ms),
+            ("db_connect_timeout_ms", self.db_connect_timeout_ms),
+            ("db_reconnect_max_delay_ms", self.db_reconnect_max_delay_ms),
+            (
+                "circuit_breaker_failure_threshold",
+                self.circuit_breaker_failure_threshold.into(),
+            ),
+            (
+                "circuit_breaker_reset_seconds",
+                self.circuit_breaker_reset_seconds,
+            ),
+        ] {
+            if value == 0 {
+                errors.push(format!("{} must be positive", name));
+            }
+        }
+    }
+}
+
+fn default_db_query_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_db_connect_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_db_connect_retries() -> usize {
+    6
+}
+
+fn default_db_reconnect_max_delay_ms() -> u64 {
+    10000
+}
+
+fn default_circuit_breaker_failure_threshold() -> u32 {
+    5
+}
+
+fn default_circuit_breaker_reset_seconds() -> u64 {
+    30
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceConfig {
+    /// Key signing the device cookie, the JWT secret is used when unset.
+    pub device_cookie_secret: Option<String>,
+    #[serde(default = "default_device_cookie_max_age_days")]
+    pub device_cookie_max_age_days: i64,
+}
+
+impl DeviceConfig {
+    pub fn validate(&self, errors: &mut Vec<String>) {
+        if self.device_cookie_max_age_days <= 0 {
+            errors.push("device_cookie_max_age_days must be positive".to_string());
+        }
+    }
+}
+
+fn default_device_cookie_max_age_days() -> i64 {
+    365
+}
@@ -1,6 +1,23 @@
+use axum::http::HeaderValue;
 use serde::{Deserialize, Serialize};
 
+use crate::config::is_http_url;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FrontendServerConfig {
     pub frontend_address: String,
 }
+
+impl FrontendServerConfig {
+    pub fn validate(&self, errors: &mut Vec<String>) {
+        // Used as the CORS origin, so it has to be a valid header value as well.
+        if !is_http_url(&self.frontend_address)
+            || self.frontend_address.parse::<HeaderValue>().is_err()
+        {
+            errors.push(format!(
+                "frontend_address must be an http(s) URL, got {:?}",
+                self.frontend_address
+            ));
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeoIpConfig {
+    /// Path of a MaxMind-format city database, e.g. `GeoLite2-City.mmdb`.
+    pub geoip_city_db_path: Option<String>,
+    /// Path of a MaxMind-format ASN database, e.g. `GeoLite2-ASN.mmdb`.
+    pub geoip_asn_db_path: Option<String>,
+    /// How often the database files are checked for changes.
+    #[serde(default = "default_geoip_reload_interval_seconds")]
+    pub geoip_reload_interval_seconds: u64,
+}
+
+impl GeoIpConfig {
+    pub fn validate(&self, errors: &mut Vec<String>) {
+        for path in [&self.geoip_city_db_path, &self.geoip_asn_db_path]
+            .into_iter()
+            .flatten()
+        {
+            if !Path::new(path).is_file() {
+                errors.push(format!("GeoIP database {} does not exist", path));
+            }
+        }
+        if self.geoip_reload_interval_seconds == 0 {
+            errors.push("geoip_reload_interval_seconds must be positive".to_string());
+        }
+    }
+}
+
+fn default_geoip_reload_interval_seconds() -> u64 {
+    300
+}
@@ -5,3 +5,14 @@ pub struct JwtConfig {
     pub jwt_secret: String,
     pub jwt_expires_in_seconds: i64,
 }
+
+impl JwtConfig {
+    pub fn validate(&self, errors: &mut Vec<String>) {
+        if self.jwt_secret.is_empty() {
+            errors.push("jwt_secret must not be empty".to_string());
+        }
+        if self.jwt_expires_in_seconds <= 0 {
+            errors.push("jwt_expires_in_seconds must be positive".to_string());
+        }
+    }
+}
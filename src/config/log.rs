@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use tracing_subscriber::EnvFilter;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogConfig {
+    /// Filter directives such as `info` or `info,backend=debug`. `RUST_LOG` or `info` is used when unset.
+    pub log_level: Option<String>,
+}
+
+impl LogConfig {
+    pub fn validate(&self, errors: &mut Vec<String>) {
+        if let Some(level) = &self.log_level
+            && let Err(e) = EnvFilter::try_new(level)
+        {
+            errors.push(format!("log_level {:?} is invalid: {}", level, e));
+        }
+    }
+}
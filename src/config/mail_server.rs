@@ -1,7 +1,33 @@
 use serde::{Deserialize, Serialize};
 
+use crate::config::is_http_url;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MailServerConfig {
     pub from_email: String,
     pub resend_api_key: String,
+    /// Sends through a proxy or a local test server instead of the Resend API.
+    pub resend_base_url: Option<String>,
+}
+
+impl MailServerConfig {
+    pub fn validate(&self, errors: &mut Vec<String>) {
+        if !self.from_email.contains('@') {
+            errors.push(format!(
+                "from_email must be an email address, got {:?}",
+                self.from_email
+            ));
+        }
+        if self.resend_api_key.is_empty() {
+            errors.push("resend_api_key must not be empty".to_string());
+        }
+        if let Some(url) = &self.resend_base_url
+            && !is_http_url(url)
+        {
+            errors.push(format!(
+                "resend_base_url must be an http(s) URL, got {:?}",
+                url
+            ));
+        }
+    }
 }
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    /// Whether `/metrics` is served. It answers 404 otherwise.
+    #[serde(default)]
+    pub metrics_enabled: bool,
+    /// Bearer token `/metrics` requires. Without one it's open to anyone who can reach it, so
+    /// only leave it unset when the endpoint isn't exposed publicly.
+    pub metrics_token: Option<String>,
+}
+
+impl MetricsConfig {
+    pub fn validate(&self, errors: &mut Vec<String>) {
+        if self
+            .metrics_token
+            .as_ref()
+            .is_some_and(|token| token.trim().is_empty())
+        {
+            errors.push("metrics_token must not be empty when set".to_string());
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationConfig {
+    /// Directory holding the `schemas/` and `migrations/` folders.
+    #[serde(default = "default_migration_dir")]
+    pub migration_dir: String,
+    /// Applies pending migrations before the server starts.
+    #[serde(default = "default_migrate_on_startup")]
+    pub migrate_on_startup: bool,
+}
+
+impl MigrationConfig {
+    pub fn validate(&self, errors: &mut Vec<String>) {
+        if !Path::new(&self.migration_dir).is_dir() {
+            errors.push(format!(
+                "migration_dir {} is not a directory",
+                self.migration_dir
+            ));
+        }
+    }
+}
+
+fn default_migration_dir() -> String {
+    ".".to_string()
+}
+
+fn default_migrate_on_startup() -> bool {
+    true
+}
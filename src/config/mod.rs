@@ -1,6 +1,25 @@
+pub mod account;
 pub mod backend_server;
+pub mod challenge;
+pub mod database;
+pub mod device;
 pub mod frontend_server;
+pub mod geoip;
 pub mod jwt;
+pub mod log;
 pub mod mail_server;
+pub mod metrics;
+pub mod migration;
+pub mod password_hashing;
+pub mod password_policy;
 pub mod redis_server;
+pub mod risk;
+pub mod security;
+pub mod storage;
 pub mod surreal_server;
+pub mod user_agent;
+
+/// Whether `value` is an absolute `http://` or `https://` URL.
+pub(crate) fn is_http_url(value: &str) -> bool {
+    reqwest::Url::parse(value).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordHashingConfig {
+    /// Argon2id memory cost in KiB.
+    #[serde(default = "default_argon2_memory_kib")]
+    pub argon2_memory_kib: u32,
+    #[serde(default = "default_argon2_iterations")]
+    pub argon2_iterations: u32,
+    #[serde(default = "default_argon2_parallelism")]
+    pub argon2_parallelism: u32,
+    /// Server-side secret mixed into every new hash. It is never stored in the database.
+    pub password_pepper: Option<String>,
+    /// Peppers used before the current one (or before it was removed). Hashes made under them
+    /// still verify and are rehashed under the current pepper on the next login.
+    #[serde(default)]
+    pub password_previous_peppers: Vec<String>,
+    /// Number of hashing threads, defaults to the number of CPUs.
+    pub password_hashing_concurrency: Option<usize>,
+    /// How long a request may wait for a free hashing thread before getting a 503.
+    #[serde(default = "default_password_hashing_queue_timeout_ms")]
+    pub password_hashing_queue_timeout_ms: u64,
+}
+
+impl PasswordHashingConfig {
+    pub fn validate(&self, errors: &mut Vec<String>) {
+        if let Err(e) = argon2::Params::new(
+            self.argon2_memory_kib,
+            self.argon2_iterations,
+            self.argon2_parallelism,
+            None,
+        ) {
+            errors.push(format!("Invalid Argon2 parameters: {}", e));
+        }
+        if self
+            .password_previous_peppers
+            .iter()
+            .any(|pepper| pepper.is_empty())
+        {
+            errors.push("password_previous_peppers must not contain empty peppers".to_string());
+        }
+        if self.password_hashing_concurrency == Some(0) {
+            errors.push("password_hashing_concurrency must be positive".to_string());
+        }
+    }
+}
+
+fn default_argon2_memory_kib() -> u32 {
+    19 * 1024
+}
+
+fn default_argon2_iterations() -> u32 {
+    2
+}
+
+fn default_argon2_parallelism() -> u32 {
+    1
+}
+
+fn default_password_hashing_queue_timeout_ms() -> u64 {
+    5000
+}
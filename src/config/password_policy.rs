@@ -0,0 +1,71 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordPolicyConfig {
+    #[serde(default = "default_password_min_length")]
+    pub password_min_length: usize,
+    #[serde(default = "default_password_max_length")]
+    pub password_max_length: usize,
+    #[serde(default = "default_true")]
+    pub password_require_letter: bool,
+    #[serde(default = "default_true")]
+    pub password_require_digit: bool,
+    #[serde(default = "default_true")]
+    pub password_require_special: bool,
+    #[serde(default)]
+    pub password_require_mixed_case: bool,
+    /// Minimum zxcvbn score from 0 (too guessable) to 4 (very unguessable).
+    #[serde(default = "default_password_min_strength_score")]
+    pub password_min_strength_score: u8,
+    /// Number of recent passwords, the current one included, that can't be reused.
+    #[serde(default = "default_password_history_size")]
+    pub password_history_size: usize,
+    /// Directory of `<PREFIX>.txt` files holding `SUFFIX:COUNT` lines of breached SHA-1 hashes.
+    pub breached_password_range_dir: Option<String>,
+    /// Base URL of a HIBP-compatible range API, e.g. `https://api.pwnedpasswords.com/range`.
+    pub breached_password_api_url: Option<String>,
+}
+
+impl PasswordPolicyConfig {
+    pub fn validate(&self, errors: &mut Vec<String>) {
+        if self.password_min_length == 0 || self.password_min_length > self.password_max_length {
+            errors.push(format!(
+                "password_min_length must be between 1 and password_max_length ({}), got {}",
+                self.password_max_length, self.password_min_length
+            ));
+        }
+        if self.password_min_strength_score > 4 {
+            errors.push("password_min_strength_score must be between 0 and 4".to_string());
+        }
+        if let Some(dir) = &self.breached_password_range_dir
+            && !Path::new(dir).is_dir()
+        {
+            errors.push(format!(
+                "breached_password_range_dir {} is not a directory",
+                dir
+            ));
+        }
+    }
+}
+
+fn default_password_min_length() -> usize {
+    8
+}
+
+fn default_password_max_length() -> usize {
+    128
+}
+
+fn default_password_history_size() -> usize {
+    5
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_password_min_strength_score() -> u8 {
+    2
+}
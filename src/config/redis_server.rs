@@ -1,6 +1,34 @@
 use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheBackend {
+    /// Redis at `redis_address`, shared by every instance of the backend.
+    #[default]
+    Redis,
+    /// Kept in this process. Only for a single instance, and lost on restart.
+    Memory,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RedisServerConfig {
+    #[serde(default)]
+    pub cache_backend: CacheBackend,
+    #[serde(default)]
     pub redis_address: String,
 }
+
+impl RedisServerConfig {
+    pub fn validate(&self, errors: &mut Vec<String>) {
+        if self.cache_backend == CacheBackend::Redis
+            && !["redis://", "rediss://", "unix://"]
+                .iter()
+                .any(|scheme| self.redis_address.starts_with(scheme))
+        {
+            errors.push(format!(
+                "redis_address must be a redis:// URL, got {:?}",
+                self.redis_address
+            ));
+        }
+    }
+}
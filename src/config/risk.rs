@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskConfig {
+    /// Logins scoring at least this much need an emailed code before tokens are issued.
+    #[serde(default = "default_risk_step_up_score")]
+    pub risk_step_up_score: u32,
+    /// Logins scoring at least this much are refused.
+    #[serde(default = "default_risk_block_score")]
+    pub risk_block_score: u32,
+    #[serde(default = "default_risk_new_device_score")]
+    pub risk_new_device_score: u32,
+    /// Kept below `risk_block_score`: logins from a blacklisted IP already have to solve a
+    /// challenge, which would be pointless if the login were refused anyway.
+    #[serde(default = "default_risk_blacklisted_ip_score")]
+    pub risk_blacklisted_ip_score: u32,
+    #[serde(default = "default_risk_impossible_travel_score")]
+    pub risk_impossible_travel_score: u32,
+    /// Travel between two logins faster than this is considered impossible.
+    #[serde(default = "default_risk_max_travel_speed_kmh")]
+    pub risk_max_travel_speed_kmh: f64,
+    /// Added per failed password attempt within the window.
+    #[serde(default = "default_risk_failed_attempt_score")]
+    pub risk_failed_attempt_score: u32,
+    /// Failed attempts add up to at most this much. Anyone can fail logins on an email, so they
+    /// never count towards `risk_block_score` and can only force a step-up.
+    #[serde(default = "default_risk_failed_attempts_max_score")]
+    pub risk_failed_attempts_max_score: u32,
+    /// Counted from the first failure, later ones don't extend it.
+    #[serde(default = "default_risk_failed_attempts_window_seconds")]
+    pub risk_failed_attempts_window_seconds: u64,
+    #[serde(default = "default_risk_unusual_hour_score")]
+    pub risk_unusual_hour_score: u32,
+    /// Local hours, in the user's timezone, in `[start, end)` count as unusual.
+    #[serde(default = "default_risk_unusual_hours_start")]
+    pub risk_unusual_hours_start: u32,
+    #[serde(default = "default_risk_unusual_hours_end")]
+    pub risk_unusual_hours_end: u32,
+}
+
+impl RiskConfig {
+    pub fn validate(&self, errors: &mut Vec<String>) {
+        if self.risk_step_up_score > self.risk_block_score {
+            errors.push("risk_step_up_score must not be above risk_block_score".to_string());
+        }
+        if self.risk_blacklisted_ip_score >= self.risk_block_score {
+            errors.push("risk_blacklisted_ip_score must be below risk_block_score".to_string());
+        }
+        if self.risk_unusual_hours_start > 23 || self.risk_unusual_hours_end > 24 {
+            errors.push(
+                "risk_unusual_hours_start and risk_unusual_hours_end must be hours of the day"
+                    .to_string(),
+            );
+        }
+        if self.risk_max_travel_speed_kmh <= 0.0 {
+            errors.push("risk_max_travel_speed_kmh must be positive".to_string());
+        }
+    }
+}
+
+fn default_risk_step_up_score() -> u32 {
+    40
+}
+
+fn default_risk_block_score() -> u32 {
+    80
+}
+
+fn default_risk_new_device_score() -> u32 {
+    20
+}
+
+fn default_risk_blacklisted_ip_score() -> u32 {
+    50
+}
+
+fn default_risk_impossible_travel_score() -> u32 {
+    50
+}
+
+fn default_risk_max_travel_speed_kmh() -> f64 {
+    1000.0
+}
+
+fn default_risk_failed_attempt_score() -> u32 {
+    10
+}
+
+fn default_risk_failed_attempts_max_score() -> u32 {
+    40
+}
+
+fn default_risk_failed_attempts_window_seconds() -> u64 {
+    3600
+}
+
+fn default_risk_unusual_hour_score() -> u32 {
+    10
+}
+
+fn default_risk_unusual_hours_start() -> u32 {
+    1
+}
+
+fn default_risk_unusual_hours_end() -> u32 {
+    5
+}
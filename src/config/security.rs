@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityConfig {
+    /// Makes register, login and forget-password indistinguishable for known and unknown emails.
+    #[serde(default)]
+    pub anti_enumeration: bool,
+}
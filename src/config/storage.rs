@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    #[default]
+    Local,
+    S3,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageConfig {
+    #[serde(default)]
+    pub storage_backend: StorageBackend,
+    #[serde(default = "default_storage_local_path")]
+    pub storage_local_path: String,
+    #[serde(default = "default_storage_public_url")]
+    pub storage_public_url: String,
+    pub s3_endpoint: Option<String>,
+    pub s3_bucket: Option<String>,
+    #[serde(default = "default_s3_region")]
+    pub s3_region: String,
+    pub s3_access_key: Option<String>,
+    pub s3_secret_key: Option<String>,
+    #[serde(default = "default_avatar_max_bytes")]
+    pub avatar_max_bytes: usize,
+}
+
+impl StorageConfig {
+    pub fn validate(&self, errors: &mut Vec<String>) {
+        if self.storage_backend == StorageBackend::S3 {
+            for (name, value) in [
+                ("s3_endpoint", &self.s3_endpoint),
+                ("s3_bucket", &self.s3_bucket),
+                ("s3_access_key", &self.s3_access_key),
+                ("s3_secret_key", &self.s3_secret_key),
+            ] {
+                if value.is_none() {
+                    errors.push(format!("{} is required when storage_backend is s3", name));
+                }
+            }
+        }
+        if self.avatar_max_bytes == 0 {
+            errors.push("avatar_max_bytes must be positive".to_string());
+        }
+    }
+}
+
+fn default_storage_local_path() -> String {
+    "./uploads".to_string()
+}
+
+fn default_storage_public_url() -> String {
+    "/api/v1/uploads".to_string()
+}
+
+fn default_s3_region() -> String {
+    "us-east-1".to_string()
+}
+
+fn default_avatar_max_bytes() -> usize {
+    5 * 1024 * 1024
+}
@@ -1,10 +1,84 @@
 use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SurrealEngine {
+    /// A SurrealDB server reached over WebSocket at `surreal_host`.
+    #[default]
+    Remote,
+    /// Embedded, in memory. Everything is lost on restart.
+    Memory,
+    /// Embedded, persisted in the SurrealKV directory `surreal_path`.
+    SurrealKv,
+    /// Embedded, persisted in the RocksDB directory `surreal_path`. Needs the `rocksdb` feature.
+    RocksDb,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SurrealServerConfig {
+    #[serde(default)]
+    pub surreal_engine: SurrealEngine,
     pub surreal_host: String,
+    pub surreal_path: Option<String>,
+    #[serde(default)]
     pub surreal_root_name: String,
+    #[serde(default)]
     pub surreal_root_password: String,
     pub surreal_namespace: String,
     pub surreal_database: String,
 }
+
+impl SurrealServerConfig {
+    pub fn validate(&self, errors: &mut Vec<String>) {
+        for (name, value) in [
+            ("surreal_namespace", &self.surreal_namespace),
+            ("surreal_database", &self.surreal_database),
+        ] {
+            if value.is_empty() {
+                errors.push(format!("{} must not be empty", name));
+            }
+        }
+        match self.surreal_engine {
+            SurrealEngine::Remote => {
+                for (name, value) in [
+                    ("surreal_host", &self.surreal_host),
+                    ("surreal_root_name", &self.surreal_root_name),
+                    ("surreal_root_password", &self.surreal_root_password),
+                ] {
+                    if value.is_empty() {
+                        errors.push(format!(
+                            "{} is required when surreal_engine is remote",
+                            name
+                        ));
+                    }
+                }
+            }
+            SurrealEngine::Memory => {}
+            SurrealEngine::SurrealKv | SurrealEngine::RocksDb => {
+                if self.surreal_path.as_deref().is_none_or(str::is_empty) {
+                    errors.push(
+                        "surreal_path is required when surreal_engine is surrealkv or rocksdb"
+                            .to_string(),
+                    );
+                }
+            }
+        }
+        if self.surreal_engine == SurrealEngine::RocksDb && !cfg!(feature = "rocksdb") {
+            errors.push(
+                "surreal_engine rocksdb needs the backend built with the rocksdb feature"
+                    .to_string(),
+            );
+        }
+    }
+
+    /// The endpoint `surrealdb::engine::any::connect` opens for the configured engine.
+    pub fn endpoint(&self) -> String {
+        let path = self.surreal_path.as_deref().unwrap_or_default();
+        match self.surreal_engine {
+            SurrealEngine::Remote => format!("ws://{}", self.surreal_host),
+            SurrealEngine::Memory => "mem://".to_string(),
+            SurrealEngine::SurrealKv => format!("surrealkv://{}", path),
+            SurrealEngine::RocksDb => format!("rocksdb://{}", path),
+        }
+    }
+}
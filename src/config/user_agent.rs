@@ -0,0 +1,29 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserAgentConfig {
+    /// Path of a uap-core `regexes.yaml`. The copy embedded in the binary is used when unset.
+    pub user_agent_regexes_path: Option<String>,
+    /// Number of parsed user agents kept in memory.
+    #[serde(default = "default_user_agent_cache_size")]
+    pub user_agent_cache_size: usize,
+}
+
+impl UserAgentConfig {
+    pub fn validate(&self, errors: &mut Vec<String>) {
+        if let Some(path) = &self.user_agent_regexes_path
+            && !Path::new(path).is_file()
+        {
+            errors.push(format!("user_agent_regexes_path {} does not exist", path));
+        }
+        if self.user_agent_cache_size == 0 {
+            errors.push("user_agent_cache_size must be positive".to_string());
+        }
+    }
+}
+
+fn default_user_agent_cache_size() -> usize {
+    1024
+}
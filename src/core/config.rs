@@ -1,14 +1,35 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+
 use figment::Figment;
-use figment::providers::Env;
+use figment::providers::{Env, Format, Serialized, Toml};
+use figment::value::Value;
 use serde::{Deserialize, Serialize};
 
+use crate::config::account::AccountConfig;
+use crate::config::challenge::ChallengeConfig;
+use crate::config::database::DatabaseConfig;
+use crate::config::device::DeviceConfig;
+use crate::config::geoip::GeoIpConfig;
 use crate::config::jwt::JwtConfig;
+use crate::config::log::LogConfig;
+use crate::config::metrics::MetricsConfig;
+use crate::config::migration::MigrationConfig;
+use crate::config::password_hashing::PasswordHashingConfig;
+use crate::config::password_policy::PasswordPolicyConfig;
 use crate::config::redis_server::RedisServerConfig;
+use crate::config::risk::RiskConfig;
+use crate::config::security::SecurityConfig;
+use crate::config::storage::StorageConfig;
 use crate::config::surreal_server::SurrealServerConfig;
+use crate::config::user_agent::UserAgentConfig;
 use crate::config::{
     backend_server::BackendServerConfig, frontend_server::FrontendServerConfig,
     mail_server::MailServerConfig,
 };
+use crate::core::error::config::ConfigErrorKind;
 use crate::core::error::external::ExternalError;
 use crate::core::result::AppResult;
 
@@ -25,14 +46,198 @@ pub struct AppConfig {
     #[serde(flatten)]
     pub redis_server: RedisServerConfig,
     #[serde(flatten)]
+    pub database: DatabaseConfig,
+    #[serde(flatten)]
     pub jwt_config: JwtConfig,
+    #[serde(flatten)]
+    pub storage: StorageConfig,
+    #[serde(flatten)]
+    pub account: AccountConfig,
+    #[serde(flatten)]
+    pub security: SecurityConfig,
+    #[serde(flatten)]
+    pub password_policy: PasswordPolicyConfig,
+    #[serde(flatten)]
+    pub password_hashing: PasswordHashingConfig,
+    #[serde(flatten)]
+    pub user_agent: UserAgentConfig,
+    #[serde(flatten)]
+    pub device: DeviceConfig,
+    #[serde(flatten)]
+    pub geoip: GeoIpConfig,
+    #[serde(flatten)]
+    pub risk: RiskConfig,
+    #[serde(flatten)]
+    pub challenge: ChallengeConfig,
+    #[serde(flatten)]
+    pub migration: MigrationConfig,
+    #[serde(flatten)]
+    pub log: LogConfig,
+    #[serde(flatten)]
+    pub metrics: MetricsConfig,
 }
 
+/// The live config. Services `load()` the current snapshot on every use so reloads apply to them.
+pub type SharedConfig = Arc<ArcSwap<AppConfig>>;
+
+/// Prefix of every environment variable the config is read from.
+const ENV_PREFIX: &str = "APP_";
+/// Suffix of environment variables naming a file that holds the value, e.g. `APP_JWT_SECRET_FILE`.
+const SECRET_FILE_SUFFIX: &str = "_FILE";
+
 impl AppConfig {
+    /// Loads the config from, in increasing priority:
+    /// `{APP_CONFIG_DIR}/default.toml`, `{APP_CONFIG_DIR}/{APP_PROFILE}.toml`,
+    /// `APP_*` environment variables and files named by `APP_*_FILE` variables.
+    /// `APP_CONFIG_DIR` defaults to `config`. `APP_PROFILE` has no default so the placeholder
+    /// secrets of the dev profile are only ever loaded when it's chosen explicitly.
     pub fn init() -> AppResult<Self> {
-        Ok(Figment::new()
-            .merge(Env::prefixed(""))
-            .extract()
-            .map_err(ExternalError::from)?)
+        Self::load(&profile()?)
     }
+
+    /// Same as [`AppConfig::init`] with the profile given instead of read from `APP_PROFILE`,
+    /// e.g. `AppConfig::load("test")` in tests.
+    pub fn load(profile: &str) -> AppResult<Self> {
+        let [default_file, profile_file] = toml_files(profile);
+        let mut figment = Figment::new()
+            .merge(Toml::file(default_file))
+            .merge(Toml::file(profile_file))
+            .merge(
+                Env::prefixed(ENV_PREFIX)
+                    .ignore(&["profile", "config_dir"])
+                    .filter(|key| !key.as_str().ends_with("_file")),
+            );
+        for (key, name, path) in secret_files() {
+            let contents = std::fs::read_to_string(&path)
+                .map_err(|_| ConfigErrorKind::ReadSecretFailed(path.clone(), key.clone()))?;
+            let value = contents
+                .trim_end()
+                .parse::<Value>()
+                .unwrap_or_else(|never| match never {});
+            figment = figment.merge(Serialized::default(&name, value));
+        }
+
+        let config: Self = figment.extract().map_err(ExternalError::from)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Files the config is read from, so a watcher can tell when it needs reloading.
+    pub fn files() -> Vec<PathBuf> {
+        profile()
+            .map(|profile| toml_files(&profile).to_vec())
+            .unwrap_or_default()
+            .into_iter()
+            .chain(secret_files().map(|(_, _, path)| PathBuf::from(path)))
+            .collect()
+    }
+
+    /// Loads the config again and returns a copy of `self` with the reloadable sections replaced,
+    /// along with the names of the other sections that changed and only apply after a restart.
+    /// Nothing is returned when the new config fails to load or validate.
+    pub fn reload(&self) -> AppResult<(Self, Vec<&'static str>)> {
+        let next = Self::init()?;
+        let restart_required = [
+            (
+                "backend_server",
+                changed(&self.backend_server, &next.backend_server),
+            ),
+            ("mail_server", changed(&self.mail_server, &next.mail_server)),
+            (
+                "surreal_server",
+                changed(&self.surreal_server, &next.surreal_server),
+            ),
+            (
+                "redis_server",
+                changed(&self.redis_server, &next.redis_server),
+            ),
+            ("database", changed(&self.database, &next.database)),
+            ("storage", changed(&self.storage, &next.storage)),
+            (
+                "password_policy",
+                changed(&self.password_policy, &next.password_policy),
+            ),
+            (
+                "password_hashing",
+                changed(&self.password_hashing, &next.password_hashing),
+            ),
+            ("user_agent", changed(&self.user_agent, &next.user_agent)),
+            ("geoip", changed(&self.geoip, &next.geoip)),
+            ("risk", changed(&self.risk, &next.risk)),
+            ("challenge", changed(&self.challenge, &next.challenge)),
+            ("migration", changed(&self.migration, &next.migration)),
+        ]
+        .into_iter()
+        .filter_map(|(name, changed)| changed.then_some(name))
+        .collect();
+        let reloaded = Self {
+            frontend_server: next.frontend_server,
+            jwt_config: next.jwt_config,
+            account: next.account,
+            security: next.security,
+            device: next.device,
+            log: next.log,
+            metrics: next.metrics,
+            ..self.clone()
+        };
+        Ok((reloaded, restart_required))
+    }
+
+    /// Checks every section and reports all problems at once.
+    pub fn validate(&self) -> AppResult<()> {
+        let mut errors = Vec::new();
+        self.backend_server.validate(&mut errors);
+        self.frontend_server.validate(&mut errors);
+        self.mail_server.validate(&mut errors);
+        self.surreal_server.validate(&mut errors);
+        self.redis_server.validate(&mut errors);
+        self.database.validate(&mut errors);
+        self.jwt_config.validate(&mut errors);
+        self.storage.validate(&mut errors);
+        self.account.validate(&mut errors);
+        self.password_policy.validate(&mut errors);
+        self.password_hashing.validate(&mut errors);
+        self.user_agent.validate(&mut errors);
+        self.device.validate(&mut errors);
+        self.geoip.validate(&mut errors);
+        self.risk.validate(&mut errors);
+        self.challenge.validate(&mut errors);
+        self.migration.validate(&mut errors);
+        self.log.validate(&mut errors);
+        self.metrics.validate(&mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigErrorKind::Invalid(errors).into())
+        }
+    }
+}
+
+fn profile() -> AppResult<String> {
+    std::env::var("APP_PROFILE").map_err(|_| ConfigErrorKind::ProfileMissing.into())
+}
+
+/// `{APP_CONFIG_DIR}/default.toml` and `{APP_CONFIG_DIR}/{profile}.toml`.
+fn toml_files(profile: &str) -> [PathBuf; 2] {
+    let config_dir = std::env::var("APP_CONFIG_DIR").unwrap_or_else(|_| "config".to_string());
+    let config_dir = Path::new(&config_dir);
+    [
+        config_dir.join("default.toml"),
+        config_dir.join(format!("{}.toml", profile)),
+    ]
+}
+
+/// `(variable, config key, path)` for every `APP_*_FILE` variable.
+fn secret_files() -> impl Iterator<Item = (String, String, String)> {
+    std::env::vars().filter_map(|(key, path)| {
+        let name = key
+            .strip_prefix(ENV_PREFIX)?
+            .strip_suffix(SECRET_FILE_SUFFIX)?
+            .to_lowercase();
+        Some((key, name, path))
+    })
+}
+
+fn changed<T: Serialize>(current: &T, next: &T) -> bool {
+    serde_json::to_value(current).ok() != serde_json::to_value(next).ok()
 }
@@ -0,0 +1,33 @@
+use axum::http::StatusCode;
+use thiserror::Error;
+
+use crate::core::error::error_trait::ErrorKind;
+
+#[derive(Debug, Error)]
+pub enum AvatarErrorKind {
+    #[error("Avatar file is missing")]
+    MissingAvatar,
+    #[error("Invalid avatar upload")]
+    InvalidUpload,
+    #[error("Avatar must be a PNG, JPEG or WebP image")]
+    UnsupportedImageType,
+    #[error("Avatar is too large")]
+    AvatarTooLarge,
+    #[error("Avatar image could not be decoded")]
+    InvalidImage,
+}
+
+impl ErrorKind for AvatarErrorKind {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AvatarErrorKind::MissingAvatar => StatusCode::BAD_REQUEST,
+            AvatarErrorKind::InvalidUpload => StatusCode::BAD_REQUEST,
+            AvatarErrorKind::UnsupportedImageType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            AvatarErrorKind::AvatarTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            AvatarErrorKind::InvalidImage => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
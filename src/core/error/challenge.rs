@@ -0,0 +1,27 @@
+use axum::http::StatusCode;
+use thiserror::Error;
+
+use crate::core::error::error_trait::ErrorKind;
+
+#[derive(Debug, Error)]
+pub enum ChallengeErrorKind {
+    #[error("A challenge is required, get one from /auth/challenge")]
+    ChallengeRequired,
+    #[error("The challenge is invalid or expired")]
+    InvalidChallenge,
+    #[error("Too many challenges requested, try again in a minute")]
+    TooManyChallenges,
+}
+
+impl ErrorKind for ChallengeErrorKind {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ChallengeErrorKind::ChallengeRequired => StatusCode::PRECONDITION_REQUIRED,
+            ChallengeErrorKind::InvalidChallenge => StatusCode::BAD_REQUEST,
+            ChallengeErrorKind::TooManyChallenges => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
@@ -0,0 +1,27 @@
+use axum::http::StatusCode;
+use thiserror::Error;
+
+use crate::core::error::error_trait::ErrorKind;
+
+#[derive(Debug, Error)]
+pub enum ConfigErrorKind {
+    #[error("APP_PROFILE is not set, choose \"dev\", \"test\" or \"prod\"")]
+    ProfileMissing,
+    #[error("Failed to read secret file {0} for {1}")]
+    ReadSecretFailed(String, String),
+    #[error("Invalid configuration:\n  - {}", .0.join("\n  - "))]
+    Invalid(Vec<String>),
+}
+
+impl ErrorKind for ConfigErrorKind {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ConfigErrorKind::ProfileMissing => StatusCode::INTERNAL_SERVER_ERROR,
+            ConfigErrorKind::ReadSecretFailed(_, _) => StatusCode::INTERNAL_SERVER_ERROR,
+            ConfigErrorKind::Invalid(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
@@ -0,0 +1,30 @@
+use axum::http::StatusCode;
+use thiserror::Error;
+
+use crate::core::error::error_trait::ErrorKind;
+
+#[derive(Debug, Error)]
+pub enum DataExportErrorKind {
+    #[error("Create data export failed")]
+    CreateDataExportFailed,
+    #[error("Data export not found")]
+    DataExportNotFound,
+    #[error("Data export has expired")]
+    DataExportExpired,
+    #[error("A data export was requested recently, please check your email")]
+    DataExportAlreadyRequested,
+}
+
+impl ErrorKind for DataExportErrorKind {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            DataExportErrorKind::CreateDataExportFailed => StatusCode::INTERNAL_SERVER_ERROR,
+            DataExportErrorKind::DataExportNotFound => StatusCode::NOT_FOUND,
+            DataExportErrorKind::DataExportExpired => StatusCode::GONE,
+            DataExportErrorKind::DataExportAlreadyRequested => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
@@ -0,0 +1,24 @@
+use axum::http::StatusCode;
+use thiserror::Error;
+
+use crate::core::error::error_trait::ErrorKind;
+
+#[derive(Debug, Error)]
+pub enum DatabaseErrorKind {
+    #[error("{0} did not answer in time, please try again later")]
+    Timeout(&'static str),
+    #[error("{0} is unavailable, please try again later")]
+    Unavailable(&'static str),
+}
+
+impl ErrorKind for DatabaseErrorKind {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            DatabaseErrorKind::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            DatabaseErrorKind::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
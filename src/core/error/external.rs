@@ -20,6 +20,10 @@ pub enum ExternalError {
     Resend(#[from] resend_rs::Error),
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
+    #[error(transparent)]
+    Join(#[from] tokio::task::JoinError),
 }
 
 impl ErrorKind for ExternalError {
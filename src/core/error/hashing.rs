@@ -0,0 +1,24 @@
+use axum::http::StatusCode;
+use thiserror::Error;
+
+use crate::core::error::error_trait::ErrorKind;
+
+#[derive(Debug, Error)]
+pub enum HashingErrorKind {
+    #[error("Server is busy, please try again later")]
+    Overloaded,
+    #[error("Password hashing worker stopped")]
+    WorkerStopped,
+}
+
+impl ErrorKind for HashingErrorKind {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            HashingErrorKind::Overloaded => StatusCode::SERVICE_UNAVAILABLE,
+            HashingErrorKind::WorkerStopped => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
@@ -0,0 +1,24 @@
+use axum::http::StatusCode;
+use thiserror::Error;
+
+use crate::core::error::error_trait::ErrorKind;
+
+#[derive(Debug, Error)]
+pub enum MetricsErrorKind {
+    #[error("Not found")]
+    Disabled,
+    #[error("Invalid metrics token")]
+    Unauthorized,
+}
+
+impl ErrorKind for MetricsErrorKind {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            MetricsErrorKind::Disabled => StatusCode::NOT_FOUND,
+            MetricsErrorKind::Unauthorized => StatusCode::UNAUTHORIZED,
+        }
+    }
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
@@ -0,0 +1,27 @@
+use axum::http::StatusCode;
+use thiserror::Error;
+
+use crate::core::error::error_trait::ErrorKind;
+
+#[derive(Debug, Error)]
+pub enum MigrationErrorKind {
+    #[error("Failed to read migration scripts from {0}")]
+    ReadScriptsFailed(String),
+    #[error("Migration {0} was changed after it was applied")]
+    ChecksumMismatch(String),
+    #[error("Migration {0} failed: {1}")]
+    MigrationFailed(String, String),
+}
+
+impl ErrorKind for MigrationErrorKind {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            MigrationErrorKind::ReadScriptsFailed(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            MigrationErrorKind::ChecksumMismatch(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            MigrationErrorKind::MigrationFailed(_, _) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
@@ -1,10 +1,19 @@
 pub mod access_token;
+pub mod avatar;
+pub mod challenge;
+pub mod config;
+pub mod data_export;
+pub mod database;
 pub mod device;
 pub mod email;
 pub mod error_trait;
 pub mod external;
+pub mod hashing;
+pub mod metrics;
+pub mod migration;
 pub mod other;
 pub mod refresh_token;
+pub mod storage;
 pub mod user;
 pub mod validation;
 
@@ -0,0 +1,30 @@
+use axum::http::StatusCode;
+use thiserror::Error;
+
+use crate::core::error::error_trait::ErrorKind;
+
+#[derive(Debug, Error)]
+pub enum StorageErrorKind {
+    #[error("Storage is misconfigured: {0} is required")]
+    MissingConfig(&'static str),
+    #[error("Upload object failed: {0}")]
+    UploadFailed(String),
+    #[error("Download object failed: {0}")]
+    DownloadFailed(String),
+    #[error("Delete object failed: {0}")]
+    DeleteFailed(String),
+}
+
+impl ErrorKind for StorageErrorKind {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            StorageErrorKind::MissingConfig(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            StorageErrorKind::UploadFailed(_) => StatusCode::BAD_GATEWAY,
+            StorageErrorKind::DownloadFailed(_) => StatusCode::BAD_GATEWAY,
+            StorageErrorKind::DeleteFailed(_) => StatusCode::BAD_GATEWAY,
+        }
+    }
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
@@ -13,14 +13,22 @@ pub enum UserErrorKind {
     UserNotFound,
     #[error("Wrong password")]
     WrongPassword,
+    #[error("Invalid email or password")]
+    InvalidCredentials,
     #[error("Password must be different from last password")]
     PasswordMustBeDifferentFromLastPassword,
+    #[error("Password must be different from your recent passwords")]
+    PasswordRecentlyUsed,
     #[error("Unauthorized")]
     Unauthorized,
     #[error("Failed to generate tokens")]
     TokenGenerationFailed,
     #[error("Missing user agent")]
     MissingUserAgent,
+    #[error("Profile has been modified, please reload and try again")]
+    ProfileVersionConflict,
+    #[error("This login looks suspicious and has been blocked")]
+    LoginBlocked,
 }
 
 impl ErrorKind for UserErrorKind {
@@ -30,10 +38,14 @@ impl ErrorKind for UserErrorKind {
             Self::UserAlreadyExists => StatusCode::CONFLICT,
             Self::UserNotFound => StatusCode::NOT_FOUND,
             Self::WrongPassword => StatusCode::UNAUTHORIZED,
+            Self::InvalidCredentials => StatusCode::UNAUTHORIZED,
             Self::PasswordMustBeDifferentFromLastPassword => StatusCode::UNAUTHORIZED,
+            Self::PasswordRecentlyUsed => StatusCode::BAD_REQUEST,
             Self::Unauthorized => StatusCode::UNAUTHORIZED,
             Self::TokenGenerationFailed => StatusCode::INTERNAL_SERVER_ERROR,
             Self::MissingUserAgent => StatusCode::BAD_REQUEST,
+            Self::ProfileVersionConflict => StatusCode::CONFLICT,
+            Self::LoginBlocked => StatusCode::FORBIDDEN,
         }
     }
     fn message(&self) -> String {
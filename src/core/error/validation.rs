@@ -7,6 +7,8 @@ use crate::core::error::error_trait::ErrorKind;
 pub enum ValidationErrorKind {
     #[error("{0}")]
     ValidationFailed(String),
+    #[error("This password has appeared in a data breach, please choose another one")]
+    BreachedPassword,
     #[error("Password hashing error: {0}")]
     PasswordHashingError(#[from] argon2::password_hash::Error),
 }
@@ -15,6 +17,7 @@ impl ErrorKind for ValidationErrorKind {
     fn status_code(&self) -> axum::http::StatusCode {
         match self {
             ValidationErrorKind::ValidationFailed(_) => StatusCode::BAD_REQUEST,
+            ValidationErrorKind::BreachedPassword => StatusCode::BAD_REQUEST,
             ValidationErrorKind::PasswordHashingError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
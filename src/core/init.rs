@@ -5,23 +5,52 @@ use tracing::info;
 use tracing_appender::non_blocking::WorkerGuard;
 
 use crate::{
-    constants::logo::LOGO, core::config::AppConfig, core::result::AppResult, core::state::AppState,
-    database::client::DBClient, middlewares::logger::logger, routers::api_routers,
+    constants::logo::LOGO,
+    core::config::AppConfig,
+    core::result::AppResult,
+    core::state::AppState,
+    database::{client::DBClient, surreal::migration::MigrationRunner},
+    jobs::spawn_jobs,
+    middlewares::logger::{apply_log_level, logger},
+    routers::api_routers,
+    storage::init_storage,
     utils::color::gradient_text,
+    utils::{device::UserAgentParser, geoip::GeoIpResolver, password::PasswordHasher},
 };
 
 pub async fn init_app() -> AppResult<(WorkerGuard, Router, u16)> {
-    let guard = logger();
+    let (guard, log_filter) = logger();
     dotenv().ok();
     let _ = gradient_text(LOGO);
     let config = AppConfig::init()?;
+    apply_log_level(&log_filter, &config.log);
     let db_client = DBClient::new(config.clone()).await?;
+    if config.migration.migrate_on_startup {
+        let applied = MigrationRunner::new(&config.migration)
+            .up(db_client.database.as_ref(), false)
+            .await?;
+        for name in applied {
+            info!("✅ Applied migration {}", name);
+        }
+    }
+    let storage = init_storage(&config.storage)?;
+    let password_hasher = Arc::new(PasswordHasher::new(&config.password_hashing)?);
+    let user_agent_parser = Arc::new(UserAgentParser::new(&config.user_agent)?);
+    let geoip = Arc::new(GeoIpResolver::new(&config.geoip)?);
     let port = config.backend_server.backend_port;
     info!(
         "✅ The backend server is running at http://localhost:{}",
         port
     );
-    let app_state = Arc::new(AppState::new(config, db_client));
+    let app_state = Arc::new(AppState::new(
+        config,
+        db_client,
+        storage,
+        password_hasher,
+        user_agent_parser,
+        geoip,
+    ));
+    spawn_jobs(app_state.clone(), log_filter);
     let router = api_routers(app_state.clone());
     Ok((guard, router, port))
 }
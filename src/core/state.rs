@@ -1,9 +1,13 @@
 use std::sync::Arc;
 
+use arc_swap::ArcSwap;
+
 use crate::{
-    core::config::AppConfig,
+    core::config::{AppConfig, SharedConfig},
     database::client::DBClient,
     services::Services,
+    storage::ObjectStorage,
+    utils::{device::UserAgentParser, geoip::GeoIpResolver, password::PasswordHasher},
 };
 
 #[derive(Debug)]
@@ -14,16 +18,30 @@ pub struct AppStateTemp {
 
 #[derive(Debug)]
 pub struct AppState {
-    pub config: Arc<AppConfig>,
+    pub config: SharedConfig,
     pub db_client: Arc<DBClient>,
     pub services: Services,
 }
 
 impl AppState {
-    pub fn new(config: AppConfig, db_client: DBClient) -> Self {
-        let config = Arc::new(config);
+    pub fn new(
+        config: AppConfig,
+        db_client: DBClient,
+        storage: Arc<dyn ObjectStorage>,
+        password_hasher: Arc<PasswordHasher>,
+        user_agent_parser: Arc<UserAgentParser>,
+        geoip: Arc<GeoIpResolver>,
+    ) -> Self {
+        let config = Arc::new(ArcSwap::from_pointee(config));
         let db_client = Arc::new(db_client);
-        let services = Services::new(config.clone(), db_client.clone());
+        let services = Services::new(
+            config.clone(),
+            db_client.clone(),
+            storage,
+            password_hasher,
+            user_agent_parser,
+            geoip,
+        );
         AppState {
             config,
             db_client,
@@ -0,0 +1,133 @@
+use std::{
+    future::Future,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::{
+    config::database::DatabaseConfig,
+    core::{
+        error::{database::DatabaseErrorKind, external::ExternalError},
+        result::AppResult,
+    },
+    utils::metrics::METRICS,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Queries go through.
+    Closed,
+    /// Too many queries failed in a row, they fail fast without reaching the server.
+    Open,
+    /// The open period is over and the next query probes the server.
+    HalfOpen,
+}
+
+/// Puts a deadline on every query to one server and stops sending queries for a while once
+/// it keeps timing out or dropping the connection, so requests fail fast instead of piling up.
+/// Errors the server answers with, e.g. a failed query, don't count: the server is up.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    name: &'static str,
+    query_timeout: Duration,
+    failure_threshold: u32,
+    reset_after: Duration,
+    state: Mutex<BreakerState>,
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub fn new(name: &'static str, config: &DatabaseConfig) -> Self {
+        Self {
+            name,
+            query_timeout: Duration::from_millis(config.db_query_timeout_ms),
+            failure_threshold: config.circuit_breaker_failure_threshold,
+            reset_after: Duration::from_secs(config.circuit_breaker_reset_seconds),
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    pub fn state(&self) -> CircuitState {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        match state.opened_at {
+            None => CircuitState::Closed,
+            Some(opened_at) if opened_at.elapsed() < self.reset_after => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Runs `query` with the query timeout. `is_outage` tells which of its errors mean the
+    /// server is unreachable rather than that it rejected the query.
+    pub async fn call<T, E>(
+        &self,
+        query: impl Future<Output = Result<T, E>>,
+        is_outage: impl FnOnce(&E) -> bool,
+    ) -> AppResult<T>
+    where
+        ExternalError: From<E>,
+    {
+        if self.state() == CircuitState::Open {
+            return Err(DatabaseErrorKind::Unavailable(self.name).into());
+        }
+        let started_at = Instant::now();
+        let result = tokio::time::timeout(self.query_timeout, query).await;
+        METRICS.record_db_query(self.name, started_at.elapsed());
+        match result {
+            Ok(Ok(value)) => {
+                self.record_success();
+                Ok(value)
+            }
+            Ok(Err(e)) => {
+                if is_outage(&e) {
+                    self.record_failure();
+                } else {
+                    self.record_success();
+                }
+                Err(ExternalError::from(e).into())
+            }
+            Err(_) => {
+                self.record_failure();
+                Err(DatabaseErrorKind::Timeout(self.name).into())
+            }
+        }
+    }
+
+    fn record_success(&self) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if state.opened_at.take().is_some() {
+            info!("✅ {} is reachable again, circuit closed", self.name);
+        }
+        state.consecutive_failures = 0;
+    }
+
+    fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.consecutive_failures += 1;
+        // A failed probe opens the circuit again right away.
+        if state.opened_at.is_some() || state.consecutive_failures >= self.failure_threshold {
+            if state.opened_at.is_none() {
+                warn!(
+                    "❌ {} failed {} times in a row, circuit open for {:?}",
+                    self.name, state.consecutive_failures, self.reset_after
+                );
+            }
+            state.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+/// Delay before connection attempt `attempt` (0 for the first retry): doubles from 100 ms up
+/// to `max_delay`, the same schedule Redis' connection manager uses.
+pub fn backoff_delay(attempt: usize, max_delay: Duration) -> Duration {
+    let delay = Duration::from_millis(100).saturating_mul(2u32.saturating_pow(attempt as u32));
+    delay.min(max_delay)
+}
@@ -1,21 +1,39 @@
+use std::sync::Arc;
+
+use crate::config::redis_server::CacheBackend;
 use crate::core::config::AppConfig;
 use crate::core::result::AppResult;
+use crate::database::memory::client::{MemoryCacheClient, MemoryClient};
 use crate::database::redis::client::RedisClient;
 use crate::database::surreal::client::SurrealClient;
+use crate::repositories::{CacheRepositories, Repositories};
 
 #[derive(Debug, Clone)]
 pub struct DBClient {
-    pub surreal_client: SurrealClient,
-    pub redis_client: RedisClient,
+    pub database: Arc<dyn Repositories>,
+    pub cache: Arc<dyn CacheRepositories>,
 }
 
 impl DBClient {
     pub async fn new(config: AppConfig) -> AppResult<Self> {
-        let surreal_client = SurrealClient::new(config.surreal_server).await?;
-        let redis_client = RedisClient::new(config.redis_server).await?;
+        let surreal_client = SurrealClient::new(config.surreal_server, &config.database).await?;
+        let cache: Arc<dyn CacheRepositories> = match config.redis_server.cache_backend {
+            CacheBackend::Redis => {
+                Arc::new(RedisClient::new(config.redis_server, &config.database).await?)
+            }
+            CacheBackend::Memory => Arc::new(MemoryCacheClient::default()),
+        };
         Ok(DBClient {
-            surreal_client,
-            redis_client,
+            database: Arc::new(surreal_client),
+            cache,
         })
     }
+    /// Keeps everything in process memory, so the app runs without SurrealDB and Redis.
+    /// Nothing survives a restart, it's meant for tests.
+    pub fn memory() -> Self {
+        DBClient {
+            database: Arc::new(MemoryClient::default()),
+            cache: Arc::new(MemoryCacheClient::default()),
+        }
+    }
 }
@@ -0,0 +1,29 @@
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+
+use crate::core::result::AppResult;
+use crate::database::circuit_breaker::CircuitState;
+
+/// Short-lived string values that expire on their own: caches, one-time tokens and counters.
+/// Implemented by `RedisClient` and, for single-instance deployments, by `MemoryCacheClient`.
+#[async_trait]
+pub trait EphemeralStore: Debug + Send + Sync {
+    async fn set_ex(&self, key: &str, value: &str, ttl_seconds: u64) -> AppResult<()>;
+    /// Sets the value only if the key isn't already set, returning whether it was.
+    async fn set_nx_ex(&self, key: &str, value: &str, ttl_seconds: u64) -> AppResult<bool>;
+    async fn get(&self, key: &str) -> AppResult<Option<String>>;
+    /// Returns the value and deletes it, so only one caller ever gets it.
+    async fn get_del(&self, key: &str) -> AppResult<Option<String>>;
+    async fn del(&self, key: &str) -> AppResult<()>;
+    async fn exists(&self, key: &str) -> AppResult<bool>;
+    /// Increments a counter, starting from 0. The expiry is set when the counter is created and
+    /// left alone by later increments, so it counts within a fixed window.
+    async fn incr_ex(&self, key: &str, ttl_seconds: u64) -> AppResult<u64>;
+    async fn health_check(&self) -> AppResult<bool>;
+    async fn version(&self) -> AppResult<String>;
+    /// Whether queries currently reach the store, see `CircuitBreaker`.
+    fn circuit_state(&self) -> CircuitState {
+        CircuitState::Closed
+    }
+}
@@ -0,0 +1,157 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+
+use crate::core::result::AppResult;
+use crate::database::ephemeral::EphemeralStore;
+use crate::models::{
+    audit_log::AuditLog, data_export::DataExport, device::Device, email::Email,
+    ip_blacklist::IpBlacklist, password_history::PasswordHistory,
+    script_migration::ScriptMigration, token::RefreshToken, user::User,
+};
+
+/// Stand-in for SurrealDB that keeps every table in a `Vec`.
+#[derive(Debug, Default)]
+pub struct MemoryClient {
+    tables: Mutex<MemoryTables>,
+}
+
+#[derive(Debug, Default)]
+pub struct MemoryTables {
+    pub users: Vec<User>,
+    pub devices: Vec<Device>,
+    pub emails: Vec<Email>,
+    pub refresh_tokens: Vec<RefreshToken>,
+    pub audit_log: Vec<AuditLog>,
+    pub data_exports: Vec<DataExport>,
+    pub password_history: Vec<PasswordHistory>,
+    pub ip_blacklist: Vec<IpBlacklist>,
+    pub script_migrations: Vec<ScriptMigration>,
+}
+
+impl MemoryClient {
+    /// Every query holds the lock from start to end, which makes each of them a transaction.
+    pub fn tables(&self) -> MutexGuard<'_, MemoryTables> {
+        self.tables.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// In-process replacement for Redis. Expired entries are never returned and are swept out
+/// every [`SWEEP_INTERVAL`], so the map doesn't grow with keys nobody reads again.
+/// Entries live in this process only: deployments with several instances need Redis.
+#[derive(Debug)]
+pub struct MemoryCacheClient {
+    entries: Mutex<MemoryCacheEntries>,
+}
+
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug)]
+struct MemoryCacheEntries {
+    entries: HashMap<String, MemoryCacheEntry>,
+    swept_at: Instant,
+}
+
+#[derive(Debug)]
+struct MemoryCacheEntry {
+    value: String,
+    expires_at: Instant,
+}
+
+impl Default for MemoryCacheClient {
+    fn default() -> Self {
+        Self {
+            entries: Mutex::new(MemoryCacheEntries {
+                entries: HashMap::new(),
+                swept_at: Instant::now(),
+            }),
+        }
+    }
+}
+
+impl MemoryCacheClient {
+    fn entries(&self) -> MutexGuard<'_, MemoryCacheEntries> {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        let now = Instant::now();
+        if now.duration_since(entries.swept_at) >= SWEEP_INTERVAL {
+            entries.entries.retain(|_, entry| entry.expires_at > now);
+            entries.swept_at = now;
+        }
+        entries
+    }
+}
+
+impl MemoryCacheEntries {
+    fn live(&self, key: &str) -> Option<&MemoryCacheEntry> {
+        self.entries
+            .get(key)
+            .filter(|entry| entry.expires_at > Instant::now())
+    }
+    fn insert(&mut self, key: &str, value: String, ttl_seconds: u64) {
+        self.entries.insert(
+            key.to_string(),
+            MemoryCacheEntry {
+                value,
+                expires_at: Instant::now() + Duration::from_secs(ttl_seconds),
+            },
+        );
+    }
+}
+
+#[async_trait]
+impl EphemeralStore for MemoryCacheClient {
+    async fn set_ex(&self, key: &str, value: &str, ttl_seconds: u64) -> AppResult<()> {
+        self.entries().insert(key, value.to_string(), ttl_seconds);
+        Ok(())
+    }
+    async fn set_nx_ex(&self, key: &str, value: &str, ttl_seconds: u64) -> AppResult<bool> {
+        let mut entries = self.entries();
+        if entries.live(key).is_some() {
+            return Ok(false);
+        }
+        entries.insert(key, value.to_string(), ttl_seconds);
+        Ok(true)
+    }
+    async fn get(&self, key: &str) -> AppResult<Option<String>> {
+        Ok(self.entries().live(key).map(|entry| entry.value.clone()))
+    }
+    async fn get_del(&self, key: &str) -> AppResult<Option<String>> {
+        Ok(self
+            .entries()
+            .entries
+            .remove(key)
+            .filter(|entry| entry.expires_at > Instant::now())
+            .map(|entry| entry.value))
+    }
+    async fn del(&self, key: &str) -> AppResult<()> {
+        self.entries().entries.remove(key);
+        Ok(())
+    }
+    async fn exists(&self, key: &str) -> AppResult<bool> {
+        Ok(self.entries().live(key).is_some())
+    }
+    async fn incr_ex(&self, key: &str, ttl_seconds: u64) -> AppResult<u64> {
+        let mut entries = self.entries();
+        if let Some(entry) = entries
+            .entries
+            .get_mut(key)
+            .filter(|entry| entry.expires_at > Instant::now())
+        {
+            let count = entry.value.parse::<u64>().unwrap_or(0) + 1;
+            entry.value = count.to_string();
+            return Ok(count);
+        }
+        entries.insert(key, "1".to_string(), ttl_seconds);
+        Ok(1)
+    }
+    async fn health_check(&self) -> AppResult<bool> {
+        Ok(true)
+    }
+    async fn version(&self) -> AppResult<String> {
+        Ok("memory".to_string())
+    }
+}
@@ -1,3 +1,6 @@
+pub mod circuit_breaker;
 pub mod client;
+pub mod ephemeral;
+pub mod memory;
 pub mod redis;
 pub mod surreal;
@@ -1,22 +1,143 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use redis::{
+    AsyncTypedCommands, RedisError, Script,
+    aio::{ConnectionManager, ConnectionManagerConfig},
+};
+
 use crate::{
-    config::redis_server::RedisServerConfig, core::error::external::ExternalError,
+    config::{database::DatabaseConfig, redis_server::RedisServerConfig},
+    core::error::external::ExternalError,
     core::result::AppResult,
+    database::{
+        circuit_breaker::{CircuitBreaker, CircuitState},
+        ephemeral::EphemeralStore,
+    },
 };
-use redis::aio::MultiplexedConnection;
 
-#[derive(Debug, Clone)]
+/// Increments the counter and sets its expiry in one step, so a failure in between can't leave
+/// a counter that never expires. A counter found without an expiry gets one as well.
+static INCR_EX: Lazy<Script> = Lazy::new(|| {
+    Script::new(
+        r"
+        local count = redis.call('INCR', KEYS[1])
+        if count == 1 or redis.call('TTL', KEYS[1]) == -1 then
+            redis.call('EXPIRE', KEYS[1], ARGV[1])
+        end
+        return count
+        ",
+    )
+});
+
+/// The connection manager reconnects with exponential backoff whenever the connection drops,
+/// commands sent meanwhile fail and count towards the circuit breaker.
 pub struct RedisClient {
-    pub conn: MultiplexedConnection,
+    pub conn: ConnectionManager,
+    pub breaker: CircuitBreaker,
+}
+
+impl std::fmt::Debug for RedisClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisClient")
+            .field("breaker", &self.breaker)
+            .finish_non_exhaustive()
+    }
 }
 
 impl RedisClient {
-    pub async fn new(redis_server_config: RedisServerConfig) -> AppResult<Self> {
+    pub async fn new(
+        redis_server_config: RedisServerConfig,
+        database_config: &DatabaseConfig,
+    ) -> AppResult<Self> {
         let client =
             redis::Client::open(redis_server_config.redis_address).map_err(ExternalError::from)?;
-        let conn = client
-            .get_multiplexed_async_connection()
+        let manager_config = ConnectionManagerConfig::new()
+            .set_number_of_retries(database_config.db_connect_retries)
+            .set_max_delay(database_config.db_reconnect_max_delay_ms)
+            .set_connection_timeout(Duration::from_millis(database_config.db_connect_timeout_ms))
+            .set_response_timeout(Duration::from_millis(database_config.db_query_timeout_ms));
+        let conn = ConnectionManager::new_with_config(client, manager_config)
             .await
             .map_err(ExternalError::from)?;
-        Ok(RedisClient { conn })
+        Ok(RedisClient {
+            conn,
+            breaker: CircuitBreaker::new("Redis", database_config),
+        })
+    }
+}
+
+fn is_outage(e: &RedisError) -> bool {
+    e.is_io_error() || e.is_connection_dropped() || e.is_connection_refusal() || e.is_timeout()
+}
+
+#[async_trait]
+impl EphemeralStore for RedisClient {
+    async fn set_ex(&self, key: &str, value: &str, ttl_seconds: u64) -> AppResult<()> {
+        let mut conn = self.conn.clone();
+        self.breaker
+            .call(conn.set_ex(key, value, ttl_seconds), is_outage)
+            .await
+    }
+    async fn set_nx_ex(&self, key: &str, value: &str, ttl_seconds: u64) -> AppResult<bool> {
+        let mut conn = self.conn.clone();
+        let mut set = redis::cmd("SET");
+        set.arg(key).arg(value).arg("NX").arg("EX").arg(ttl_seconds);
+        let reply: Option<String> = self
+            .breaker
+            .call(set.query_async(&mut conn), is_outage)
+            .await?;
+        Ok(reply.is_some())
+    }
+    async fn get(&self, key: &str) -> AppResult<Option<String>> {
+        let mut conn = self.conn.clone();
+        self.breaker.call(conn.get(key), is_outage).await
+    }
+    async fn get_del(&self, key: &str) -> AppResult<Option<String>> {
+        let mut conn = self.conn.clone();
+        self.breaker.call(conn.get_del(key), is_outage).await
+    }
+    async fn del(&self, key: &str) -> AppResult<()> {
+        let mut conn = self.conn.clone();
+        self.breaker.call(conn.del(key), is_outage).await?;
+        Ok(())
+    }
+    async fn exists(&self, key: &str) -> AppResult<bool> {
+        let mut conn = self.conn.clone();
+        self.breaker.call(conn.exists(key), is_outage).await
+    }
+    async fn incr_ex(&self, key: &str, ttl_seconds: u64) -> AppResult<u64> {
+        let mut conn = self.conn.clone();
+        let mut invocation = INCR_EX.key(key);
+        invocation.arg(ttl_seconds);
+        let count: i64 = self
+            .breaker
+            .call(invocation.invoke_async(&mut conn), is_outage)
+            .await?;
+        Ok(count.max(0) as u64)
+    }
+    async fn health_check(&self) -> AppResult<bool> {
+        let mut conn = self.conn.clone();
+        let response = self.breaker.call(conn.ping(), is_outage).await?;
+        Ok(response == "PONG")
+    }
+    async fn version(&self) -> AppResult<String> {
+        let mut conn = self.conn.clone();
+        let mut info = redis::cmd("INFO");
+        info.arg("server");
+        let info: String = self
+            .breaker
+            .call(info.query_async(&mut conn), is_outage)
+            .await?;
+        Ok(info
+            .lines()
+            .find_map(|line| line.strip_prefix("redis_version:"))
+            .unwrap_or("unknown")
+            .trim()
+            .to_string())
+    }
+    fn circuit_state(&self) -> CircuitState {
+        self.breaker.state()
     }
 }
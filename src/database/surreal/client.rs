@@ -1,34 +1,100 @@
+use std::{future::IntoFuture, time::Duration};
+
 use surrealdb::{
     Surreal,
-    engine::remote::ws::{Client, Ws},
+    engine::any::{self, Any},
+    error::Api,
     opt::auth::Root,
 };
+use tracing::warn;
 
 use crate::{
-    config::surreal_server::SurrealServerConfig, core::error::external::ExternalError,
+    config::{
+        database::DatabaseConfig,
+        surreal_server::{SurrealEngine, SurrealServerConfig},
+    },
+    core::error::{database::DatabaseErrorKind, external::ExternalError},
     core::result::AppResult,
+    database::circuit_breaker::{CircuitBreaker, backoff_delay},
 };
 
-#[derive(Debug, Clone)]
+/// Once connected, the SDK reconnects a dropped WebSocket by itself and replays the sign-in
+/// and `USE` on the new one. Queries sent meanwhile wait, up to the query timeout.
+#[derive(Debug)]
 pub struct SurrealClient {
-    pub client: Surreal<Client>,
+    pub client: Surreal<Any>,
+    pub breaker: CircuitBreaker,
 }
 
 impl SurrealClient {
-    pub async fn new(surreal_server_config: SurrealServerConfig) -> AppResult<Self> {
-        let db = Surreal::new::<Ws>(surreal_server_config.surreal_host)
-            .await
-            .map_err(ExternalError::from)?;
-        db.signin(Root {
-            username: &surreal_server_config.surreal_root_name,
-            password: &surreal_server_config.surreal_root_password,
+    pub async fn new(
+        surreal_server_config: SurrealServerConfig,
+        database_config: &DatabaseConfig,
+    ) -> AppResult<Self> {
+        let connect_timeout = Duration::from_millis(database_config.db_connect_timeout_ms);
+        let max_delay = Duration::from_millis(database_config.db_reconnect_max_delay_ms);
+        let mut attempt = 0;
+        let db = loop {
+            match Self::connect(&surreal_server_config, connect_timeout).await {
+                Ok(db) => break db,
+                Err(e) if attempt < database_config.db_connect_retries => {
+                    let delay = backoff_delay(attempt, max_delay);
+                    warn!(
+                        "❌ Failed to connect to SurrealDB: {}, retrying in {:?}",
+                        e, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        };
+        Ok(SurrealClient {
+            client: db,
+            breaker: CircuitBreaker::new("SurrealDB", database_config),
         })
-        .await
-        .map_err(ExternalError::from)?;
-        db.use_ns(surreal_server_config.surreal_namespace)
-            .use_db(surreal_server_config.surreal_database)
-            .await
-            .map_err(ExternalError::from)?;
-        Ok(SurrealClient { client: db })
     }
+
+    async fn connect(
+        surreal_server_config: &SurrealServerConfig,
+        connect_timeout: Duration,
+    ) -> AppResult<Surreal<Any>> {
+        let connect = async {
+            let db = any::connect(surreal_server_config.endpoint()).await?;
+            // Embedded engines run without authentication, only a server needs credentials.
+            if surreal_server_config.surreal_engine == SurrealEngine::Remote {
+                db.signin(Root {
+                    username: &surreal_server_config.surreal_root_name,
+                    password: &surreal_server_config.surreal_root_password,
+                })
+                .await?;
+            }
+            db.use_ns(&surreal_server_config.surreal_namespace)
+                .use_db(&surreal_server_config.surreal_database)
+                .await?;
+            Ok::<_, surrealdb::Error>(db)
+        };
+        match tokio::time::timeout(connect_timeout, connect).await {
+            Ok(db) => Ok(db.map_err(ExternalError::from)?),
+            Err(_) => Err(DatabaseErrorKind::Timeout("SurrealDB").into()),
+        }
+    }
+
+    /// Sends a query, or any other request built from `client`, through the circuit breaker.
+    pub async fn execute<Q, T>(&self, query: Q) -> AppResult<T>
+    where
+        Q: IntoFuture<Output = surrealdb::Result<T>>,
+    {
+        self.breaker.call(query.into_future(), is_outage).await
+    }
+}
+
+/// Errors meaning the server couldn't be reached, as opposed to it rejecting the query.
+fn is_outage(e: &surrealdb::Error) -> bool {
+    matches!(
+        e,
+        surrealdb::Error::Api(
+            Api::Ws(_) | Api::Http(_) | Api::ConnectionUninitialised | Api::InternalError(_)
+        )
+    )
 }
@@ -0,0 +1,164 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    config::migration::MigrationConfig,
+    core::{error::migration::MigrationErrorKind, result::AppResult},
+    repositories::surreal::script_migration::ScriptMigrationRepository,
+};
+
+/// A `.surql` file and the SHA-256 of its content.
+#[derive(Debug, Clone)]
+pub struct MigrationScript {
+    pub name: String,
+    pub checksum: String,
+    pub sql: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MigrationState {
+    Applied,
+    Pending,
+    /// Applied, but the file changed since.
+    Drifted,
+}
+
+impl std::fmt::Display for MigrationState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrationState::Applied => write!(f, "applied"),
+            MigrationState::Pending => write!(f, "pending"),
+            MigrationState::Drifted => write!(f, "drifted"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    pub name: String,
+    pub state: MigrationState,
+    pub executed_at: Option<DateTime<Utc>>,
+}
+
+/// Applies `schemas/*.surql` and `migrations/*.surql` the same way the `surrealdb-migrations`
+/// CLI lays them out. Schema files only hold `DEFINE ... OVERWRITE` statements and are applied
+/// on every run; migrations run once, in file name order, and are recorded in
+/// `script_migration` with their checksum.
+#[derive(Debug)]
+pub struct MigrationRunner {
+    pub root: PathBuf,
+}
+
+impl MigrationRunner {
+    pub fn new(config: &MigrationConfig) -> Self {
+        Self {
+            root: PathBuf::from(&config.migration_dir),
+        }
+    }
+
+    pub fn schema_scripts(&self) -> AppResult<Vec<MigrationScript>> {
+        read_scripts(&self.root.join("schemas"))
+    }
+
+    pub fn migration_scripts(&self) -> AppResult<Vec<MigrationScript>> {
+        read_scripts(&self.root.join("migrations"))
+    }
+
+    pub async fn status(
+        &self,
+        repository: &(impl ScriptMigrationRepository + ?Sized),
+    ) -> AppResult<Vec<MigrationStatus>> {
+        let applied = repository.find_script_migrations().await?;
+        Ok(self
+            .migration_scripts()?
+            .into_iter()
+            .map(|script| {
+                match applied.iter().find(|m| m.script_name == script.name) {
+                    Some(migration) => MigrationStatus {
+                        name: script.name,
+                        // Scripts recorded without a checksum can't be checked for drift.
+                        state: match &migration.checksum {
+                            Some(checksum) if *checksum != script.checksum => {
+                                MigrationState::Drifted
+                            }
+                            _ => MigrationState::Applied,
+                        },
+                        executed_at: Some(migration.executed_at),
+                    },
+                    None => MigrationStatus {
+                        name: script.name,
+                        state: MigrationState::Pending,
+                        executed_at: None,
+                    },
+                }
+            })
+            .collect())
+    }
+
+    /// Applies the schemas and every pending migration, returning the names of the migrations
+    /// applied. With `dry_run` nothing is written and the pending names are returned. Nothing
+    /// is applied while any applied migration has drifted.
+    pub async fn up(
+        &self,
+        repository: &(impl ScriptMigrationRepository + ?Sized),
+        dry_run: bool,
+    ) -> AppResult<Vec<String>> {
+        let status = self.status(repository).await?;
+        if let Some(drifted) = status.iter().find(|s| s.state == MigrationState::Drifted) {
+            return Err(MigrationErrorKind::ChecksumMismatch(drifted.name.clone()).into());
+        }
+        let pending = self
+            .migration_scripts()?
+            .into_iter()
+            .filter(|script| {
+                status
+                    .iter()
+                    .any(|s| s.name == script.name && s.state == MigrationState::Pending)
+            })
+            .collect::<Vec<_>>();
+        if dry_run {
+            return Ok(pending.into_iter().map(|script| script.name).collect());
+        }
+        for script in self.schema_scripts()? {
+            repository
+                .apply_definitions(&script.sql)
+                .await
+                .map_err(|e| MigrationErrorKind::MigrationFailed(script.name, e.to_string()))?;
+        }
+        let mut applied = Vec::with_capacity(pending.len());
+        for script in pending {
+            repository
+                .apply_script_migration(&script.name, &script.checksum, &script.sql)
+                .await
+                .map_err(|e| {
+                    MigrationErrorKind::MigrationFailed(script.name.clone(), e.to_string())
+                })?;
+            applied.push(script.name);
+        }
+        Ok(applied)
+    }
+}
+
+fn read_scripts(dir: &Path) -> AppResult<Vec<MigrationScript>> {
+    let read_failed = || MigrationErrorKind::ReadScriptsFailed(dir.display().to_string());
+    let mut scripts = Vec::new();
+    for entry in std::fs::read_dir(dir).map_err(|_| read_failed())? {
+        let path = entry.map_err(|_| read_failed())?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("surql") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let sql = std::fs::read_to_string(&path).map_err(|_| read_failed())?;
+        scripts.push(MigrationScript {
+            name: name.to_string(),
+            checksum: hex::encode(Sha256::digest(sql.as_bytes())),
+            sql,
+        });
+    }
+    scripts.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(scripts)
+}
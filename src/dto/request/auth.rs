@@ -1,17 +1,26 @@
 use serde::Deserialize;
 
+/// Answer to a challenge from `GET /auth/challenge`.
+#[derive(Debug, Deserialize)]
+pub struct ChallengeSolution {
+    pub challenge_id: String,
+    pub solution: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct RegisterRequest {
     pub name: String,
     pub email: String,
     pub password: String,
     pub confirm_password: String,
+    pub challenge: Option<ChallengeSolution>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct LoginRequest {
     pub email: String,
     pub password: String,
+    pub challenge: Option<ChallengeSolution>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -23,6 +32,14 @@ pub struct VerifyUserRequest {
 #[derive(Debug, Deserialize)]
 pub struct ForgetPasswordRequest {
     pub email: String,
+    pub challenge: Option<ChallengeSolution>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RestoreAccountRequest {
+    pub email: String,
+    pub password: String,
+    pub challenge: Option<ChallengeSolution>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -1,8 +1,37 @@
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer};
 
+/// `None` leaves a field unchanged, `Some(None)` (an explicit `null`) clears it.
 #[derive(Debug, Deserialize)]
 pub struct UserProfileRequest {
-    pub name: String,
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub locale: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub timezone: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub avatar_url: Option<Option<String>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Tells a field sent as `null` apart from a missing one, which `#[serde(default)]` leaves `None`.
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NotificationPreferencesRequest {
+    pub new_device: Option<bool>,
+    pub suspicious_login: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteAccountRequest {
+    pub password: String,
 }
 
 #[derive(Debug, Deserialize)]
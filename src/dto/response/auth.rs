@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 
 use crate::models::device::Device;
@@ -6,6 +7,8 @@ use crate::models::device::Device;
 pub struct LoginResponse {
     pub device: Option<Device>,
     pub need_verification: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deletion_scheduled_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize)]
@@ -0,0 +1,105 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use surrealdb::sql::Thing;
+
+use crate::models::{
+    audit_log::AuditLog,
+    device::Device,
+    email::{Email, EmailType},
+    geo_location::GeoLocation,
+    token::RefreshToken,
+    user::{AvatarUrls, NotificationPreferences, User, UserRole, UserStatus},
+};
+
+/// Everything stored about a user. Secrets such as the password hash, refresh token
+/// values and email tokens are deliberately left out.
+#[derive(Debug, Serialize)]
+pub struct DataExportArchive {
+    pub generated_at: DateTime<Utc>,
+    pub profile: ExportProfile,
+    pub devices: Vec<Device>,
+    pub sessions: Vec<ExportSession>,
+    pub audit_logs: Vec<AuditLog>,
+    pub emails: Vec<ExportEmail>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportProfile {
+    pub id: Thing,
+    pub name: String,
+    pub email: String,
+    pub role: UserRole,
+    pub is_verified: bool,
+    pub status: UserStatus,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    pub avatar_url: Option<String>,
+    pub avatar_urls: Option<AvatarUrls>,
+    pub notification_preferences: Option<NotificationPreferences>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportSession {
+    pub id: Thing,
+    pub device_id: Thing,
+    pub ip: Option<String>,
+    pub location: Option<GeoLocation>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportEmail {
+    pub id: Thing,
+    pub email_type: EmailType,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub is_used: bool,
+}
+
+impl From<User> for ExportProfile {
+    fn from(user: User) -> Self {
+        Self {
+            id: user.id,
+            name: user.name,
+            email: user.email,
+            role: user.role,
+            is_verified: user.is_verified,
+            status: user.status,
+            locale: user.locale,
+            timezone: user.timezone,
+            avatar_url: user.avatar_url,
+            avatar_urls: user.avatar_urls,
+            notification_preferences: user.notification_preferences,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+        }
+    }
+}
+
+impl From<RefreshToken> for ExportSession {
+    fn from(refresh_token: RefreshToken) -> Self {
+        Self {
+            id: refresh_token.id,
+            device_id: refresh_token.device_id,
+            ip: None,
+            location: None,
+            created_at: refresh_token.created_at,
+            expires_at: refresh_token.expires_at,
+        }
+    }
+}
+
+impl From<Email> for ExportEmail {
+    fn from(email: Email) -> Self {
+        Self {
+            id: email.id,
+            email_type: email.email_type,
+            created_at: email.created_at,
+            expires_at: email.expires_at,
+            is_used: email.is_used,
+        }
+    }
+}
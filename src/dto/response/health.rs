@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::database::circuit_breaker::CircuitState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadinessStatus {
+    Up,
+    /// Every required component is up, some optional one isn't.
+    Degraded,
+    /// A required component is down, the instance shouldn't get traffic.
+    Down,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentStatus {
+    Up,
+    Down,
+    /// Not used with the current config, e.g. Redis with `cache_backend = "memory"`.
+    Disabled,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadinessResponse {
+    pub status: ReadinessStatus,
+    pub components: Vec<ComponentHealth>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ComponentHealth {
+    pub name: &'static str,
+    pub status: ComponentStatus,
+    /// Whether the instance is down, rather than degraded, without it.
+    pub required: bool,
+    pub latency_ms: Option<u64>,
+    pub version: Option<String>,
+    pub detail: Option<String>,
+    pub circuit: Option<CircuitState>,
+    /// The most recent failure, kept after the component recovers.
+    pub last_error: Option<ComponentError>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentError {
+    pub message: String,
+    pub at: DateTime<Utc>,
+}
@@ -1,2 +1,4 @@
 pub mod auth;
+pub mod export;
+pub mod health;
 pub mod user;
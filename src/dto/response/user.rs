@@ -1,4 +1,8 @@
-use crate::models::user::{User, UserRole, UserStatus};
+use crate::models::{
+    device::Device,
+    geo_location::GeoLocation,
+    user::{AvatarUrls, User, UserRole, UserStatus},
+};
 
 use chrono::{DateTime, Utc};
 use serde::Serialize;
@@ -11,7 +15,18 @@ pub struct MeResponse {
     pub role: UserRole,
     pub is_verified: bool,
     pub status: UserStatus,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    /// The avatar to show: the large uploaded size, otherwise the linked URL.
+    pub avatar_url: Option<String>,
+    pub avatar_urls: Option<AvatarUrls>,
     pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteAccountResponse {
+    pub deletion_scheduled_at: DateTime<Utc>,
 }
 
 impl From<User> for MeResponse {
@@ -22,7 +37,58 @@ impl From<User> for MeResponse {
             role: user.role,
             is_verified: user.is_verified,
             status: user.status,
+            locale: user.locale,
+            timezone: user.timezone,
+            avatar_url: user
+                .avatar_urls
+                .as_ref()
+                .map(|avatar_urls| avatar_urls.large.clone())
+                .or(user.avatar_url),
+            avatar_urls: user.avatar_urls,
             created_at: user.created_at,
+            updated_at: user.updated_at,
         }
     }
 }
+
+#[derive(Debug, Serialize)]
+pub struct DeviceResponse {
+    pub id: Thing,
+    pub ip: String,
+    pub user_agent: String,
+    pub os: String,
+    pub device: String,
+    pub location: Option<GeoLocation>,
+    pub is_trusted: bool,
+    pub last_login_at: DateTime<Utc>,
+}
+
+impl From<Device> for DeviceResponse {
+    fn from(device: Device) -> Self {
+        Self {
+            id: device.id,
+            ip: device.ip,
+            user_agent: device.user_agent,
+            os: device.os,
+            device: device.device,
+            location: device.location,
+            is_trusted: device.is_trusted,
+            last_login_at: device.last_login_at,
+        }
+    }
+}
+
+/// A signed-in session, shown with the device it was opened from.
+#[derive(Debug, Serialize)]
+pub struct SessionResponse {
+    pub id: Thing,
+    pub device_id: Thing,
+    pub user_agent: Option<String>,
+    pub os: Option<String>,
+    pub ip: Option<String>,
+    pub location: Option<GeoLocation>,
+    /// Whether this is the session the request was made from.
+    pub is_current: bool,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
@@ -1,8 +1,8 @@
 use axum::{
     Extension, Json,
-    extract::{ConnectInfo, OriginalUri, State},
+    extract::{ConnectInfo, OriginalUri, Path, State},
     http::HeaderMap,
-    response::IntoResponse,
+    response::{Html, IntoResponse},
 };
 use axum_extra::extract::cookie::CookieJar;
 use std::{net::SocketAddr, sync::Arc};
@@ -13,9 +13,11 @@ use crate::{
     core::result::AppResult,
     core::state::AppState,
     dto::request::auth::{
-        ForgetPasswordRequest, RegisterRequest, ResetPasswordRequest, VerifyUserRequest,
+        ForgetPasswordRequest, RegisterRequest, ResetPasswordRequest, RestoreAccountRequest,
+        VerifyUserRequest,
     },
     models::user::User,
+    templates::report_login_html::REPORT_LOGIN_HTML,
 };
 
 #[instrument(skip(app_state))]
@@ -25,7 +27,7 @@ pub async fn register(
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     Json(payload): Json<RegisterRequest>,
 ) -> AppResult<impl IntoResponse> {
-    app_state.services.auth.register(payload).await
+    app_state.services.auth.register(addr, payload).await
 }
 
 #[instrument(skip(app_state, headers, jar))]
@@ -37,7 +39,11 @@ pub async fn login(
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     Json(payload): Json<LoginRequest>,
 ) -> AppResult<impl IntoResponse> {
-    app_state.services.auth.login(headers, jar, payload).await
+    app_state
+        .services
+        .auth
+        .login(headers, jar, addr, payload)
+        .await
 }
 
 #[instrument(skip(app_state, jar, user))]
@@ -49,26 +55,28 @@ pub async fn logout(
     app_state.services.auth.logout(jar, user).await
 }
 
-#[instrument(skip(app_state, headers))]
+#[instrument(skip(app_state, headers, jar))]
 pub async fn verify_email(
     State(app_state): State<Arc<AppState>>,
     headers: HeaderMap,
+    jar: CookieJar,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     Json(payload): Json<VerifyUserRequest>,
 ) -> AppResult<impl IntoResponse> {
     app_state
         .services
         .auth
-        .verify_email(headers, addr, payload)
+        .verify_email(headers, jar, addr, payload)
         .await
 }
 
 #[instrument(skip(app_state))]
 pub async fn forget_password(
     State(app_state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
     Json(payload): Json<ForgetPasswordRequest>,
 ) -> AppResult<impl IntoResponse> {
-    app_state.services.auth.forget_password(payload).await
+    app_state.services.auth.forget_password(addr, payload).await
 }
 
 #[instrument(skip(app_state))]
@@ -79,4 +87,38 @@ pub async fn reset_password(
     app_state.services.auth.reset_password(payload).await
 }
 
+#[instrument(skip(app_state, payload))]
+pub async fn restore_account(
+    State(app_state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Json(payload): Json<RestoreAccountRequest>,
+) -> AppResult<impl IntoResponse> {
+    app_state.services.auth.restore_account(addr, payload).await
+}
+
+pub async fn confirm_report_login() -> impl IntoResponse {
+    Html(REPORT_LOGIN_HTML)
+}
+
+#[instrument(skip(app_state))]
+pub async fn report_login(
+    State(app_state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+) -> AppResult<impl IntoResponse> {
+    app_state.services.auth.report_login(token).await
+}
+
+#[instrument(skip(app_state))]
+pub async fn issue_challenge(
+    State(app_state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+) -> AppResult<impl IntoResponse> {
+    app_state
+        .services
+        .auth
+        .challenge
+        .issue_challenge(&addr.ip().to_string())
+        .await
+}
+
 pub async fn refresh_token() {}
@@ -3,6 +3,10 @@ use std::sync::Arc;
 use crate::{core::result::AppResult, core::state::AppState};
 use axum::{extract::State, response::IntoResponse};
 
-pub async fn health_check(State(app_state): State<Arc<AppState>>) -> AppResult<impl IntoResponse> {
-    app_state.services.health.health_check().await
+pub async fn liveness(State(app_state): State<Arc<AppState>>) -> AppResult<impl IntoResponse> {
+    app_state.services.health.liveness().await
+}
+
+pub async fn readiness(State(app_state): State<Arc<AppState>>) -> AppResult<impl IntoResponse> {
+    app_state.services.health.readiness().await
 }
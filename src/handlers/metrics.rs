@@ -0,0 +1,11 @@
+use std::sync::Arc;
+
+use crate::{core::result::AppResult, core::state::AppState};
+use axum::{extract::State, http::HeaderMap, response::IntoResponse};
+
+pub async fn metrics(
+    State(app_state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> AppResult<impl IntoResponse> {
+    app_state.services.metrics.render(&headers).await
+}
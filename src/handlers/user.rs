@@ -1,11 +1,19 @@
 use std::sync::Arc;
 
-use axum::{Extension, Json, extract::State, response::IntoResponse};
+use axum::{
+    Extension, Json,
+    extract::{Multipart, Path, State},
+    response::IntoResponse,
+};
+use axum_extra::extract::cookie::CookieJar;
 use tracing::instrument;
 
 use crate::{
     core::{result::AppResult, state::AppState},
-    dto::request::user::ChangePasswordRequest,
+    dto::request::user::{
+        ChangePasswordRequest, DeleteAccountRequest, NotificationPreferencesRequest,
+        UserProfileRequest,
+    },
     models::user::User,
 };
 
@@ -17,6 +25,40 @@ pub async fn get_me(
     app_state.services.user.get_me(user).await
 }
 
+#[instrument(skip(app_state))]
+pub async fn update_me(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(payload): Json<UserProfileRequest>,
+) -> AppResult<impl IntoResponse> {
+    app_state.services.user.update_me(user, payload).await
+}
+
+#[instrument(skip(app_state))]
+pub async fn get_notification_preferences(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> AppResult<impl IntoResponse> {
+    app_state
+        .services
+        .user
+        .get_notification_preferences(user)
+        .await
+}
+
+#[instrument(skip(app_state))]
+pub async fn update_notification_preferences(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(payload): Json<NotificationPreferencesRequest>,
+) -> AppResult<impl IntoResponse> {
+    app_state
+        .services
+        .user
+        .update_notification_preferences(user, payload)
+        .await
+}
+
 #[instrument(skip(app_state))]
 pub async fn change_password(
     State(app_state): State<Arc<AppState>>,
@@ -26,6 +68,54 @@ pub async fn change_password(
     app_state.services.user.change_password(user, payload).await
 }
 
-pub async fn list_devices() {}
+#[instrument(skip(app_state, multipart))]
+pub async fn upload_avatar(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    multipart: Multipart,
+) -> AppResult<impl IntoResponse> {
+    app_state.services.user.upload_avatar(user, multipart).await
+}
+
+#[instrument(skip(app_state))]
+pub async fn request_data_export(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> AppResult<impl IntoResponse> {
+    app_state.services.user.request_data_export(user).await
+}
+
+#[instrument(skip(app_state, token))]
+pub async fn download_data_export(
+    State(app_state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+) -> AppResult<impl IntoResponse> {
+    app_state.services.user.download_data_export(token).await
+}
+
+#[instrument(skip(app_state))]
+pub async fn list_devices(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> AppResult<impl IntoResponse> {
+    app_state.services.user.list_devices(user).await
+}
+
+#[instrument(skip(app_state, jar))]
+pub async fn list_sessions(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    jar: CookieJar,
+) -> AppResult<impl IntoResponse> {
+    app_state.services.user.list_sessions(user, jar).await
+}
 
-pub async fn delete_account() {}
+#[instrument(skip(app_state, jar, payload))]
+pub async fn delete_account(
+    State(app_state): State<Arc<AppState>>,
+    jar: CookieJar,
+    Extension(user): Extension<User>,
+    Json(payload): Json<DeleteAccountRequest>,
+) -> AppResult<impl IntoResponse> {
+    app_state.services.user.delete_me(jar, user, payload).await
+}
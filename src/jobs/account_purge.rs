@@ -0,0 +1,26 @@
+use std::{sync::Arc, time::Duration};
+
+use tracing::{error, info};
+
+use crate::core::state::AppState;
+
+pub async fn account_purge(app_state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(Duration::from_secs(
+        app_state
+            .config
+            .load()
+            .account
+            .account_purge_interval_seconds,
+    ));
+    loop {
+        interval.tick().await;
+        match app_state.services.user.purge_deleted_accounts().await {
+            Ok(0) => {}
+            Ok(count) => info!(
+                "✅ Purged {} accounts past their deletion grace period",
+                count
+            ),
+            Err(e) => error!("❌ Failed to purge deleted accounts: {}", e),
+        }
+    }
+}
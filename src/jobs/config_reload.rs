@@ -0,0 +1,87 @@
+use std::{sync::Arc, time::Duration, time::SystemTime};
+
+use tracing::{error, info, warn};
+
+use crate::{
+    core::{config::AppConfig, state::AppState},
+    middlewares::logger::{LogFilterHandle, apply_log_level},
+};
+
+/// How often the config files are checked for changes.
+const CONFIG_WATCH_INTERVAL_SECONDS: u64 = 5;
+
+/// Reloads the config on SIGHUP or when one of its files changes.
+pub async fn config_reload(app_state: Arc<AppState>, log_filter: LogFilterHandle) {
+    let mut interval = tokio::time::interval(Duration::from_secs(CONFIG_WATCH_INTERVAL_SECONDS));
+    let mut hangup = hangup_signal();
+    let mut modified = files_modified();
+    // The first tick completes immediately and the config was just loaded.
+    interval.tick().await;
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                let current = files_modified();
+                if current == modified {
+                    continue;
+                }
+                modified = current;
+                info!("Config files changed, reloading");
+            }
+            _ = next_hangup(&mut hangup) => info!("SIGHUP received, reloading config"),
+        }
+        let current = app_state.config.load_full();
+        match current.reload() {
+            Ok((next, restart_required)) => {
+                if !restart_required.is_empty() {
+                    warn!(
+                        "⚠️ Changes to {} only apply after a restart",
+                        restart_required.join(", ")
+                    );
+                }
+                apply_log_level(&log_filter, &next.log);
+                app_state.config.store(Arc::new(next));
+                info!("✅ Reloaded config");
+            }
+            Err(e) => error!(
+                "❌ Failed to reload config, keeping the previous one: {}",
+                e
+            ),
+        }
+    }
+}
+
+fn files_modified() -> Vec<Option<SystemTime>> {
+    AppConfig::files()
+        .iter()
+        .map(|path| path.metadata().and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+#[cfg(unix)]
+type Hangup = Option<tokio::signal::unix::Signal>;
+#[cfg(not(unix))]
+type Hangup = ();
+
+#[cfg(unix)]
+fn hangup_signal() -> Hangup {
+    use tokio::signal::unix::{SignalKind, signal};
+    signal(SignalKind::hangup())
+        .inspect_err(|e| error!("❌ Failed to listen for SIGHUP: {}", e))
+        .ok()
+}
+#[cfg(not(unix))]
+fn hangup_signal() -> Hangup {}
+
+#[cfg(unix)]
+async fn next_hangup(hangup: &mut Hangup) {
+    match hangup {
+        Some(signal) => {
+            signal.recv().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+#[cfg(not(unix))]
+async fn next_hangup(_: &mut Hangup) {
+    std::future::pending().await
+}
@@ -0,0 +1,20 @@
+use std::{sync::Arc, time::Duration};
+
+use tracing::{error, info};
+
+use crate::core::state::AppState;
+
+const DATA_EXPORT_CLEANUP_INTERVAL_SECONDS: u64 = 3600;
+
+pub async fn data_export_cleanup(app_state: Arc<AppState>) {
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(DATA_EXPORT_CLEANUP_INTERVAL_SECONDS));
+    loop {
+        interval.tick().await;
+        match app_state.services.user.cleanup_expired_data_exports().await {
+            Ok(0) => {}
+            Ok(count) => info!("✅ Removed {} expired data exports", count),
+            Err(e) => error!("❌ Failed to remove expired data exports: {}", e),
+        }
+    }
+}
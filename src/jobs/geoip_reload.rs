@@ -0,0 +1,30 @@
+use std::{sync::Arc, time::Duration};
+
+use tracing::{error, info};
+
+use crate::core::state::AppState;
+
+pub async fn geoip_reload(app_state: Arc<AppState>) {
+    let geoip = app_state.services.auth.geoip.clone();
+    if !geoip.is_enabled() {
+        return;
+    }
+    let mut interval = tokio::time::interval(Duration::from_secs(
+        app_state
+            .config
+            .load()
+            .geoip
+            .geoip_reload_interval_seconds
+            .max(1),
+    ));
+    // The first tick completes immediately and the databases were just opened.
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        match geoip.reload_if_changed() {
+            Ok(0) => {}
+            Ok(count) => info!("✅ Reloaded {} GeoIP databases", count),
+            Err(e) => error!("❌ Failed to reload GeoIP databases: {}", e),
+        }
+    }
+}
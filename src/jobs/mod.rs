@@ -0,0 +1,22 @@
+pub mod account_purge;
+pub mod config_reload;
+pub mod data_export_cleanup;
+pub mod geoip_reload;
+
+use std::sync::Arc;
+
+use crate::{
+    core::state::AppState,
+    jobs::{
+        account_purge::account_purge, config_reload::config_reload,
+        data_export_cleanup::data_export_cleanup, geoip_reload::geoip_reload,
+    },
+    middlewares::logger::LogFilterHandle,
+};
+
+pub fn spawn_jobs(app_state: Arc<AppState>, log_filter: LogFilterHandle) {
+    tokio::spawn(config_reload(app_state.clone(), log_filter));
+    tokio::spawn(account_purge(app_state.clone()));
+    tokio::spawn(data_export_cleanup(app_state.clone()));
+    tokio::spawn(geoip_reload(app_state));
+}
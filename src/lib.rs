@@ -8,17 +8,20 @@ use crate::{
     utils::shutdown::shutdown_signal,
 };
 
+pub mod cli;
 pub mod config;
 pub mod constants;
 pub mod core;
 pub mod database;
 pub mod dto;
 pub mod handlers;
+pub mod jobs;
 pub mod middlewares;
 pub mod models;
 pub mod repositories;
 pub mod routers;
 pub mod services;
+pub mod storage;
 pub mod templates;
 pub mod utils;
 pub mod validation;
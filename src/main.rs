@@ -1,6 +1,10 @@
-use backend::{core::result::AppResult, run};
+use backend::{
+    cli::{Cli, execute},
+    core::result::AppResult,
+};
+use clap::Parser;
 
 #[tokio::main]
 async fn main() -> AppResult<()> {
-    run().await
+    execute(Cli::parse()).await
 }
@@ -10,8 +10,7 @@ use axum::{
 use crate::{
     core::error::{access_token::AccessTokenErrorKind, user::UserErrorKind},
     core::{result::AppResult, state::AppState},
-    models::user::{User, UserRole},
-    repositories::{redis::auth::AuthCacheRepository, surreal::auth::AuthRepository},
+    models::user::{User, UserRole, UserStatus},
     utils::token::validate_access_token,
 };
 
@@ -33,30 +32,29 @@ pub async fn auth(
     };
     let user_id = match validate_access_token(
         access_token,
-        app_state.config.jwt_config.jwt_secret.as_bytes(),
+        app_state.config.load().jwt_config.jwt_secret.as_bytes(),
     ) {
         Ok(user_id) => user_id,
         Err(_) => {
             return Err(AccessTokenErrorKind::InvalidAccessToken.into());
         }
     };
-    let cached_user = app_state.db_client.redis_client.get_user(&user_id).await?;
+    let cached_user = app_state.db_client.cache.get_user(&user_id).await?;
     let user = if let Some(user) = cached_user {
         user
     } else {
         let db_user = app_state
             .db_client
-            .surreal_client
+            .database
             .find_user_by_id(user_id.clone())
             .await?
             .ok_or(UserErrorKind::UserNotFound)?;
-        app_state
-            .db_client
-            .redis_client
-            .set_user(&db_user, 900)
-            .await?;
+        app_state.db_client.cache.set_user(&db_user, 900).await?;
         db_user
     };
+    if user.status == UserStatus::Deleted {
+        return Err(UserErrorKind::Unauthorized.into());
+    }
 
     req.extensions_mut().insert(user);
     Ok(next.run(req).await)
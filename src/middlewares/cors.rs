@@ -1,13 +1,24 @@
 use axum::http::{
-    HeaderValue, Method,
+    Method,
     header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE},
 };
-use tower_http::cors::CorsLayer;
+use tower_http::cors::{AllowOrigin, CorsLayer};
 
-pub fn cors(frontend_address: String) -> CorsLayer {
+use crate::core::config::SharedConfig;
+
+/// Allows the configured frontend, read on every request so a config reload applies at once.
+pub fn cors(config: SharedConfig) -> CorsLayer {
     CorsLayer::new()
-        .allow_origin(frontend_address.parse::<HeaderValue>().unwrap())
+        .allow_origin(AllowOrigin::predicate(move |origin, _| {
+            config.load().frontend_server.frontend_address.as_bytes() == origin.as_bytes()
+        }))
         .allow_credentials(true)
         .allow_headers([AUTHORIZATION, CONTENT_TYPE, ACCEPT])
-        .allow_methods([Method::GET, Method::POST, Method::PUT])
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+        ])
 }
@@ -1,7 +1,15 @@
+use tracing::error;
 use tracing_appender::non_blocking::WorkerGuard;
-use tracing_subscriber::{EnvFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{
+    EnvFilter, Registry, fmt, layer::SubscriberExt, reload, util::SubscriberInitExt,
+};
 
-pub fn logger() -> WorkerGuard {
+use crate::config::log::LogConfig;
+
+/// Swaps the level filter of the running subscriber.
+pub type LogFilterHandle = reload::Handle<EnvFilter, Registry>;
+
+pub fn logger() -> (WorkerGuard, LogFilterHandle) {
     let file_appender = tracing_appender::rolling::hourly("logs", "backend.log");
     let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
     let file_layer = fmt::layer()
@@ -9,13 +17,28 @@ pub fn logger() -> WorkerGuard {
         .with_line_number(false)
         .with_writer(non_blocking);
     let console_layer = fmt::layer().with_file(true).with_line_number(false);
-    let filter_layer = EnvFilter::try_from_default_env()
-        .or_else(|_| EnvFilter::try_new("info"))
-        .unwrap();
+    let (filter_layer, log_filter) = reload::Layer::new(default_filter());
     tracing_subscriber::registry()
         .with(filter_layer)
         .with(file_layer)
         .with(console_layer)
         .init();
-    guard
+    (guard, log_filter)
+}
+
+/// Applies `log_level`, falling back to `RUST_LOG` or `info` when it is unset.
+pub fn apply_log_level(log_filter: &LogFilterHandle, config: &LogConfig) {
+    let filter = match &config.log_level {
+        Some(level) => EnvFilter::try_new(level).unwrap_or_else(|_| default_filter()),
+        None => default_filter(),
+    };
+    if let Err(e) = log_filter.reload(filter) {
+        error!("❌ Failed to apply the log level: {}", e);
+    }
+}
+
+fn default_filter() -> EnvFilter {
+    EnvFilter::try_from_default_env()
+        .or_else(|_| EnvFilter::try_new("info"))
+        .unwrap()
 }
@@ -0,0 +1,35 @@
+use std::time::Instant;
+
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::utils::metrics::METRICS;
+
+/// Counts every request and its latency by method, route template and status.
+pub async fn track_metrics(req: Request, next: Next) -> Response {
+    // Any token is a valid method, only the standard ones get their own series.
+    let method = match req.method().as_str() {
+        method @ ("GET" | "HEAD" | "POST" | "PUT" | "DELETE" | "CONNECT" | "OPTIONS" | "TRACE"
+        | "PATCH") => method,
+        _ => "OTHER",
+    }
+    .to_string();
+    // Requests matching no route are grouped, so probing random paths can't grow the series.
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let started_at = Instant::now();
+    let response = next.run(req).await;
+    METRICS.record_http_request(
+        &method,
+        &route,
+        response.status().as_u16(),
+        started_at.elapsed(),
+    );
+    response
+}
@@ -1,3 +1,4 @@
 pub mod auth;
 pub mod cors;
 pub mod logger;
+pub mod metrics;
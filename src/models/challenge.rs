@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChallengeKind {
+    ProofOfWork,
+    Captcha,
+}
+
+/// A challenge handed out to a client and kept in Redis until it is answered or expires.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Challenge {
+    pub id: String,
+    pub kind: ChallengeKind,
+    /// Proof of work: find a `solution` so that `SHA-256(nonce + solution)` starts with
+    /// `difficulty` zero bits.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<u32>,
+    /// CAPTCHA: site key of the widget whose token is the `solution`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub site_key: Option<String>,
+    pub expires_at: DateTime<Utc>,
+}
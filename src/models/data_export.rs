@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataExport {
+    pub id: Thing,
+    pub user_id: Thing,
+    pub token: String,
+    /// The JSON archive. It is kept in the database rather than the object storage, whose
+    /// avatars are public, so it can only be fetched through the download API.
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
@@ -2,6 +2,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use surrealdb::sql::Thing;
 
+use crate::models::geo_location::GeoLocation;
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Device {
     pub id: Thing,
@@ -10,6 +12,28 @@ pub struct Device {
     pub user_agent: String,
     pub os: String,
     pub device: String,
+    pub browser_family: Option<String>,
+    pub browser_major: Option<String>,
+    pub os_family: Option<String>,
+    pub location: Option<GeoLocation>,
     pub is_trusted: bool,
     pub last_login_at: DateTime<Utc>,
 }
+
+/// Why a login was reported to its owner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoginAlertReason {
+    NewDevice,
+    NewIpRange,
+    NewCountry,
+}
+
+impl std::fmt::Display for LoginAlertReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoginAlertReason::NewDevice => write!(f, "new device"),
+            LoginAlertReason::NewIpRange => write!(f, "new IP range"),
+            LoginAlertReason::NewCountry => write!(f, "new country"),
+        }
+    }
+}
@@ -13,11 +13,12 @@ pub struct Email {
     pub is_used: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub enum EmailType {
     Verification,
     PasswordReset,
+    LoginAlert,
 }
 
 impl std::fmt::Display for EmailType {
@@ -25,6 +26,7 @@ impl std::fmt::Display for EmailType {
         match self {
             EmailType::Verification => write!(f, "verification"),
             EmailType::PasswordReset => write!(f, "password_reset"),
+            EmailType::LoginAlert => write!(f, "login_alert"),
         }
     }
 }
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+/// Where an IP address is located according to the local GeoIP databases.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GeoLocation {
+    /// ISO 3166-1 alpha-2 country code, e.g. `DE`.
+    pub country: Option<String>,
+    pub city: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub asn: Option<u32>,
+    pub as_organization: Option<String>,
+}
+
+impl GeoLocation {
+    /// Human readable place, e.g. `Berlin, DE`.
+    pub fn label(&self) -> Option<String> {
+        match (&self.city, &self.country) {
+            (Some(city), Some(country)) => Some(format!("{}, {}", city, country)),
+            (None, Some(country)) => Some(country.clone()),
+            (Some(city), None) => Some(city.clone()),
+            (None, None) => None,
+        }
+    }
+    /// Great-circle distance to another location in kilometres, when both have coordinates.
+    pub fn distance_km(&self, other: &GeoLocation) -> Option<f64> {
+        const EARTH_RADIUS_KM: f64 = 6371.0;
+        let (lat1, lon1) = (self.latitude?.to_radians(), self.longitude?.to_radians());
+        let (lat2, lon2) = (other.latitude?.to_radians(), other.longitude?.to_radians());
+        let a = ((lat2 - lat1) / 2.0).sin().powi(2)
+            + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+        Some(2.0 * EARTH_RADIUS_KM * a.sqrt().asin())
+    }
+}
@@ -1,6 +1,11 @@
 pub mod audit_log;
+pub mod challenge;
+pub mod data_export;
 pub mod device;
 pub mod email;
+pub mod geo_location;
 pub mod ip_blacklist;
+pub mod password_history;
+pub mod script_migration;
 pub mod token;
 pub mod user;
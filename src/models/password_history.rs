@@ -0,0 +1,11 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordHistory {
+    pub id: Thing,
+    pub user_id: Thing,
+    pub password: String,
+    pub created_at: DateTime<Utc>,
+}
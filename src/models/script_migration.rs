@@ -0,0 +1,12 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptMigration {
+    pub id: Thing,
+    pub script_name: String,
+    pub executed_at: DateTime<Utc>,
+    /// Missing for scripts recorded by the `surrealdb-migrations` CLI.
+    pub checksum: Option<String>,
+}
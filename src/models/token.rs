@@ -13,6 +13,7 @@ pub struct TokenClaims {
 pub struct RefreshToken {
     pub id: Thing,
     pub user_id: Thing,
+    pub device_id: Thing,
     pub token_value: String,
     pub created_at: DateTime<Utc>,
     pub expires_at: DateTime<Utc>,
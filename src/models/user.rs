@@ -9,13 +9,44 @@ pub struct User {
     pub email: String,
     pub password: String,
     pub role: UserRole,
-    pub salt: String,
     pub is_verified: bool,
     pub status: UserStatus,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    /// An avatar linked with the profile API. Setting it drops the uploaded one and the other
+    /// way around, so at most one of `avatar_url` and `avatar_urls` is set.
+    pub avatar_url: Option<String>,
+    /// The sizes of an uploaded avatar.
+    pub avatar_urls: Option<AvatarUrls>,
+    pub deletion_scheduled_at: Option<DateTime<Utc>>,
+    pub notification_preferences: Option<NotificationPreferences>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AvatarUrls {
+    pub small: String,
+    pub medium: String,
+    pub large: String,
+}
+
+/// Which security emails the user wants. Users who never changed them get everything.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationPreferences {
+    pub new_device: bool,
+    pub suspicious_login: bool,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            new_device: true,
+            suspicious_login: true,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 pub enum UserRole {
@@ -23,7 +54,7 @@ pub enum UserRole {
     User,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 pub enum UserStatus {
     Active,
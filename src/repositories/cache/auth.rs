@@ -0,0 +1,155 @@
+use async_trait::async_trait;
+use surrealdb::sql::Thing;
+
+use crate::{
+    core::{error::external::ExternalError, result::AppResult},
+    database::ephemeral::EphemeralStore,
+    models::{challenge::Challenge, email::EmailType, user::User},
+};
+
+#[async_trait]
+pub trait AuthCacheRepository {
+    async fn set_user(&self, user: &User, ttl_seconds: u64) -> AppResult<()>;
+    async fn get_user(&self, user_id: &Thing) -> AppResult<Option<User>>;
+    async fn delete_user(&self, user_id: &Thing) -> AppResult<()>;
+    async fn set_email_token(
+        &self,
+        email_token_type: EmailType,
+        email_token: &str,
+        user_id: &Thing,
+        ttl_seconds: u64,
+    ) -> AppResult<()>;
+    async fn use_email_token(
+        &self,
+        email_token_type: EmailType,
+        email_token: &str,
+    ) -> AppResult<Option<Thing>>;
+    async fn add_jti_to_blacklist(&self, jti: &str, ttl_seconds: u64) -> AppResult<()>;
+    async fn is_jti_in_blacklist(&self, jti: &str) -> AppResult<bool>;
+    /// Failures are counted per submitted email, whether or not it belongs to an account, so
+    /// that the challenge they trigger doesn't reveal which emails are registered.
+    async fn record_login_failure(&self, email: &str, window_seconds: u64) -> AppResult<u64>;
+    async fn get_login_failures(&self, email: &str) -> AppResult<u64>;
+    async fn clear_login_failures(&self, email: &str) -> AppResult<()>;
+    async fn set_step_up_passed(
+        &self,
+        user_id: &Thing,
+        device_id: &Thing,
+        ttl_seconds: u64,
+    ) -> AppResult<()>;
+    async fn use_step_up_passed(&self, user_id: &Thing, device_id: &Thing) -> AppResult<bool>;
+    async fn set_challenge(&self, challenge: &Challenge, ttl_seconds: u64) -> AppResult<()>;
+    /// Counts the challenges issued to `ip` within `window_seconds`.
+    async fn record_challenge_issued(&self, ip: &str, window_seconds: u64) -> AppResult<u64>;
+    /// Returns `false` when the user already asked for a data export within `ttl_seconds`.
+    async fn claim_data_export(&self, user_id: &Thing, ttl_seconds: u64) -> AppResult<bool>;
+    async fn release_data_export(&self, user_id: &Thing) -> AppResult<()>;
+    async fn take_challenge(&self, challenge_id: &str) -> AppResult<Option<Challenge>>;
+}
+
+#[async_trait]
+impl<T> AuthCacheRepository for T
+where
+    T: EphemeralStore + ?Sized,
+{
+    async fn set_user(&self, user: &User, ttl_seconds: u64) -> AppResult<()> {
+        let key = format!("user:{}", user.id);
+        let user_json = serde_json::to_string(user).map_err(ExternalError::from)?;
+        self.set_ex(&key, &user_json, ttl_seconds).await
+    }
+    async fn get_user(&self, user_id: &Thing) -> AppResult<Option<User>> {
+        let key = format!("user:{}", user_id);
+        match self.get(&key).await? {
+            Some(json) => {
+                let user: User = serde_json::from_str(&json).map_err(ExternalError::from)?;
+                Ok(Some(user))
+            }
+            None => Ok(None),
+        }
+    }
+    async fn delete_user(&self, user_id: &Thing) -> AppResult<()> {
+        let key = format!("user:{}", user_id);
+        self.del(&key).await
+    }
+    async fn set_email_token(
+        &self,
+        email_token_type: EmailType,
+        email_token: &str,
+        user_id: &Thing,
+        ttl_seconds: u64,
+    ) -> AppResult<()> {
+        let key = format!("temp_token:{}:{}", email_token_type, email_token);
+        self.set_ex(&key, &user_id.to_string(), ttl_seconds).await
+    }
+    async fn use_email_token(
+        &self,
+        email_token_type: EmailType,
+        email_token: &str,
+    ) -> AppResult<Option<Thing>> {
+        let key = format!("temp_token:{}:{}", email_token_type, email_token);
+        let user_id_str = self.get_del(&key).await?;
+        Ok(user_id_str.and_then(|s| s.parse::<Thing>().ok()))
+    }
+    async fn add_jti_to_blacklist(&self, jti: &str, ttl_seconds: u64) -> AppResult<()> {
+        let key = format!("blacklist:jti:{}", jti);
+        self.set_ex(&key, "1", ttl_seconds).await
+    }
+    async fn is_jti_in_blacklist(&self, jti: &str) -> AppResult<bool> {
+        let key = format!("blacklist:jti:{}", jti);
+        self.exists(&key).await
+    }
+    async fn record_login_failure(&self, email: &str, window_seconds: u64) -> AppResult<u64> {
+        self.incr_ex(&login_failures_key(email), window_seconds)
+            .await
+    }
+    async fn get_login_failures(&self, email: &str) -> AppResult<u64> {
+        let failures = self.get(&login_failures_key(email)).await?;
+        Ok(failures.and_then(|f| f.parse().ok()).unwrap_or(0))
+    }
+    async fn clear_login_failures(&self, email: &str) -> AppResult<()> {
+        self.del(&login_failures_key(email)).await
+    }
+    async fn set_step_up_passed(
+        &self,
+        user_id: &Thing,
+        device_id: &Thing,
+        ttl_seconds: u64,
+    ) -> AppResult<()> {
+        let key = format!("step_up:{}:{}", user_id, device_id);
+        self.set_ex(&key, "1", ttl_seconds).await
+    }
+    async fn use_step_up_passed(&self, user_id: &Thing, device_id: &Thing) -> AppResult<bool> {
+        let key = format!("step_up:{}:{}", user_id, device_id);
+        Ok(self.get_del(&key).await?.is_some())
+    }
+    async fn claim_data_export(&self, user_id: &Thing, ttl_seconds: u64) -> AppResult<bool> {
+        let key = format!("data_export:{}", user_id);
+        self.set_nx_ex(&key, "1", ttl_seconds).await
+    }
+    async fn release_data_export(&self, user_id: &Thing) -> AppResult<()> {
+        let key = format!("data_export:{}", user_id);
+        self.del(&key).await
+    }
+    async fn set_challenge(&self, challenge: &Challenge, ttl_seconds: u64) -> AppResult<()> {
+        let key = format!("challenge:{}", challenge.id);
+        let challenge_json = serde_json::to_string(challenge).map_err(ExternalError::from)?;
+        self.set_ex(&key, &challenge_json, ttl_seconds).await
+    }
+    async fn record_challenge_issued(&self, ip: &str, window_seconds: u64) -> AppResult<u64> {
+        let key = format!("challenges_issued:{}", ip);
+        self.incr_ex(&key, window_seconds).await
+    }
+    async fn take_challenge(&self, challenge_id: &str) -> AppResult<Option<Challenge>> {
+        let key = format!("challenge:{}", challenge_id);
+        match self.get_del(&key).await? {
+            Some(json) => Ok(Some(
+                serde_json::from_str(&json).map_err(ExternalError::from)?,
+            )),
+            None => Ok(None),
+        }
+    }
+}
+
+fn login_failures_key(email: &str) -> String {
+    format!("login_failures:{}", email.trim().to_lowercase())
+}
@@ -0,0 +1,47 @@
+use std::cmp::Reverse;
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use crate::{
+    core::result::AppResult,
+    database::memory::client::MemoryClient,
+    models::audit_log::{AuditLog, Detail},
+    repositories::{memory::new_id, surreal::audit_log::AuditLogRepository},
+};
+
+#[async_trait]
+impl AuditLogRepository for MemoryClient {
+    async fn create_audit_log(
+        &self,
+        actor: String,
+        action: &str,
+        status: &str,
+        ip: Option<String>,
+        user_agent: Option<String>,
+        details: Option<Detail>,
+    ) -> AppResult<()> {
+        self.tables().audit_log.push(AuditLog {
+            id: new_id("audit_log"),
+            actor,
+            action: action.to_string(),
+            status: status.to_string(),
+            timestamp: Utc::now(),
+            ip,
+            user_agent,
+            details,
+        });
+        Ok(())
+    }
+    async fn find_audit_logs_by_actor(&self, actor: String) -> AppResult<Vec<AuditLog>> {
+        let tables = self.tables();
+        let mut audit_logs: Vec<AuditLog> = tables
+            .audit_log
+            .iter()
+            .filter(|audit_log| audit_log.actor == actor)
+            .cloned()
+            .collect();
+        audit_logs.sort_by_key(|audit_log| Reverse(audit_log.timestamp));
+        Ok(audit_logs)
+    }
+}
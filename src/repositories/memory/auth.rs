@@ -0,0 +1,92 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use surrealdb::sql::Thing;
+
+use crate::{
+    core::{error::user::UserErrorKind, result::AppResult},
+    database::memory::client::MemoryClient,
+    models::{
+        email::EmailType,
+        user::{User, UserRole, UserStatus},
+    },
+    repositories::{memory::new_id, surreal::auth::AuthRepository},
+};
+
+#[async_trait]
+impl AuthRepository for MemoryClient {
+    async fn create_user(
+        &self,
+        name: &str,
+        email: &str,
+        password_hash: &str,
+        role: UserRole,
+    ) -> AppResult<()> {
+        let mut tables = self.tables();
+        // Mirrors the unique index on `users.email`.
+        if tables.users.iter().any(|user| user.email == email) {
+            return Err(UserErrorKind::CreateUserFailed.into());
+        }
+        let now = Utc::now();
+        tables.users.push(User {
+            id: new_id("users"),
+            name: name.to_string(),
+            email: email.to_string(),
+            password: password_hash.to_string(),
+            role,
+            is_verified: false,
+            status: UserStatus::Inactive,
+            locale: None,
+            timezone: None,
+            avatar_url: None,
+            avatar_urls: None,
+            deletion_scheduled_at: None,
+            notification_preferences: None,
+            created_at: now,
+            updated_at: now,
+        });
+        Ok(())
+    }
+    async fn find_user_by_email(&self, email: &str) -> AppResult<Option<User>> {
+        let tables = self.tables();
+        Ok(tables
+            .users
+            .iter()
+            .find(|user| user.email == email)
+            .cloned())
+    }
+    async fn find_user_by_id(&self, user_id: Thing) -> AppResult<Option<User>> {
+        let tables = self.tables();
+        Ok(tables.users.iter().find(|user| user.id == user_id).cloned())
+    }
+    async fn user_verified(&self, user_id: Thing, user_status: UserStatus) -> AppResult<()> {
+        let mut tables = self.tables();
+        let Some(user) = tables.users.iter_mut().find(|user| user.id == user_id) else {
+            return Err(UserErrorKind::UserNotFound.into());
+        };
+        user.is_verified = true;
+        user.status = user_status;
+        user.updated_at = Utc::now();
+        if let Some(email) = tables
+            .emails
+            .iter_mut()
+            .filter(|email| {
+                email.user_id == user_id
+                    && email.email_type == EmailType::Verification
+                    && !email.is_used
+            })
+            .max_by_key(|email| email.created_at)
+        {
+            email.is_used = true;
+        }
+        Ok(())
+    }
+    async fn reset_password(&self, user_id: Thing, password_hash: &str) -> AppResult<()> {
+        let mut tables = self.tables();
+        let Some(user) = tables.users.iter_mut().find(|user| user.id == user_id) else {
+            return Err(UserErrorKind::UserNotFound.into());
+        };
+        user.password = password_hash.to_string();
+        user.updated_at = Utc::now();
+        Ok(())
+    }
+}
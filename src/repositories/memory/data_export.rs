@@ -0,0 +1,85 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use surrealdb::sql::Thing;
+
+use crate::{
+    core::{error::data_export::DataExportErrorKind, result::AppResult},
+    database::memory::client::MemoryClient,
+    models::data_export::DataExport,
+    repositories::{memory::new_id, surreal::data_export::DataExportRepository},
+};
+
+#[async_trait]
+impl DataExportRepository for MemoryClient {
+    async fn create_data_export(
+        &self,
+        user_id: Thing,
+        token: &str,
+        content: &str,
+        expires_at: DateTime<Utc>,
+    ) -> AppResult<DataExport> {
+        let mut tables = self.tables();
+        if tables
+            .data_exports
+            .iter()
+            .any(|data_export| data_export.token == token)
+        {
+            return Err(DataExportErrorKind::CreateDataExportFailed.into());
+        }
+        let data_export = DataExport {
+            id: new_id("data_exports"),
+            user_id,
+            token: token.to_string(),
+            content: content.to_string(),
+            created_at: Utc::now(),
+            expires_at,
+        };
+        tables.data_exports.push(data_export.clone());
+        Ok(data_export)
+    }
+    async fn find_data_export_by_token(&self, token: &str) -> AppResult<Option<DataExport>> {
+        let tables = self.tables();
+        Ok(tables
+            .data_exports
+            .iter()
+            .find(|data_export| data_export.token == token)
+            .cloned())
+    }
+    async fn find_latest_data_export_by_user_id(
+        &self,
+        user_id: Thing,
+    ) -> AppResult<Option<DataExport>> {
+        let tables = self.tables();
+        Ok(tables
+            .data_exports
+            .iter()
+            .filter(|data_export| data_export.user_id == user_id)
+            .max_by_key(|data_export| data_export.created_at)
+            .cloned())
+    }
+    async fn find_data_exports_by_user_id(&self, user_id: Thing) -> AppResult<Vec<DataExport>> {
+        let tables = self.tables();
+        Ok(tables
+            .data_exports
+            .iter()
+            .filter(|data_export| data_export.user_id == user_id)
+            .cloned()
+            .collect())
+    }
+    async fn find_expired_data_exports(&self) -> AppResult<Vec<DataExport>> {
+        let now = Utc::now();
+        let tables = self.tables();
+        Ok(tables
+            .data_exports
+            .iter()
+            .filter(|data_export| data_export.expires_at <= now)
+            .cloned()
+            .collect())
+    }
+    async fn delete_data_export(&self, data_export_id: Thing) -> AppResult<()> {
+        self.tables()
+            .data_exports
+            .retain(|data_export| data_export.id != data_export_id);
+        Ok(())
+    }
+}
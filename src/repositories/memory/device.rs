@@ -0,0 +1,105 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use surrealdb::sql::Thing;
+
+use crate::{
+    core::{error::device::DeviceErrorKind, result::AppResult},
+    database::memory::client::MemoryClient,
+    models::{device::Device, geo_location::GeoLocation},
+    repositories::{memory::new_id, surreal::device::DeviceRepository},
+    utils::device::ParsedUserAgent,
+};
+
+#[async_trait]
+impl DeviceRepository for MemoryClient {
+    async fn create_device(
+        &self,
+        user_id: Thing,
+        user_agent: &ParsedUserAgent,
+        ip: String,
+        location: Option<GeoLocation>,
+    ) -> AppResult<Device> {
+        let device = Device {
+            id: new_id("devices"),
+            user_id,
+            ip,
+            user_agent: user_agent.browser_label(),
+            os: user_agent.os_label(),
+            device: user_agent.device_label(),
+            browser_family: Some(user_agent.browser.family.clone()),
+            browser_major: user_agent.browser.major.clone(),
+            os_family: Some(user_agent.os.family.clone()),
+            location,
+            is_trusted: true,
+            last_login_at: Utc::now(),
+        };
+        self.tables().devices.push(device.clone());
+        Ok(device)
+    }
+    async fn record_device_login(
+        &self,
+        device_id: Thing,
+        user_agent: &ParsedUserAgent,
+        ip: String,
+        location: Option<GeoLocation>,
+    ) -> AppResult<Device> {
+        let mut tables = self.tables();
+        let Some(device) = tables
+            .devices
+            .iter_mut()
+            .find(|device| device.id == device_id)
+        else {
+            return Err(DeviceErrorKind::DeviceNotFound.into());
+        };
+        device.user_agent = user_agent.browser_label();
+        device.os = user_agent.os_label();
+        device.device = user_agent.device_label();
+        device.browser_family = Some(user_agent.browser.family.clone());
+        device.browser_major = user_agent.browser.major.clone();
+        device.os_family = Some(user_agent.os.family.clone());
+        device.ip = ip;
+        device.location = location;
+        device.last_login_at = Utc::now();
+        Ok(device.clone())
+    }
+    async fn distrust_device(&self, device_id: Thing, user_id: Thing) -> AppResult<()> {
+        let mut tables = self.tables();
+        match tables
+            .devices
+            .iter_mut()
+            .find(|device| device.id == device_id && device.user_id == user_id)
+        {
+            Some(device) => {
+                device.is_trusted = false;
+                Ok(())
+            }
+            None => Err(DeviceErrorKind::DeviceNotFound.into()),
+        }
+    }
+    async fn find_trusted_devices_by_user_id(&self, user_id: Thing) -> AppResult<Vec<Device>> {
+        let tables = self.tables();
+        Ok(tables
+            .devices
+            .iter()
+            .filter(|device| device.user_id == user_id && device.is_trusted)
+            .cloned()
+            .collect())
+    }
+    async fn find_device_by_id(&self, device_id: Thing) -> AppResult<Option<Device>> {
+        let tables = self.tables();
+        Ok(tables
+            .devices
+            .iter()
+            .find(|device| device.id == device_id)
+            .cloned())
+    }
+    async fn find_devices_by_user_id(&self, user_id: Thing) -> AppResult<Vec<Device>> {
+        let tables = self.tables();
+        Ok(tables
+            .devices
+            .iter()
+            .filter(|device| device.user_id == user_id)
+            .cloned()
+            .collect())
+    }
+}
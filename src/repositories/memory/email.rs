@@ -0,0 +1,72 @@
+use std::cmp::Reverse;
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use surrealdb::sql::Thing;
+
+use crate::{
+    core::{error::email::EmailErrorKind, result::AppResult},
+    database::memory::client::MemoryClient,
+    models::email::{Email, EmailType},
+    repositories::{memory::new_id, surreal::email::EmailRepository},
+};
+
+#[async_trait]
+impl EmailRepository for MemoryClient {
+    async fn create_email(
+        &self,
+        user_id: Thing,
+        email_type: EmailType,
+        email_token: String,
+    ) -> AppResult<()> {
+        let mut tables = self.tables();
+        if tables
+            .emails
+            .iter()
+            .any(|email| email.email_token == email_token)
+        {
+            return Err(EmailErrorKind::CreateEmailFailed.into());
+        }
+        let now = Utc::now();
+        tables.emails.push(Email {
+            id: new_id("emails"),
+            user_id,
+            email_type,
+            email_token,
+            created_at: now,
+            expires_at: now + Duration::days(1),
+            is_used: false,
+        });
+        Ok(())
+    }
+    async fn find_email_by_user_id_and_email_type(
+        &self,
+        user_id: Thing,
+        email_type: EmailType,
+    ) -> AppResult<Option<Email>> {
+        let now = Utc::now();
+        let tables = self.tables();
+        Ok(tables
+            .emails
+            .iter()
+            .filter(|email| {
+                email.user_id == user_id
+                    && email.email_type == email_type
+                    && !email.is_used
+                    && email.expires_at > now
+            })
+            .max_by_key(|email| email.created_at)
+            .cloned())
+    }
+    async fn find_emails_by_user_id(&self, user_id: Thing) -> AppResult<Vec<Email>> {
+        let tables = self.tables();
+        let mut emails: Vec<Email> = tables
+            .emails
+            .iter()
+            .filter(|email| email.user_id == user_id)
+            .cloned()
+            .collect();
+        emails.sort_by_key(|email| Reverse(email.created_at));
+        Ok(emails)
+    }
+}
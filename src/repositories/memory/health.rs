@@ -0,0 +1,16 @@
+use async_trait::async_trait;
+
+use crate::{
+    core::result::AppResult, database::memory::client::MemoryClient,
+    repositories::surreal::health::HealthRepository,
+};
+
+#[async_trait]
+impl HealthRepository for MemoryClient {
+    async fn health_check(&self) -> AppResult<bool> {
+        Ok(true)
+    }
+    async fn version(&self) -> AppResult<String> {
+        Ok("memory".to_string())
+    }
+}
@@ -0,0 +1,20 @@
+use async_trait::async_trait;
+use chrono::Utc;
+
+use crate::{
+    core::result::AppResult, database::memory::client::MemoryClient,
+    models::ip_blacklist::IpBlacklist, repositories::surreal::ip_blacklist::IpBlacklistRepository,
+};
+
+#[async_trait]
+impl IpBlacklistRepository for MemoryClient {
+    async fn find_active_ip_blacklist(&self, ip: &str) -> AppResult<Option<IpBlacklist>> {
+        let now = Utc::now();
+        let tables = self.tables();
+        Ok(tables
+            .ip_blacklist
+            .iter()
+            .find(|entry| entry.ip == ip && entry.expires_at > now)
+            .cloned())
+    }
+}
@@ -0,0 +1,18 @@
+use surrealdb::sql::{Id, Thing};
+
+pub mod audit_log;
+pub mod auth;
+pub mod data_export;
+pub mod device;
+pub mod email;
+pub mod health;
+pub mod ip_blacklist;
+pub mod password_history;
+pub mod refresh_token;
+pub mod script_migration;
+pub mod user;
+
+/// A new record id, like `rand::uuid::v4()` in the SurrealDB queries.
+fn new_id(table: &str) -> Thing {
+    Thing::from((table, Id::uuid()))
+}
@@ -0,0 +1,60 @@
+use std::cmp::Reverse;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use surrealdb::sql::Thing;
+
+use crate::{
+    core::result::AppResult,
+    database::memory::client::MemoryClient,
+    models::password_history::PasswordHistory,
+    repositories::{memory::new_id, surreal::password_history::PasswordHistoryRepository},
+};
+
+#[async_trait]
+impl PasswordHistoryRepository for MemoryClient {
+    async fn find_password_history_by_user_id(
+        &self,
+        user_id: Thing,
+        limit: usize,
+    ) -> AppResult<Vec<PasswordHistory>> {
+        let tables = self.tables();
+        let mut history: Vec<PasswordHistory> = tables
+            .password_history
+            .iter()
+            .filter(|entry| entry.user_id == user_id)
+            .cloned()
+            .collect();
+        history.sort_by_key(|entry| Reverse(entry.created_at));
+        history.truncate(limit);
+        Ok(history)
+    }
+    async fn add_password_history(
+        &self,
+        user_id: Thing,
+        password: &str,
+        keep: usize,
+    ) -> AppResult<()> {
+        let mut tables = self.tables();
+        tables.password_history.push(PasswordHistory {
+            id: new_id("password_history"),
+            user_id: user_id.clone(),
+            password: password.to_string(),
+            created_at: Utc::now(),
+        });
+        // Entries are appended in order, so the most recent ones are at the end.
+        let mut recent = tables
+            .password_history
+            .iter()
+            .filter(|entry| entry.user_id == user_id)
+            .count();
+        tables.password_history.retain(|entry| {
+            if entry.user_id != user_id {
+                return true;
+            }
+            recent -= 1;
+            recent < keep
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use surrealdb::sql::Thing;
+
+use crate::{
+    core::{error::refresh_token::RefreshTokenErrorKind, result::AppResult},
+    database::memory::client::MemoryClient,
+    models::token::RefreshToken,
+    repositories::{memory::new_id, surreal::refresh_token::RefreshTokenRepository},
+};
+
+#[async_trait]
+impl RefreshTokenRepository for MemoryClient {
+    async fn create_refresh_token(
+        &self,
+        user_id: Thing,
+        device_id: Thing,
+        token_value: &str,
+    ) -> AppResult<RefreshToken> {
+        let mut tables = self.tables();
+        if tables
+            .refresh_tokens
+            .iter()
+            .any(|token| token.token_value == token_value)
+        {
+            return Err(RefreshTokenErrorKind::CreateRefreshTokenFailed.into());
+        }
+        let now = Utc::now();
+        let refresh_token = RefreshToken {
+            id: new_id("refresh_tokens"),
+            user_id,
+            device_id,
+            token_value: token_value.to_string(),
+            created_at: now,
+            expires_at: now + Duration::days(7),
+        };
+        tables.refresh_tokens.push(refresh_token.clone());
+        Ok(refresh_token)
+    }
+    async fn find_refresh_token_by_user_and_device(
+        &self,
+        user_id: Thing,
+        device_id: Thing,
+    ) -> AppResult<Option<RefreshToken>> {
+        let tables = self.tables();
+        Ok(tables
+            .refresh_tokens
+            .iter()
+            .rfind(|token| token.user_id == user_id && token.device_id == device_id)
+            .cloned())
+    }
+    async fn delete_refresh_token(&self, user_id: Thing, token_value: &str) -> AppResult<()> {
+        let mut tables = self.tables();
+        let count = tables.refresh_tokens.len();
+        tables
+            .refresh_tokens
+            .retain(|token| !(token.user_id == user_id && token.token_value == token_value));
+        if tables.refresh_tokens.len() == count {
+            return Err(RefreshTokenErrorKind::DeleteRefreshTokenFailed.into());
+        }
+        Ok(())
+    }
+    async fn delete_refresh_tokens_by_user_id(&self, user_id: Thing) -> AppResult<()> {
+        self.tables()
+            .refresh_tokens
+            .retain(|token| token.user_id != user_id);
+        Ok(())
+    }
+    async fn delete_refresh_tokens_by_device_id(&self, device_id: Thing) -> AppResult<()> {
+        self.tables()
+            .refresh_tokens
+            .retain(|token| token.device_id != device_id);
+        Ok(())
+    }
+    async fn find_refresh_tokens_by_user_id(&self, user_id: Thing) -> AppResult<Vec<RefreshToken>> {
+        let tables = self.tables();
+        Ok(tables
+            .refresh_tokens
+            .iter()
+            .filter(|token| token.user_id == user_id)
+            .cloned()
+            .collect())
+    }
+    async fn delete_expired_refresh_tokens(&self) -> AppResult<usize> {
+        let now = Utc::now();
+        let mut tables = self.tables();
+        let count = tables.refresh_tokens.len();
+        tables.refresh_tokens.retain(|token| token.expires_at > now);
+        Ok(count - tables.refresh_tokens.len())
+    }
+}
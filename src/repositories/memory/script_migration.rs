@@ -0,0 +1,37 @@
+use async_trait::async_trait;
+use chrono::Utc;
+
+use crate::{
+    core::result::AppResult,
+    database::memory::client::MemoryClient,
+    models::script_migration::ScriptMigration,
+    repositories::{memory::new_id, surreal::script_migration::ScriptMigrationRepository},
+};
+
+/// SurrealQL can't run here, so schemas are skipped and migrations are only recorded.
+#[async_trait]
+impl ScriptMigrationRepository for MemoryClient {
+    async fn find_script_migrations(&self) -> AppResult<Vec<ScriptMigration>> {
+        let tables = self.tables();
+        let mut script_migrations = tables.script_migrations.clone();
+        script_migrations.sort_by(|a, b| a.script_name.cmp(&b.script_name));
+        Ok(script_migrations)
+    }
+    async fn apply_definitions(&self, _sql: &str) -> AppResult<()> {
+        Ok(())
+    }
+    async fn apply_script_migration(
+        &self,
+        script_name: &str,
+        checksum: &str,
+        _sql: &str,
+    ) -> AppResult<()> {
+        self.tables().script_migrations.push(ScriptMigration {
+            id: new_id("script_migration"),
+            script_name: script_name.to_string(),
+            executed_at: Utc::now(),
+            checksum: Some(checksum.to_string()),
+        });
+        Ok(())
+    }
+}
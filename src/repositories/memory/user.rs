@@ -0,0 +1,169 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use surrealdb::sql::Thing;
+
+use crate::{
+    core::{error::user::UserErrorKind, result::AppResult},
+    database::memory::client::{MemoryClient, MemoryTables},
+    dto::request::user::UserProfileRequest,
+    models::user::{AvatarUrls, NotificationPreferences, User, UserRole, UserStatus},
+    repositories::surreal::user::UserRepository,
+};
+
+/// Applies `update` to the user and returns the result, like `UPDATE ... RETURN AFTER`.
+fn update_user(
+    tables: &mut MemoryTables,
+    user_id: &Thing,
+    update: impl FnOnce(&mut User),
+) -> AppResult<User> {
+    match tables.users.iter_mut().find(|user| user.id == *user_id) {
+        Some(user) => {
+            update(user);
+            Ok(user.clone())
+        }
+        None => Err(UserErrorKind::UserNotFound.into()),
+    }
+}
+
+#[async_trait]
+impl UserRepository for MemoryClient {
+    async fn change_password(&self, user_id: Thing, password_hash: &str) -> AppResult<()> {
+        update_user(&mut self.tables(), &user_id, |user| {
+            user.password = password_hash.to_string();
+            user.updated_at = Utc::now();
+        })?;
+        Ok(())
+    }
+    async fn update_password_hash(&self, user_id: Thing, password_hash: &str) -> AppResult<()> {
+        update_user(&mut self.tables(), &user_id, |user| {
+            user.password = password_hash.to_string();
+        })?;
+        Ok(())
+    }
+    async fn update_profile(
+        &self,
+        user_id: Thing,
+        profile: UserProfileRequest,
+    ) -> AppResult<Option<User>> {
+        let mut tables = self.tables();
+        let Some(user) = tables
+            .users
+            .iter_mut()
+            .find(|user| user.id == user_id && user.updated_at == profile.updated_at)
+        else {
+            return Ok(None);
+        };
+        if let Some(name) = profile.name {
+            user.name = name;
+        }
+        if let Some(locale) = profile.locale {
+            user.locale = locale;
+        }
+        if let Some(timezone) = profile.timezone {
+            user.timezone = timezone;
+        }
+        if let Some(avatar_url) = profile.avatar_url {
+            user.avatar_url = avatar_url;
+            user.avatar_urls = None;
+        }
+        user.updated_at = Utc::now();
+        Ok(Some(user.clone()))
+    }
+    async fn update_avatar(&self, user_id: Thing, avatar_urls: AvatarUrls) -> AppResult<User> {
+        update_user(&mut self.tables(), &user_id, |user| {
+            user.avatar_url = None;
+            user.avatar_urls = Some(avatar_urls);
+            user.updated_at = Utc::now();
+        })
+    }
+    async fn update_notification_preferences(
+        &self,
+        user_id: Thing,
+        notification_preferences: NotificationPreferences,
+    ) -> AppResult<User> {
+        update_user(&mut self.tables(), &user_id, |user| {
+            user.notification_preferences = Some(notification_preferences);
+            user.updated_at = Utc::now();
+        })
+    }
+    async fn schedule_deletion(
+        &self,
+        user_id: Thing,
+        deletion_scheduled_at: DateTime<Utc>,
+    ) -> AppResult<()> {
+        update_user(&mut self.tables(), &user_id, |user| {
+            user.status = UserStatus::Deleted;
+            user.deletion_scheduled_at = Some(deletion_scheduled_at);
+            user.updated_at = Utc::now();
+        })?;
+        Ok(())
+    }
+    async fn restore_account(&self, user_id: Thing, user_status: UserStatus) -> AppResult<()> {
+        update_user(&mut self.tables(), &user_id, |user| {
+            user.status = user_status;
+            user.deletion_scheduled_at = None;
+            user.updated_at = Utc::now();
+        })?;
+        Ok(())
+    }
+    async fn update_role(&self, user_id: Thing, role: UserRole) -> AppResult<User> {
+        update_user(&mut self.tables(), &user_id, |user| {
+            user.role = role;
+            user.updated_at = Utc::now();
+        })
+    }
+    async fn update_status(&self, user_id: Thing, user_status: UserStatus) -> AppResult<User> {
+        update_user(&mut self.tables(), &user_id, |user| {
+            user.status = user_status;
+            user.updated_at = Utc::now();
+        })
+    }
+    async fn find_users_due_for_purge(&self) -> AppResult<Vec<User>> {
+        let now = Utc::now();
+        let tables = self.tables();
+        Ok(tables
+            .users
+            .iter()
+            .filter(|user| {
+                user.status == UserStatus::Deleted
+                    && user.deletion_scheduled_at.is_some_and(|at| at <= now)
+            })
+            .cloned()
+            .collect())
+    }
+    async fn purge_user(&self, user_id: Thing) -> AppResult<()> {
+        let mut tables = self.tables();
+        tables
+            .refresh_tokens
+            .retain(|token| token.user_id != user_id);
+        tables.devices.retain(|device| device.user_id != user_id);
+        tables.emails.retain(|email| email.user_id != user_id);
+        tables
+            .password_history
+            .retain(|entry| entry.user_id != user_id);
+        tables
+            .data_exports
+            .retain(|data_export| data_export.user_id != user_id);
+        let actor = user_id.to_string();
+        for audit_log in tables.audit_log.iter_mut().filter(|log| log.actor == actor) {
+            audit_log.actor = "deleted-user".to_string();
+            audit_log.ip = None;
+            audit_log.user_agent = None;
+            audit_log.details = None;
+        }
+        let anonymized_email = format!("deleted+{}@invalid", user_id.id.to_raw());
+        if let Some(user) = tables.users.iter_mut().find(|user| user.id == user_id) {
+            user.name = "deleted".to_string();
+            user.email = anonymized_email;
+            user.password = String::new();
+            user.locale = None;
+            user.timezone = None;
+            user.avatar_url = None;
+            user.avatar_urls = None;
+            user.notification_preferences = None;
+            user.deletion_scheduled_at = None;
+            user.updated_at = Utc::now();
+        }
+        Ok(())
+    }
+}
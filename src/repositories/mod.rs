@@ -1,2 +1,59 @@
-pub mod redis;
+use std::fmt::Debug;
+
+use crate::database::ephemeral::EphemeralStore;
+use crate::repositories::{
+    cache::auth::AuthCacheRepository,
+    surreal::{
+        audit_log::AuditLogRepository, auth::AuthRepository, data_export::DataExportRepository,
+        device::DeviceRepository, email::EmailRepository, health::HealthRepository,
+        ip_blacklist::IpBlacklistRepository, password_history::PasswordHistoryRepository,
+        refresh_token::RefreshTokenRepository, script_migration::ScriptMigrationRepository,
+        user::UserRepository,
+    },
+};
+
+pub mod cache;
+pub mod memory;
 pub mod surreal;
+
+/// Every repository kept in the primary database, implemented by `SurrealClient` and `MemoryClient`.
+pub trait Repositories:
+    AuditLogRepository
+    + AuthRepository
+    + DataExportRepository
+    + DeviceRepository
+    + EmailRepository
+    + HealthRepository
+    + IpBlacklistRepository
+    + PasswordHistoryRepository
+    + RefreshTokenRepository
+    + ScriptMigrationRepository
+    + UserRepository
+    + Debug
+    + Send
+    + Sync
+{
+}
+
+impl<T> Repositories for T where
+    T: AuditLogRepository
+        + AuthRepository
+        + DataExportRepository
+        + DeviceRepository
+        + EmailRepository
+        + HealthRepository
+        + IpBlacklistRepository
+        + PasswordHistoryRepository
+        + RefreshTokenRepository
+        + ScriptMigrationRepository
+        + UserRepository
+        + Debug
+        + Send
+        + Sync
+{
+}
+
+/// Every repository kept in the ephemeral store, which is Redis or `MemoryCacheClient`.
+pub trait CacheRepositories: AuthCacheRepository + EphemeralStore {}
+
+impl<T> CacheRepositories for T where T: EphemeralStore {}
@@ -0,0 +1,73 @@
+use async_trait::async_trait;
+
+use crate::{
+    core::{error::external::ExternalError, result::AppResult},
+    database::surreal::client::SurrealClient,
+    models::audit_log::{AuditLog, Detail},
+};
+
+#[async_trait]
+pub trait AuditLogRepository {
+    async fn create_audit_log(
+        &self,
+        actor: String,
+        action: &str,
+        status: &str,
+        ip: Option<String>,
+        user_agent: Option<String>,
+        details: Option<Detail>,
+    ) -> AppResult<()>;
+    async fn find_audit_logs_by_actor(&self, actor: String) -> AppResult<Vec<AuditLog>>;
+}
+
+#[async_trait]
+impl AuditLogRepository for SurrealClient {
+    async fn create_audit_log(
+        &self,
+        actor: String,
+        action: &str,
+        status: &str,
+        ip: Option<String>,
+        user_agent: Option<String>,
+        details: Option<Detail>,
+    ) -> AppResult<()> {
+        let sql = r#"
+            CREATE audit_log CONTENT {
+                id: rand::uuid::v4(),
+                actor: $actor,
+                action: $action,
+                status: $status,
+                ip: $ip,
+                user_agent: $user_agent,
+                details: $details,
+            }
+        "#;
+        self.execute(
+            self.client
+                .query(sql)
+                .bind(("actor", actor))
+                .bind(("action", action.to_string()))
+                .bind(("status", status.to_string()))
+                .bind(("ip", ip))
+                .bind(("user_agent", user_agent))
+                .bind(("details", details)),
+        )
+        .await?
+        .check()
+        .map_err(ExternalError::from)?;
+        Ok(())
+    }
+    async fn find_audit_logs_by_actor(&self, actor: String) -> AppResult<Vec<AuditLog>> {
+        let sql = r#"
+            SELECT * FROM audit_log
+            WHERE
+                actor = $actor
+                ORDER BY timestamp DESC
+        "#;
+        let mut result = self
+            .execute(self.client.query(sql).bind(("actor", actor)))
+            .await?;
+        let audit_logs: Vec<AuditLog> = result.take(0).map_err(ExternalError::from)?;
+        Ok(audit_logs)
+    }
+}
@@ -0,0 +1,133 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use surrealdb::sql::{Datetime, Thing};
+
+use crate::{
+    core::{
+        error::{data_export::DataExportErrorKind, external::ExternalError},
+        result::AppResult,
+    },
+    database::surreal::client::SurrealClient,
+    models::data_export::DataExport,
+};
+
+#[async_trait]
+pub trait DataExportRepository {
+    async fn create_data_export(
+        &self,
+        user_id: Thing,
+        token: &str,
+        content: &str,
+        expires_at: DateTime<Utc>,
+    ) -> AppResult<DataExport>;
+    async fn find_data_export_by_token(&self, token: &str) -> AppResult<Option<DataExport>>;
+    async fn find_latest_data_export_by_user_id(
+        &self,
+        user_id: Thing,
+    ) -> AppResult<Option<DataExport>>;
+    async fn find_data_exports_by_user_id(&self, user_id: Thing) -> AppResult<Vec<DataExport>>;
+    async fn find_expired_data_exports(&self) -> AppResult<Vec<DataExport>>;
+    async fn delete_data_export(&self, data_export_id: Thing) -> AppResult<()>;
+}
+
+#[async_trait]
+impl DataExportRepository for SurrealClient {
+    async fn create_data_export(
+        &self,
+        user_id: Thing,
+        token: &str,
+        content: &str,
+        expires_at: DateTime<Utc>,
+    ) -> AppResult<DataExport> {
+        let sql = r#"
+            CREATE data_exports CONTENT {
+                id: rand::uuid::v4(),
+                user_id: $user_id,
+                token: $token,
+                content: $content,
+                expires_at: $expires_at,
+            }
+        "#;
+        let mut result = self
+            .execute(
+                self.client
+                    .query(sql)
+                    .bind(("user_id", user_id))
+                    .bind(("token", token.to_string()))
+                    .bind(("content", content.to_string()))
+                    .bind(("expires_at", Datetime::from(expires_at))),
+            )
+            .await?;
+        let mut data_export: Vec<DataExport> = result.take(0).map_err(ExternalError::from)?;
+        match data_export.pop() {
+            Some(data_export) => Ok(data_export),
+            None => Err(DataExportErrorKind::CreateDataExportFailed.into()),
+        }
+    }
+    async fn find_data_export_by_token(&self, token: &str) -> AppResult<Option<DataExport>> {
+        let sql = r#"
+            SELECT * FROM data_exports
+            WHERE
+                token = $token
+                LIMIT 1
+        "#;
+        let mut result = self
+            .execute(self.client.query(sql).bind(("token", token.to_string())))
+            .await?;
+        let data_export: Option<DataExport> = result.take(0).map_err(ExternalError::from)?;
+        Ok(data_export)
+    }
+    async fn find_latest_data_export_by_user_id(
+        &self,
+        user_id: Thing,
+    ) -> AppResult<Option<DataExport>> {
+        let sql = r#"
+            SELECT * FROM data_exports
+            WHERE
+                user_id = $user_id
+                ORDER BY created_at DESC
+                LIMIT 1
+        "#;
+        let mut result = self
+            .execute(self.client.query(sql).bind(("user_id", user_id)))
+            .await?;
+        let data_export: Option<DataExport> = result.take(0).map_err(ExternalError::from)?;
+        Ok(data_export)
+    }
+    async fn find_data_exports_by_user_id(&self, user_id: Thing) -> AppResult<Vec<DataExport>> {
+        let sql = r#"
+            SELECT * FROM data_exports
+            WHERE
+                user_id = $user_id
+        "#;
+        let mut result = self
+            .execute(self.client.query(sql).bind(("user_id", user_id)))
+            .await?;
+        let data_exports: Vec<DataExport> = result.take(0).map_err(ExternalError::from)?;
+        Ok(data_exports)
+    }
+    async fn find_expired_data_exports(&self) -> AppResult<Vec<DataExport>> {
+        let sql = r#"
+            SELECT * FROM data_exports
+            WHERE
+                expires_at <= time::now()
+        "#;
+        let mut result = self.execute(self.client.query(sql)).await?;
+        let data_exports: Vec<DataExport> = result.take(0).map_err(ExternalError::from)?;
+        Ok(data_exports)
+    }
+    async fn delete_data_export(&self, data_export_id: Thing) -> AppResult<()> {
+        let sql = r#"
+            DELETE data_exports WHERE id = $data_export_id
+        "#;
+        self.execute(
+            self.client
+                .query(sql)
+                .bind(("data_export_id", data_export_id)),
+        )
+        .await?
+        .check()
+        .map_err(ExternalError::from)?;
+        Ok(())
+    }
+}
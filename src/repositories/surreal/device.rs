@@ -5,7 +5,8 @@ use crate::{
     core::error::{device::DeviceErrorKind, external::ExternalError},
     core::result::AppResult,
     database::surreal::client::SurrealClient,
-    models::device::Device,
+    models::{device::Device, geo_location::GeoLocation},
+    utils::device::ParsedUserAgent,
 };
 
 #[async_trait]
@@ -13,14 +14,21 @@ pub trait DeviceRepository {
     async fn create_device(
         &self,
         user_id: Thing,
-        user_agent: String,
-        os: String,
-        device: String,
+        user_agent: &ParsedUserAgent,
         ip: String,
+        location: Option<GeoLocation>,
+    ) -> AppResult<Device>;
+    async fn record_device_login(
+        &self,
+        device_id: Thing,
+        user_agent: &ParsedUserAgent,
+        ip: String,
+        location: Option<GeoLocation>,
     ) -> AppResult<Device>;
     async fn distrust_device(&self, device_id: Thing, user_id: Thing) -> AppResult<()>;
     async fn find_trusted_devices_by_user_id(&self, user_id: Thing) -> AppResult<Vec<Device>>;
     async fn find_device_by_id(&self, device_id: Thing) -> AppResult<Option<Device>>;
+    async fn find_devices_by_user_id(&self, user_id: Thing) -> AppResult<Vec<Device>>;
 }
 
 #[async_trait]
@@ -28,10 +36,9 @@ impl DeviceRepository for SurrealClient {
     async fn create_device(
         &self,
         user_id: Thing,
-        user_agent: String,
-        os: String,
-        device: String,
+        user_agent: &ParsedUserAgent,
         ip: String,
+        location: Option<GeoLocation>,
     ) -> AppResult<Device> {
         let sql = r#"
             CREATE devices CONTENT {
@@ -40,26 +47,79 @@ impl DeviceRepository for SurrealClient {
                 user_agent: $user_agent,
                 os: $os,
                 device: $device,
+                browser_family: $browser_family,
+                browser_major: $browser_major,
+                os_family: $os_family,
                 ip: $ip,
+                location: $location,
                 is_trusted: true
             }
         "#;
         let mut result = self
-            .client
-            .query(sql)
-            .bind(("user_id", user_id))
-            .bind(("user_agent", user_agent))
-            .bind(("os", os))
-            .bind(("device", device))
-            .bind(("ip", ip))
-            .await
-            .map_err(ExternalError::from)?;
+            .execute(
+                self.client
+                    .query(sql)
+                    .bind(("user_id", user_id))
+                    .bind(("user_agent", user_agent.browser_label()))
+                    .bind(("os", user_agent.os_label()))
+                    .bind(("device", user_agent.device_label()))
+                    .bind(("browser_family", user_agent.browser.family.clone()))
+                    .bind(("browser_major", user_agent.browser.major.clone()))
+                    .bind(("os_family", user_agent.os.family.clone()))
+                    .bind(("ip", ip))
+                    .bind(("location", location)),
+            )
+            .await?;
         let mut device: Vec<Device> = result.take(0).map_err(ExternalError::from)?;
         match device.pop() {
             Some(device) => Ok(device),
             None => Err(DeviceErrorKind::CreateDeviceFailed.into()),
         }
     }
+    async fn record_device_login(
+        &self,
+        device_id: Thing,
+        user_agent: &ParsedUserAgent,
+        ip: String,
+        location: Option<GeoLocation>,
+    ) -> AppResult<Device> {
+        // Labels are refreshed as well, so browser and OS updates show up in the device list.
+        let sql = r#"
+            UPDATE devices SET
+                user_agent = $user_agent,
+                os = $os,
+                device = $device,
+                browser_family = $browser_family,
+                browser_major = $browser_major,
+                os_family = $os_family,
+                ip = $ip,
+                location = $location,
+                last_login_at = time::now()
+            WHERE
+                id = $device_id
+            RETURN AFTER
+        "#;
+        let mut result = self
+            .execute(
+                self.client
+                    .query(sql)
+                    .bind(("user_agent", user_agent.browser_label()))
+                    .bind(("os", user_agent.os_label()))
+                    .bind(("device", user_agent.device_label()))
+                    .bind(("browser_family", user_agent.browser.family.clone()))
+                    .bind(("browser_major", user_agent.browser.major.clone()))
+                    .bind(("os_family", user_agent.os.family.clone()))
+                    .bind(("ip", ip))
+                    .bind(("location", location))
+                    .bind(("device_id", device_id)),
+            )
+            .await?;
+        let mut device: Vec<Device> = result.take(0).map_err(ExternalError::from)?;
+        match device.pop() {
+            Some(device) => Ok(device),
+            None => Err(DeviceErrorKind::DeviceNotFound.into()),
+        }
+    }
     async fn distrust_device(&self, device_id: Thing, user_id: Thing) -> AppResult<()> {
         let sql = r#"
             UPDATE devices SET is_trusted = false
@@ -68,12 +128,13 @@ impl DeviceRepository for SurrealClient {
                 user_id = $user_id
         "#;
         let mut result = self
-            .client
-            .query(sql)
-            .bind(("device_id", device_id))
-            .bind(("user_id", user_id))
-            .await
-            .map_err(ExternalError::from)?;
+            .execute(
+                self.client
+                    .query(sql)
+                    .bind(("device_id", device_id))
+                    .bind(("user_id", user_id)),
+            )
+            .await?;
         let mut updated_device: Vec<Device> = result.take(0).map_err(ExternalError::from)?;
         match updated_device.pop() {
             Some(_) => Ok(()),
@@ -88,11 +149,8 @@ impl DeviceRepository for SurrealClient {
                 is_trusted = true
         "#;
         let mut result = self
-            .client
-            .query(sql)
-            .bind(("user_id", user_id))
-            .await
-            .map_err(ExternalError::from)?;
+            .execute(self.client.query(sql).bind(("user_id", user_id)))
+            .await?;
         let trusted_device: Vec<Device> = result.take(0).map_err(ExternalError::from)?;
         Ok(trusted_device)
     }
@@ -104,12 +162,21 @@ impl DeviceRepository for SurrealClient {
                 id = $device_id
         "#;
         let mut result = self
-            .client
-            .query(sql)
-            .bind(("device_id", device_id))
-            .await
-            .map_err(ExternalError::from)?;
+            .execute(self.client.query(sql).bind(("device_id", device_id)))
+            .await?;
         let mut device: Vec<Device> = result.take(0).map_err(ExternalError::from)?;
         Ok(device.pop())
     }
+    async fn find_devices_by_user_id(&self, user_id: Thing) -> AppResult<Vec<Device>> {
+        let sql = r#"
+            SELECT * FROM devices
+            WHERE
+                user_id = $user_id
+        "#;
+        let mut result = self
+            .execute(self.client.query(sql).bind(("user_id", user_id)))
+            .await?;
+        let devices: Vec<Device> = result.take(0).map_err(ExternalError::from)?;
+        Ok(devices)
+    }
 }
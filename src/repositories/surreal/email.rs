@@ -19,6 +19,7 @@ pub trait EmailRepository {
         user_id: Thing,
         email_type: EmailType,
     ) -> AppResult<Option<Email>>;
+    async fn find_emails_by_user_id(&self, user_id: Thing) -> AppResult<Vec<Email>>;
 }
 
 #[async_trait]
@@ -38,13 +39,14 @@ impl EmailRepository for SurrealClient {
             }
         "#;
         let mut result = self
-            .client
-            .query(sql)
-            .bind(("user_id", user_id))
-            .bind(("email_type", email_type))
-            .bind(("email_token", email_token))
-            .await
-            .map_err(ExternalError::from)?;
+            .execute(
+                self.client
+                    .query(sql)
+                    .bind(("user_id", user_id))
+                    .bind(("email_type", email_type))
+                    .bind(("email_token", email_token)),
+            )
+            .await?;
         let email: Option<Email> = result.take(0).map_err(ExternalError::from)?;
         match email {
             Some(_) => Ok(()),
@@ -67,13 +69,27 @@ impl EmailRepository for SurrealClient {
                 LIMIT 1
         "#;
         let mut result = self
-            .client
-            .query(sql)
-            .bind(("user_id", user_id))
-            .bind(("email_type", email_type))
-            .await
-            .map_err(ExternalError::from)?;
+            .execute(
+                self.client
+                    .query(sql)
+                    .bind(("user_id", user_id))
+                    .bind(("email_type", email_type)),
+            )
+            .await?;
         let email: Option<Email> = result.take(0).map_err(ExternalError::from)?;
         Ok(email)
     }
+    async fn find_emails_by_user_id(&self, user_id: Thing) -> AppResult<Vec<Email>> {
+        let sql = r#"
+            SELECT * FROM emails
+            WHERE
+                user_id = $user_id
+                ORDER BY created_at DESC
+        "#;
+        let mut result = self
+            .execute(self.client.query(sql).bind(("user_id", user_id)))
+            .await?;
+        let emails: Vec<Email> = result.take(0).map_err(ExternalError::from)?;
+        Ok(emails)
+    }
 }
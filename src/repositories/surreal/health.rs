@@ -1,22 +1,35 @@
 use async_trait::async_trait;
 
-use crate::{core::result::AppResult, database::surreal::client::SurrealClient};
+use crate::{
+    core::result::AppResult,
+    database::{circuit_breaker::CircuitState, surreal::client::SurrealClient},
+};
 
 #[async_trait]
 pub trait HealthRepository {
     async fn health_check(&self) -> AppResult<bool>;
+    async fn version(&self) -> AppResult<String>;
+    /// Whether queries currently reach the database, see `CircuitBreaker`.
+    fn circuit_state(&self) -> CircuitState {
+        CircuitState::Closed
+    }
 }
 
 #[async_trait]
 impl HealthRepository for SurrealClient {
     async fn health_check(&self) -> AppResult<bool> {
         let sql = "RETURN time::now()";
-        match self.client.query(sql).await {
-            Ok(mut result) => match result.take::<Option<String>>(0) {
-                Ok(Some(_)) => Ok(true),
-                _ => Ok(false),
-            },
-            Err(_) => Ok(false),
+        let mut result = self.execute(self.client.query(sql)).await?;
+        match result.take::<Option<String>>(0) {
+            Ok(Some(_)) => Ok(true),
+            _ => Ok(false),
         }
     }
+    async fn version(&self) -> AppResult<String> {
+        let version = self.execute(self.client.version()).await?;
+        Ok(version.to_string())
+    }
+    fn circuit_state(&self) -> CircuitState {
+        self.breaker.state()
+    }
 }
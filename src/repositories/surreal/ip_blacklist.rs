@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+
+use crate::{
+    core::{error::external::ExternalError, result::AppResult},
+    database::surreal::client::SurrealClient,
+    models::ip_blacklist::IpBlacklist,
+};
+
+#[async_trait]
+pub trait IpBlacklistRepository {
+    async fn find_active_ip_blacklist(&self, ip: &str) -> AppResult<Option<IpBlacklist>>;
+}
+
+#[async_trait]
+impl IpBlacklistRepository for SurrealClient {
+    async fn find_active_ip_blacklist(&self, ip: &str) -> AppResult<Option<IpBlacklist>> {
+        let sql = r#"
+            SELECT * FROM ip_blacklist
+            WHERE
+                ip = $ip AND
+                expires_at > time::now()
+                LIMIT 1
+        "#;
+        let mut result = self
+            .execute(self.client.query(sql).bind(("ip", ip.to_string())))
+            .await?;
+        let ip_blacklist: Option<IpBlacklist> = result.take(0).map_err(ExternalError::from)?;
+        Ok(ip_blacklist)
+    }
+}
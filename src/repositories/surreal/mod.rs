@@ -1,6 +1,11 @@
+pub mod audit_log;
 pub mod auth;
+pub mod data_export;
 pub mod device;
 pub mod email;
 pub mod health;
+pub mod ip_blacklist;
+pub mod password_history;
 pub mod refresh_token;
+pub mod script_migration;
 pub mod user;
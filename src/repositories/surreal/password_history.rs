@@ -0,0 +1,90 @@
+use async_trait::async_trait;
+use surrealdb::sql::Thing;
+
+use crate::{
+    core::{error::external::ExternalError, result::AppResult},
+    database::surreal::client::SurrealClient,
+    models::password_history::PasswordHistory,
+};
+
+#[async_trait]
+pub trait PasswordHistoryRepository {
+    async fn find_password_history_by_user_id(
+        &self,
+        user_id: Thing,
+        limit: usize,
+    ) -> AppResult<Vec<PasswordHistory>>;
+    async fn add_password_history(
+        &self,
+        user_id: Thing,
+        password: &str,
+        keep: usize,
+    ) -> AppResult<()>;
+}
+
+#[async_trait]
+impl PasswordHistoryRepository for SurrealClient {
+    async fn find_password_history_by_user_id(
+        &self,
+        user_id: Thing,
+        limit: usize,
+    ) -> AppResult<Vec<PasswordHistory>> {
+        let sql = r#"
+            SELECT * FROM password_history
+            WHERE
+                user_id = $user_id
+            ORDER BY created_at DESC
+            LIMIT $limit
+        "#;
+        let mut result = self
+            .execute(
+                self.client
+                    .query(sql)
+                    .bind(("user_id", user_id))
+                    .bind(("limit", limit)),
+            )
+            .await?;
+        let history: Vec<PasswordHistory> = result.take(0).map_err(ExternalError::from)?;
+        Ok(history)
+    }
+    async fn add_password_history(
+        &self,
+        user_id: Thing,
+        password: &str,
+        keep: usize,
+    ) -> AppResult<()> {
+        // Everything older than the `keep` most recent entries is pruned in the same
+        // transaction, so the table never grows past the configured history size.
+        let sql = r#"
+            BEGIN TRANSACTION;
+                CREATE password_history CONTENT {
+                    id: rand::uuid::v4(),
+                    user_id: $user_id,
+                    password: $password,
+                };
+                LET $recent = (
+                    SELECT id, created_at FROM password_history
+                    WHERE
+                        user_id = $user_id
+                    ORDER BY created_at DESC
+                    LIMIT $keep
+                ).id;
+                DELETE password_history
+                WHERE
+                    user_id = $user_id AND
+                    id NOTINSIDE $recent;
+            COMMIT TRANSACTION;
+        "#;
+        self.execute(
+            self.client
+                .query(sql)
+                .bind(("user_id", user_id))
+                .bind(("password", password.to_string()))
+                .bind(("keep", keep)),
+        )
+        .await?
+        .check()
+        .map_err(ExternalError::from)?;
+        Ok(())
+    }
+}
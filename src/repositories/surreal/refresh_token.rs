@@ -21,6 +21,10 @@ pub trait RefreshTokenRepository {
         device_id: Thing,
     ) -> AppResult<Option<RefreshToken>>;
     async fn delete_refresh_token(&self, user_id: Thing, token_value: &str) -> AppResult<()>;
+    async fn delete_refresh_tokens_by_user_id(&self, user_id: Thing) -> AppResult<()>;
+    async fn delete_refresh_tokens_by_device_id(&self, device_id: Thing) -> AppResult<()>;
+    async fn find_refresh_tokens_by_user_id(&self, user_id: Thing) -> AppResult<Vec<RefreshToken>>;
+    async fn delete_expired_refresh_tokens(&self) -> AppResult<usize>;
 }
 
 #[async_trait]
@@ -40,13 +44,14 @@ impl RefreshTokenRepository for SurrealClient {
             }
         "#;
         let mut result = self
-            .client
-            .query(sql)
-            .bind(("user_id", user_id))
-            .bind(("device_id", device_id))
-            .bind(("token_value", token_value.to_string()))
-            .await
-            .map_err(ExternalError::from)?;
+            .execute(
+                self.client
+                    .query(sql)
+                    .bind(("user_id", user_id))
+                    .bind(("device_id", device_id))
+                    .bind(("token_value", token_value.to_string())),
+            )
+            .await?;
         let mut refresh_token: Vec<RefreshToken> = result.take(0).map_err(ExternalError::from)?;
         match refresh_token.pop() {
             Some(refresh_token) => Ok(refresh_token),
@@ -62,12 +67,13 @@ impl RefreshTokenRepository for SurrealClient {
             SELECT * FROM refresh_tokens WHERE user_id = $user_id AND device_id = $device_id
         "#;
         let mut result = self
-            .client
-            .query(sql)
-            .bind(("user_id", user_id))
-            .bind(("device_id", device_id))
-            .await
-            .map_err(ExternalError::from)?;
+            .execute(
+                self.client
+                    .query(sql)
+                    .bind(("user_id", user_id))
+                    .bind(("device_id", device_id)),
+            )
+            .await?;
         let mut refresh_token: Vec<RefreshToken> = result.take(0).map_err(ExternalError::from)?;
         Ok(refresh_token.pop())
     }
@@ -76,16 +82,55 @@ impl RefreshTokenRepository for SurrealClient {
             DELETE * FROM refresh_tokens WHERE user_id = $user_id AND token_value = $token_value
         "#;
         let mut result = self
-            .client
-            .query(sql)
-            .bind(("user_id", user_id))
-            .bind(("token_value", token_value.to_string()))
-            .await
-            .map_err(ExternalError::from)?;
+            .execute(
+                self.client
+                    .query(sql)
+                    .bind(("user_id", user_id))
+                    .bind(("token_value", token_value.to_string())),
+            )
+            .await?;
         let deleted_result: Vec<RefreshToken> = result.take(0).map_err(ExternalError::from)?;
         if deleted_result.is_empty() {
             return Err(RefreshTokenErrorKind::DeleteRefreshTokenFailed.into());
         }
         Ok(())
     }
+    async fn delete_refresh_tokens_by_user_id(&self, user_id: Thing) -> AppResult<()> {
+        let sql = r#"
+            DELETE refresh_tokens WHERE user_id = $user_id
+        "#;
+        self.execute(self.client.query(sql).bind(("user_id", user_id)))
+            .await?
+            .check()
+            .map_err(ExternalError::from)?;
+        Ok(())
+    }
+    async fn delete_refresh_tokens_by_device_id(&self, device_id: Thing) -> AppResult<()> {
+        let sql = r#"
+            DELETE refresh_tokens WHERE device_id = $device_id
+        "#;
+        self.execute(self.client.query(sql).bind(("device_id", device_id)))
+            .await?
+            .check()
+            .map_err(ExternalError::from)?;
+        Ok(())
+    }
+    async fn find_refresh_tokens_by_user_id(&self, user_id: Thing) -> AppResult<Vec<RefreshToken>> {
+        let sql = r#"
+            SELECT * FROM refresh_tokens WHERE user_id = $user_id
+        "#;
+        let mut result = self
+            .execute(self.client.query(sql).bind(("user_id", user_id)))
+            .await?;
+        let refresh_tokens: Vec<RefreshToken> = result.take(0).map_err(ExternalError::from)?;
+        Ok(refresh_tokens)
+    }
+    async fn delete_expired_refresh_tokens(&self) -> AppResult<usize> {
+        let sql = r#"
+            DELETE refresh_tokens WHERE expires_at <= time::now() RETURN BEFORE
+        "#;
+        let mut result = self.execute(self.client.query(sql)).await?;
+        let refresh_tokens: Vec<RefreshToken> = result.take(0).map_err(ExternalError::from)?;
+        Ok(refresh_tokens.len())
+    }
 }
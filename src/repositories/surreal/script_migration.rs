@@ -0,0 +1,71 @@
+use async_trait::async_trait;
+
+use crate::{
+    core::{error::external::ExternalError, result::AppResult},
+    database::surreal::client::SurrealClient,
+    models::script_migration::ScriptMigration,
+};
+
+#[async_trait]
+pub trait ScriptMigrationRepository {
+    async fn find_script_migrations(&self) -> AppResult<Vec<ScriptMigration>>;
+    async fn apply_definitions(&self, sql: &str) -> AppResult<()>;
+    async fn apply_script_migration(
+        &self,
+        script_name: &str,
+        checksum: &str,
+        sql: &str,
+    ) -> AppResult<()>;
+}
+
+#[async_trait]
+impl ScriptMigrationRepository for SurrealClient {
+    async fn find_script_migrations(&self) -> AppResult<Vec<ScriptMigration>> {
+        let sql = r#"
+            SELECT * FROM script_migration
+            ORDER BY script_name ASC
+        "#;
+        let mut result = self.execute(self.client.query(sql)).await?;
+        let script_migrations: Vec<ScriptMigration> =
+            result.take(0).map_err(ExternalError::from)?;
+        Ok(script_migrations)
+    }
+    async fn apply_definitions(&self, sql: &str) -> AppResult<()> {
+        self.execute(self.client.query(sql))
+            .await?
+            .check()
+            .map_err(ExternalError::from)?;
+        Ok(())
+    }
+    async fn apply_script_migration(
+        &self,
+        script_name: &str,
+        checksum: &str,
+        sql: &str,
+    ) -> AppResult<()> {
+        // The script and its record commit together, a failing script leaves no trace.
+        let sql = format!(
+            r#"
+            BEGIN TRANSACTION;
+            {}
+            ;
+            CREATE script_migration CONTENT {{
+                script_name: $script_name,
+                checksum: $checksum,
+            }};
+            COMMIT TRANSACTION;
+        "#,
+            sql
+        );
+        self.execute(
+            self.client
+                .query(sql)
+                .bind(("script_name", script_name.to_string()))
+                .bind(("checksum", checksum.to_string())),
+        )
+        .await?
+        .check()
+        .map_err(ExternalError::from)?;
+        Ok(())
+    }
+}
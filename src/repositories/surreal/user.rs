@@ -1,5 +1,6 @@
 use async_trait::async_trait;
-use surrealdb::sql::Thing;
+use chrono::{DateTime, Utc};
+use surrealdb::sql::{Datetime, Thing};
 
 use crate::{
     core::{
@@ -7,37 +8,340 @@ use crate::{
         result::AppResult,
     },
     database::surreal::client::SurrealClient,
-    models::user::User,
-    utils::password::hash_password,
+    dto::request::user::UserProfileRequest,
+    models::user::{AvatarUrls, NotificationPreferences, User, UserRole, UserStatus},
 };
 
 #[async_trait]
 pub trait UserRepository {
-    async fn change_password(&self, user_id: Thing, new_password: &str) -> AppResult<()>;
+    async fn change_password(&self, user_id: Thing, password_hash: &str) -> AppResult<()>;
+    async fn update_password_hash(&self, user_id: Thing, password_hash: &str) -> AppResult<()>;
+    async fn update_profile(
+        &self,
+        user_id: Thing,
+        profile: UserProfileRequest,
+    ) -> AppResult<Option<User>>;
+    async fn update_avatar(&self, user_id: Thing, avatar_urls: AvatarUrls) -> AppResult<User>;
+    async fn update_notification_preferences(
+        &self,
+        user_id: Thing,
+        notification_preferences: NotificationPreferences,
+    ) -> AppResult<User>;
+    async fn schedule_deletion(
+        &self,
+        user_id: Thing,
+        deletion_scheduled_at: DateTime<Utc>,
+    ) -> AppResult<()>;
+    async fn restore_account(&self, user_id: Thing, user_status: UserStatus) -> AppResult<()>;
+    async fn update_role(&self, user_id: Thing, role: UserRole) -> AppResult<User>;
+    async fn update_status(&self, user_id: Thing, user_status: UserStatus) -> AppResult<User>;
+    async fn find_users_due_for_purge(&self) -> AppResult<Vec<User>>;
+    async fn purge_user(&self, user_id: Thing) -> AppResult<()>;
 }
 
 #[async_trait]
 impl UserRepository for SurrealClient {
-    async fn change_password(&self, user_id: Thing, new_password: &str) -> AppResult<()> {
-        let (new_password, new_salt) = hash_password(new_password.to_string())?;
+    async fn change_password(&self, user_id: Thing, password_hash: &str) -> AppResult<()> {
         let sql = r#"
             UPDATE users SET password = $password,
-            salt = $salt
+            updated_at = time::now()
             WHERE
                 id = $user_id
         "#;
         let mut result = self
-            .client
-            .query(sql)
-            .bind(("password", new_password.to_string()))
-            .bind(("salt", new_salt))
-            .bind(("user_id", user_id))
-            .await
-            .map_err(ExternalError::from)?;
+            .execute(
+                self.client
+                    .query(sql)
+                    .bind(("password", password_hash.to_string()))
+                    .bind(("user_id", user_id)),
+            )
+            .await?;
         let user: Option<User> = result.take(0).map_err(ExternalError::from)?;
         match user {
             Some(_) => Ok(()),
             None => Err(UserErrorKind::UserNotFound.into()),
         }
     }
+    async fn update_password_hash(&self, user_id: Thing, password_hash: &str) -> AppResult<()> {
+        // Rehashing the same password is not a profile change, so `updated_at` is left alone
+        // and pending profile edits don't hit a version conflict.
+        let sql = r#"
+            UPDATE users SET password = $password
+            WHERE
+                id = $user_id
+        "#;
+        let mut result = self
+            .execute(
+                self.client
+                    .query(sql)
+                    .bind(("password", password_hash.to_string()))
+                    .bind(("user_id", user_id)),
+            )
+            .await?;
+        let user: Option<User> = result.take(0).map_err(ExternalError::from)?;
+        match user {
+            Some(_) => Ok(()),
+            None => Err(UserErrorKind::UserNotFound.into()),
+        }
+    }
+    async fn update_profile(
+        &self,
+        user_id: Thing,
+        profile: UserProfileRequest,
+    ) -> AppResult<Option<User>> {
+        // Only the fields that were sent are set, so a `null` one is cleared (bound as NONE)
+        // while a missing one is left alone.
+        let mut assignments = vec!["name = $name ?? name"];
+        if profile.locale.is_some() {
+            assignments.push("locale = $locale");
+        }
+        if profile.timezone.is_some() {
+            assignments.push("timezone = $timezone");
+        }
+        if profile.avatar_url.is_some() {
+            // A linked avatar replaces an uploaded one, so only one of them is ever set.
+            assignments.push("avatar_url = $avatar_url");
+            assignments.push("avatar_urls = NONE");
+        }
+        // The `updated_at` guard makes this a compare-and-swap: if another write bumped
+        // the version in between, no row matches and `None` is returned.
+        let sql = format!(
+            r#"
+            UPDATE users SET
+                {},
+                updated_at = time::now()
+            WHERE
+                id = $user_id AND
+                updated_at = $updated_at
+            RETURN AFTER
+        "#,
+            assignments.join(",\n                ")
+        );
+        let mut result = self
+            .execute(
+                self.client
+                    .query(sql)
+                    .bind(("name", profile.name))
+                    .bind(("locale", profile.locale.flatten()))
+                    .bind(("timezone", profile.timezone.flatten()))
+                    .bind(("avatar_url", profile.avatar_url.flatten()))
+                    .bind(("updated_at", Datetime::from(profile.updated_at)))
+                    .bind(("user_id", user_id)),
+            )
+            .await?;
+        let user: Option<User> = result.take(0).map_err(ExternalError::from)?;
+        Ok(user)
+    }
+    async fn update_avatar(&self, user_id: Thing, avatar_urls: AvatarUrls) -> AppResult<User> {
+        let sql = r#"
+            UPDATE users SET
+                avatar_url = NONE,
+                avatar_urls = $avatar_urls,
+                updated_at = time::now()
+            WHERE
+                id = $user_id
+            RETURN AFTER
+        "#;
+        let mut result = self
+            .execute(
+                self.client
+                    .query(sql)
+                    .bind(("avatar_urls", avatar_urls))
+                    .bind(("user_id", user_id)),
+            )
+            .await?;
+        let user: Option<User> = result.take(0).map_err(ExternalError::from)?;
+        match user {
+            Some(user) => Ok(user),
+            None => Err(UserErrorKind::UserNotFound.into()),
+        }
+    }
+    async fn update_notification_preferences(
+        &self,
+        user_id: Thing,
+        notification_preferences: NotificationPreferences,
+    ) -> AppResult<User> {
+        let sql = r#"
+            UPDATE users SET
+                notification_preferences = $notification_preferences,
+                updated_at = time::now()
+            WHERE
+                id = $user_id
+            RETURN AFTER
+        "#;
+        let mut result = self
+            .execute(
+                self.client
+                    .query(sql)
+                    .bind(("notification_preferences", notification_preferences))
+                    .bind(("user_id", user_id)),
+            )
+            .await?;
+        let user: Option<User> = result.take(0).map_err(ExternalError::from)?;
+        match user {
+            Some(user) => Ok(user),
+            None => Err(UserErrorKind::UserNotFound.into()),
+        }
+    }
+    async fn schedule_deletion(
+        &self,
+        user_id: Thing,
+        deletion_scheduled_at: DateTime<Utc>,
+    ) -> AppResult<()> {
+        let sql = r#"
+            UPDATE users SET
+                status = $status,
+                deletion_scheduled_at = $deletion_scheduled_at,
+                updated_at = time::now()
+            WHERE
+                id = $user_id
+        "#;
+        let mut result = self
+            .execute(
+                self.client
+                    .query(sql)
+                    .bind(("status", UserStatus::Deleted))
+                    .bind((
+                        "deletion_scheduled_at",
+                        Datetime::from(deletion_scheduled_at),
+                    ))
+                    .bind(("user_id", user_id)),
+            )
+            .await?;
+        let user: Option<User> = result.take(0).map_err(ExternalError::from)?;
+        match user {
+            Some(_) => Ok(()),
+            None => Err(UserErrorKind::UserNotFound.into()),
+        }
+    }
+    async fn restore_account(&self, user_id: Thing, user_status: UserStatus) -> AppResult<()> {
+        let sql = r#"
+            UPDATE users SET
+                status = $status,
+                deletion_scheduled_at = NONE,
+                updated_at = time::now()
+            WHERE
+                id = $user_id
+        "#;
+        let mut result = self
+            .execute(
+                self.client
+                    .query(sql)
+                    .bind(("status", user_status))
+                    .bind(("user_id", user_id)),
+            )
+            .await?;
+        let user: Option<User> = result.take(0).map_err(ExternalError::from)?;
+        match user {
+            Some(_) => Ok(()),
+            None => Err(UserErrorKind::UserNotFound.into()),
+        }
+    }
+    async fn update_role(&self, user_id: Thing, role: UserRole) -> AppResult<User> {
+        let sql = r#"
+            UPDATE users SET
+                role = $role,
+                updated_at = time::now()
+            WHERE
+                id = $user_id
+            RETURN AFTER
+        "#;
+        let mut result = self
+            .execute(
+                self.client
+                    .query(sql)
+                    .bind(("role", role))
+                    .bind(("user_id", user_id)),
+            )
+            .await?;
+        let user: Option<User> = result.take(0).map_err(ExternalError::from)?;
+        match user {
+            Some(user) => Ok(user),
+            None => Err(UserErrorKind::UserNotFound.into()),
+        }
+    }
+    async fn update_status(&self, user_id: Thing, user_status: UserStatus) -> AppResult<User> {
+        let sql = r#"
+            UPDATE users SET
+                status = $status,
+                updated_at = time::now()
+            WHERE
+                id = $user_id
+            RETURN AFTER
+        "#;
+        let mut result = self
+            .execute(
+                self.client
+                    .query(sql)
+                    .bind(("status", user_status))
+                    .bind(("user_id", user_id)),
+            )
+            .await?;
+        let user: Option<User> = result.take(0).map_err(ExternalError::from)?;
+        match user {
+            Some(user) => Ok(user),
+            None => Err(UserErrorKind::UserNotFound.into()),
+        }
+    }
+    async fn find_users_due_for_purge(&self) -> AppResult<Vec<User>> {
+        let sql = r#"
+            SELECT * FROM users
+            WHERE
+                status = $status AND
+                deletion_scheduled_at != NONE AND
+                deletion_scheduled_at <= time::now()
+        "#;
+        let mut result = self
+            .execute(self.client.query(sql).bind(("status", UserStatus::Deleted)))
+            .await?;
+        let users: Vec<User> = result.take(0).map_err(ExternalError::from)?;
+        Ok(users)
+    }
+    async fn purge_user(&self, user_id: Thing) -> AppResult<()> {
+        // Sessions, devices, email, password history and data exports are dropped outright. The
+        // user row itself is kept but stripped of PII, so audit entries and foreign keys still
+        // resolve.
+        let sql = r#"
+            BEGIN TRANSACTION;
+                DELETE refresh_tokens WHERE user_id = $user_id;
+                DELETE devices WHERE user_id = $user_id;
+                DELETE emails WHERE user_id = $user_id;
+                DELETE password_history WHERE user_id = $user_id;
+                DELETE data_exports WHERE user_id = $user_id;
+                UPDATE audit_log SET
+                    actor = "deleted-user",
+                    ip = NONE,
+                    user_agent = NONE,
+                    details = NONE
+                WHERE
+                    actor = $actor;
+                UPDATE users SET
+                    name = "deleted",
+                    email = $anonymized_email,
+                    password = "",
+                    salt = NONE,
+                    locale = NONE,
+                    timezone = NONE,
+                    avatar_url = NONE,
+                    avatar_urls = NONE,
+                    notification_preferences = NONE,
+                    deletion_scheduled_at = NONE,
+                    updated_at = time::now()
+                WHERE
+                    id = $user_id;
+            COMMIT TRANSACTION;
+        "#;
+        let anonymized_email = format!("deleted+{}@invalid", user_id.id.to_raw());
+        self.execute(
+            self.client
+                .query(sql)
+                .bind(("actor", user_id.to_string()))
+                .bind(("anonymized_email", anonymized_email))
+                .bind(("user_id", user_id)),
+        )
+        .await?
+        .check()
+        .map_err(ExternalError::from)?;
+        Ok(())
+    }
 }
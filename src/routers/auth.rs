@@ -1,16 +1,23 @@
 use std::sync::Arc;
 
-use axum::{Router, middleware, routing::post};
+use axum::{
+    Router, middleware,
+    routing::{get, post},
+};
 
 use crate::{
     core::state::AppState,
-    handlers::auth::{forget_password, login, logout, register, reset_password, verify_email},
+    handlers::auth::{
+        confirm_report_login, forget_password, issue_challenge, login, logout, register,
+        report_login, reset_password, restore_account, verify_email,
+    },
     middlewares::auth::{auth, role_check},
     models::user::UserRole,
 };
 
 pub fn auth_routers(app_state: Arc<AppState>) -> Router {
     let auth_routers = Router::new()
+        .route("/challenge", get(issue_challenge))
         .route("/register", post(register))
         .route("/login", post(login))
         .route(
@@ -24,6 +31,14 @@ pub fn auth_routers(app_state: Arc<AppState>) -> Router {
         .route("/verify-email", post(verify_email))
         .route("/forget-password", post(forget_password))
         .route("/reset-password", post(reset_password))
+        .route("/restore-account", post(restore_account))
+        // Opened from the login alert email, so it's authorized by the token alone. The link
+        // only shows a confirmation page: mail clients prefetch links, and a GET must not sign
+        // anything out.
+        .route(
+            "/not-me/{token}",
+            get(confirm_report_login).post(report_login),
+        )
         .with_state(app_state);
     Router::new().nest("/auth", auth_routers)
 }
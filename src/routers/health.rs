@@ -2,10 +2,16 @@ use std::sync::Arc;
 
 use axum::{Router, routing::get};
 
-use crate::{core::state::AppState, handlers::health::health_check};
+use crate::{
+    core::state::AppState,
+    handlers::health::{liveness, readiness},
+};
 
 pub fn health_router(app_state: Arc<AppState>) -> Router {
     Router::new()
-        .route("/health", get(health_check))
+        // Kept for existing monitors, same as `/health/ready`.
+        .route("/health", get(readiness))
+        .route("/health/live", get(liveness))
+        .route("/health/ready", get(readiness))
         .with_state(app_state)
 }
@@ -0,0 +1,11 @@
+use std::sync::Arc;
+
+use axum::{Router, routing::get};
+
+use crate::{core::state::AppState, handlers::metrics::metrics};
+
+pub fn metrics_router(app_state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/metrics", get(metrics))
+        .with_state(app_state)
+}
@@ -1,22 +1,32 @@
-use axum::Router;
+use axum::{Router, middleware};
 use std::sync::Arc;
 
 use crate::{
     core::state::AppState,
-    middlewares::cors::cors,
-    routers::{auth::auth_routers, health::health_router, user::user_routers},
+    middlewares::{cors::cors, metrics::track_metrics},
+    routers::{
+        auth::auth_routers, health::health_router, metrics::metrics_router,
+        uploads::uploads_router, user::user_routers,
+    },
 };
 
 pub mod auth;
 pub mod health;
+pub mod metrics;
+pub mod uploads;
 pub mod user;
 
 pub fn api_routers(app_state: Arc<AppState>) -> Router {
-    let frontend_address = app_state.config.frontend_server.frontend_address.clone();
+    let config = app_state.config.clone();
     let all_router = Router::new()
         .merge(health_router(app_state.clone()))
         .merge(auth_routers(app_state.clone()))
-        .merge(user_routers(app_state))
-        .layer(cors(frontend_address));
-    Router::new().nest("/api/v1", all_router)
+        .merge(user_routers(app_state.clone()))
+        .merge(uploads_router(app_state.clone()))
+        .layer(cors(config));
+    // Scrapers expect `/metrics` at the root, next to the API rather than under it.
+    Router::new()
+        .nest("/api/v1", all_router)
+        .merge(metrics_router(app_state))
+        .layer(middleware::from_fn(track_metrics))
 }
@@ -0,0 +1,19 @@
+use std::{path::Path, sync::Arc};
+
+use axum::Router;
+use tower_http::services::ServeDir;
+
+use crate::{config::storage::StorageBackend, core::state::AppState};
+
+pub fn uploads_router(app_state: Arc<AppState>) -> Router {
+    match app_state.config.load().storage.storage_backend {
+        // Only avatars are public, anything else in the storage root stays unreachable.
+        StorageBackend::Local => Router::new().nest_service(
+            "/uploads/avatars",
+            ServeDir::new(
+                Path::new(&app_state.config.load().storage.storage_local_path).join("avatars"),
+            ),
+        ),
+        StorageBackend::S3 => Router::new(),
+    }
+}
@@ -1,25 +1,50 @@
 use std::sync::Arc;
 
 use axum::{
-    Router, middleware,
-    routing::{get, post},
+    Router,
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{get, post, put},
 };
 
 use crate::{
     core::state::AppState,
-    handlers::user::{change_password, get_me},
+    handlers::user::{
+        change_password, delete_account, download_data_export, get_me,
+        get_notification_preferences, list_devices, list_sessions, request_data_export, update_me,
+        update_notification_preferences, upload_avatar,
+    },
     middlewares::auth::{auth, role_check},
     models::user::UserRole,
 };
 
 pub fn user_routers(app_state: Arc<AppState>) -> Router {
+    // Leave headroom above the image limit for the multipart boundaries and headers.
+    let avatar_body_limit = app_state.config.load().storage.avatar_max_bytes + 64 * 1024;
     let user_router = Router::new()
-        .route("/me", get(get_me))
+        .route("/me", get(get_me).patch(update_me).delete(delete_account))
+        .route(
+            "/me/notifications",
+            get(get_notification_preferences).patch(update_notification_preferences),
+        )
+        .route("/devices", get(list_devices))
+        .route("/sessions", get(list_sessions))
         .route("/change-password", post(change_password))
+        .route(
+            "/avatar",
+            put(upload_avatar).layer(DefaultBodyLimit::max(avatar_body_limit)),
+        )
+        .route("/export", post(request_data_export))
         .layer(middleware::from_fn(|req, next| {
             role_check(req, next, vec![UserRole::Admin, UserRole::User])
         }))
         .layer(middleware::from_fn_with_state(app_state.clone(), auth))
+        .with_state(app_state.clone());
+    // Download links are opened from an email, so they're authorized by the token alone.
+    let export_router = Router::new()
+        .route("/exports/{token}", get(download_data_export))
         .with_state(app_state);
-    Router::new().nest("/user", user_router)
+    Router::new()
+        .nest("/user", user_router)
+        .merge(export_router)
 }
@@ -1,15 +1,15 @@
 use std::sync::Arc;
 
-use crate::{core::config::AppConfig, database::client::DBClient};
+use crate::{core::config::SharedConfig, database::client::DBClient};
 
 #[derive(Debug)]
 pub struct AdminService {
-    pub config: Arc<AppConfig>,
+    pub config: SharedConfig,
     pub db_client: Arc<DBClient>,
 }
 
 impl AdminService {
-    pub fn new(config: Arc<AppConfig>, db_client: Arc<DBClient>) -> Self {
+    pub fn new(config: SharedConfig, db_client: Arc<DBClient>) -> Self {
         Self { config, db_client }
     }
 }
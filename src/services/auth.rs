@@ -1,3 +1,4 @@
+use chrono::Utc;
 use resend_rs::Resend;
 use std::{net::SocketAddr, sync::Arc};
 
@@ -14,82 +15,199 @@ use tracing::{error, info};
 
 use crate::{
     core::{
-        config::AppConfig,
-        error::{email::EmailErrorKind, external::ExternalError, user::UserErrorKind},
+        config::{AppConfig, SharedConfig},
+        error::{
+            device::DeviceErrorKind, email::EmailErrorKind, external::ExternalError,
+            user::UserErrorKind,
+        },
         response::AppResponse,
         result::AppResult,
     },
     database::client::DBClient,
     dto::{
         request::auth::{
-            ForgetPasswordRequest, LoginRequest, RegisterRequest, ResetPasswordRequest,
-            VerifyUserRequest,
+            ChallengeSolution, ForgetPasswordRequest, LoginRequest, RegisterRequest,
+            ResetPasswordRequest, RestoreAccountRequest, VerifyUserRequest,
         },
         response::auth::{LoginResponse, VerifyUserResponse},
     },
     models::{
+        audit_log::Detail,
+        device::{Device, LoginAlertReason},
         email::EmailType,
-        user::{User, UserStatus},
+        geo_location::GeoLocation,
+        user::{User, UserRole, UserStatus},
     },
-    repositories::{
-        redis::auth::AuthCacheRepository,
-        surreal::{
-            auth::AuthRepository, device::DeviceRepository, refresh_token::RefreshTokenRepository,
-        },
+    services::{
+        challenge::ChallengeService,
+        user::{ensure_password_not_reused, remember_previous_password},
     },
     templates::{
+        account_exists_email_html::ACCOUNT_EXISTS_EMAIL_HTML,
+        login_alert_email_html::LOGIN_ALERT_EMAIL_HTML,
         reset_password_email_html::RESET_PASSWORD_EMAIL_HTML,
         verification_email_html::VERIFICATION_EMAIL_HTML,
     },
     utils::{
-        device::parse_user_agent_detailed,
-        mail::send_mail,
-        password::compare_hashed_password,
+        device::{
+            DEVICE_COOKIE_NAME, ParsedUserAgent, UserAgentParser, sign_device_id,
+            verify_device_cookie,
+        },
+        geoip::GeoIpResolver,
+        ip::same_network,
+        mail::{escape_html, send_mail},
+        metrics::{LoginOutcome, METRICS},
+        password::PasswordHasher,
+        password_policy::PasswordPolicy,
+        risk::{LoginRiskContext, RiskAssessment, RiskDecision, RiskEngine},
         token::{generate_access_token, generate_email_token, generate_refresh_token},
     },
     validation::auth::{
         validate_forget_password_payload, validate_login_payload, validate_register_payload,
-        validate_reset_password_payload, validate_verify_user_payload,
+        validate_reset_password_payload, validate_restore_account_payload,
+        validate_verify_user_payload,
     },
 };
 
+/// How long the "this wasn't me" link of a login alert stays valid.
+const LOGIN_ALERT_TOKEN_TTL_SECONDS: u64 = 7 * 24 * 3600;
+/// How long a confirmed emailed code lets the next login on that device skip the step-up.
+const STEP_UP_PASSED_TTL_SECONDS: u64 = 900;
+
 #[derive(Debug)]
 pub struct AuthService {
-    pub config: Arc<AppConfig>,
+    pub config: SharedConfig,
     pub db_client: Arc<DBClient>,
     pub resend: Arc<Resend>,
+    pub password_policy: Arc<PasswordPolicy>,
+    pub password_hasher: Arc<PasswordHasher>,
+    pub user_agent_parser: Arc<UserAgentParser>,
+    pub geoip: Arc<GeoIpResolver>,
+    pub risk_engine: Arc<RiskEngine>,
+    pub challenge: ChallengeService,
 }
 
 impl AuthService {
-    pub fn new(config: Arc<AppConfig>, db_client: Arc<DBClient>, resend: Arc<Resend>) -> Self {
+    pub fn new(
+        config: SharedConfig,
+        db_client: Arc<DBClient>,
+        resend: Arc<Resend>,
+        password_policy: Arc<PasswordPolicy>,
+        password_hasher: Arc<PasswordHasher>,
+        user_agent_parser: Arc<UserAgentParser>,
+        geoip: Arc<GeoIpResolver>,
+    ) -> Self {
+        let risk_engine = Arc::new(RiskEngine::new(&config.load().risk));
+        let challenge = ChallengeService::new(config.clone(), db_client.clone());
         Self {
             config,
             db_client,
             resend,
+            password_policy,
+            password_hasher,
+            user_agent_parser,
+            geoip,
+            risk_engine,
+            challenge,
         }
     }
-    pub async fn register(&self, payload: RegisterRequest) -> AppResult<impl IntoResponse + use<>> {
+    fn device_cookie_secret(&self) -> Vec<u8> {
+        let config = self.config.load();
+        config
+            .device
+            .device_cookie_secret
+            .as_deref()
+            .unwrap_or(&config.jwt_config.jwt_secret)
+            .as_bytes()
+            .to_vec()
+    }
+    fn device_cookie(&self, device: &Device) -> Cookie<'static> {
+        Cookie::build((
+            DEVICE_COOKIE_NAME,
+            sign_device_id(&device.id, &self.device_cookie_secret()),
+        ))
+        .path("/")
+        .http_only(true)
+        .secure(true)
+        .same_site(SameSite::Strict)
+        .max_age(Duration::days(
+            self.config.load().device.device_cookie_max_age_days,
+        ))
+        .build()
+    }
+    pub async fn register(
+        &self,
+        addr: SocketAddr,
+        payload: RegisterRequest,
+    ) -> AppResult<impl IntoResponse + use<>> {
         validate_register_payload(&payload)?;
-        if self
+        if self.config.load().challenge.challenge_on_register {
+            self.challenge
+                .require_challenge(payload.challenge.as_ref(), &addr.ip().to_string())
+                .await?;
+        }
+        self.password_policy
+            .check(&payload.password, &[&payload.name, &payload.email])
+            .await?;
+        if let Some(existing_user) = self
             .db_client
-            .surreal_client
+            .database
             .find_user_by_email(&payload.email)
             .await?
-            .is_some()
         {
             error!(
                 "❌ Failed: user already exists with email {}",
                 payload.email
             );
-            return Err(UserErrorKind::UserAlreadyExists.into());
+            if !self.config.load().security.anti_enumeration {
+                return Err(UserErrorKind::UserAlreadyExists.into());
+            }
+            // Spend the same hashing time as a real registration and tell the owner by email
+            // instead of the caller.
+            self.password_hasher.dummy_verify(&payload.password).await;
+            let config = self.config.load_full();
+            let resend = self.resend.clone();
+            tokio::spawn(async move {
+                let html = ACCOUNT_EXISTS_EMAIL_HTML
+                    .replace("{{username}}", &escape_html(&existing_user.name));
+                if let Err(e) = send_mail(
+                    &resend,
+                    &config.mail_server.from_email,
+                    vec![&existing_user.email],
+                    "Account already exists",
+                    &html,
+                )
+                .await
+                {
+                    error!("❌ Failed to send account exists email: {}", e);
+                }
+            });
+            return Ok(AppResponse::<()>::success(
+                StatusCode::OK.as_u16(),
+                "register success",
+                StatusCode::OK.canonical_reason().unwrap_or("OK"),
+                None,
+            ));
         }
+        let password_hash = self
+            .password_hasher
+            .hash_password(&payload.password)
+            .await?;
         match self
             .db_client
-            .surreal_client
-            .create_user(&payload.name, &payload.email, &payload.password)
+            .database
+            .create_user(
+                &payload.name,
+                &payload.email,
+                &password_hash,
+                UserRole::User,
+            )
             .await
         {
-            Ok(_) => info!("Create user successfully"),
+            Ok(_) => {
+                info!("Create user successfully");
+                METRICS.record_registration();
+            }
             Err(_) => {
                 error!("Create user failed");
                 return Err(UserErrorKind::CreateUserFailed.into());
@@ -106,41 +224,100 @@ impl AuthService {
         &self,
         headers: HeaderMap,
         jar: CookieJar,
+        addr: SocketAddr,
         payload: LoginRequest,
     ) -> AppResult<impl IntoResponse + use<>> {
         validate_login_payload(&payload)?;
+        let anti_enumeration = self.config.load().security.anti_enumeration;
+        let ip = addr.ip().to_string();
+        let challenged = self
+            .require_password_challenge(&payload.email, payload.challenge.as_ref(), &ip)
+            .await?;
         let user = match self
             .db_client
-            .surreal_client
+            .database
             .find_user_by_email(&payload.email)
             .await?
         {
             Some(user) => user,
-            None => return Err(UserErrorKind::UserNotFound.into()),
+            None => {
+                self.record_login_failure(&payload.email).await;
+                METRICS.record_login(LoginOutcome::Failed);
+                if anti_enumeration {
+                    self.password_hasher.dummy_verify(&payload.password).await;
+                    return Err(UserErrorKind::InvalidCredentials.into());
+                }
+                return Err(UserErrorKind::UserNotFound.into());
+            }
         };
-        if !compare_hashed_password(&payload.password, &user.password)? {
+        if !self
+            .password_hasher
+            .verify_password(&payload.password, &user.password)
+            .await?
+        {
+            // Failed attempts feed the risk score of the next successful login.
+            self.record_login_failure(&payload.email).await;
+            METRICS.record_login(LoginOutcome::Failed);
+            if anti_enumeration {
+                return Err(UserErrorKind::InvalidCredentials.into());
+            }
             return Err(UserErrorKind::WrongPassword.into());
         }
+        if self.password_hasher.needs_rehash(&user.password) {
+            // The login must not fail because the upgrade did; the old hash keeps working.
+            match self.password_hasher.hash_password(&payload.password).await {
+                Ok(password_hash) => {
+                    if let Err(e) = self
+                        .db_client
+                        .database
+                        .update_password_hash(user.id.clone(), &password_hash)
+                        .await
+                    {
+                        error!("❌ Failed to save rehashed password: {}", e);
+                    }
+                }
+                Err(e) => error!("❌ Failed to rehash password: {}", e),
+            }
+        }
+        if user.status == UserStatus::Deleted {
+            METRICS.record_login(LoginOutcome::DeletionScheduled);
+            return Ok((
+                HeaderMap::new(),
+                jar,
+                AppResponse::<LoginResponse>::success(
+                    StatusCode::OK.as_u16(),
+                    "Your account is scheduled for deletion, restore it to continue",
+                    StatusCode::OK.canonical_reason().unwrap_or("OK"),
+                    Some(LoginResponse {
+                        device: None,
+                        need_verification: false,
+                        deletion_scheduled_at: user.deletion_scheduled_at,
+                    }),
+                ),
+            ));
+        }
         if !user.is_verified {
             let email_token = generate_email_token();
             // Use Redis to store verification token (e.g., for 30 minutes)
             self.db_client
-                .redis_client
+                .cache
                 .set_email_token(EmailType::Verification, &email_token, &user.id, 1800)
                 .await?;
 
             let html = VERIFICATION_EMAIL_HTML
-                .replace("{{username}}", &user.name)
+                .replace("{{username}}", &escape_html(&user.name))
                 .replace("{{email_token}}", &email_token);
             let _email = send_mail(
                 &self.resend,
-                &self.config.mail_server.from_email,
+                &self.config.load().mail_server.from_email,
                 vec![&user.email],
                 "Verification",
                 &html,
             )
             .await
             .map_err(ExternalError::from)?;
+            METRICS.record_verification_email_sent();
+            METRICS.record_login(LoginOutcome::VerificationRequired);
             let response_headers = HeaderMap::new();
             return Ok((
                 response_headers,
@@ -152,6 +329,7 @@ impl AuthService {
                     Some(LoginResponse {
                         device: None,
                         need_verification: true,
+                        deletion_scheduled_at: None,
                     }),
                 ),
             ));
@@ -160,37 +338,93 @@ impl AuthService {
             Some(user_agent) => user_agent,
             None => return Err(UserErrorKind::MissingUserAgent.into()),
         };
-        let (user_agent, os, device) = parse_user_agent_detailed(user_agent_str);
+        let parsed_user_agent = self.user_agent_parser.parse(user_agent_str);
         let trusted_devices = self
             .db_client
-            .surreal_client
+            .database
             .find_trusted_devices_by_user_id(user.id.clone())
             .await?;
-        let found_device = trusted_devices
-            .into_iter()
-            .find(|d| d.user_agent == user_agent && d.os == os && d.device == device);
+        let found_device = self.find_known_device(&jar, &parsed_user_agent, trusted_devices);
+        let location = self.geoip.lookup(&ip);
+        let assessment = self
+            .assess_login_risk(&user, found_device.is_some(), &ip, &location)
+            .await?;
+        self.record_risk_decision(
+            &user,
+            found_device.as_ref(),
+            &ip,
+            &location,
+            user_agent_str,
+            &assessment,
+        )
+        .await;
+        if assessment.decision == RiskDecision::Block {
+            METRICS.record_login(LoginOutcome::Blocked);
+            return Err(UserErrorKind::LoginBlocked.into());
+        }
+        if assessment.decision == RiskDecision::StepUp && !challenged {
+            self.challenge
+                .require_challenge(payload.challenge.as_ref(), &ip)
+                .await?;
+        }
+        // A risky login on a known device goes through the same emailed code as a new device,
+        // unless that code was just confirmed for this device.
+        let step_up_required = match &found_device {
+            Some(device) if assessment.decision == RiskDecision::StepUp => {
+                !self
+                    .db_client
+                    .cache
+                    .use_step_up_passed(&user.id, &device.id)
+                    .await?
+            }
+            _ => false,
+        };
         let device = match found_device {
-            Some(device) => device,
-            None => {
+            Some(device) if !step_up_required => {
+                let mut reasons = Vec::new();
+                let previous_country = device.location.as_ref().and_then(|l| l.country.as_ref());
+                let country = location.as_ref().and_then(|l| l.country.as_ref());
+                if let (Some(previous_country), Some(country)) = (previous_country, country)
+                    && previous_country != country
+                {
+                    reasons.push(LoginAlertReason::NewCountry);
+                }
+                if !same_network(&device.ip, &ip) {
+                    reasons.push(LoginAlertReason::NewIpRange);
+                }
+                let device = self
+                    .db_client
+                    .database
+                    .record_device_login(device.id.clone(), &parsed_user_agent, ip, location)
+                    .await?;
+                if !reasons.is_empty() {
+                    self.alert_login(&user, &device, reasons, user_agent_str)
+                        .await;
+                }
+                device
+            }
+            _ => {
                 let email_token = generate_email_token();
                 // Use Redis to store verification token
                 self.db_client
-                    .redis_client
+                    .cache
                     .set_email_token(EmailType::Verification, &email_token, &user.id, 1800)
                     .await?;
 
                 let html = VERIFICATION_EMAIL_HTML
-                    .replace("{{username}}", &user.name)
+                    .replace("{{username}}", &escape_html(&user.name))
                     .replace("{{email_token}}", &email_token);
                 let _email = send_mail(
                     &self.resend,
-                    &self.config.mail_server.from_email,
+                    &self.config.load().mail_server.from_email,
                     vec![&user.email],
                     "Verification",
                     &html,
                 )
                 .await
                 .map_err(ExternalError::from)?;
+                METRICS.record_verification_email_sent();
+                METRICS.record_login(LoginOutcome::VerificationRequired);
                 let response_headers = HeaderMap::new();
                 return Ok((
                     response_headers,
@@ -202,14 +436,18 @@ impl AuthService {
                         Some(LoginResponse {
                             device: None,
                             need_verification: true,
+                            deletion_scheduled_at: None,
                         }),
                     ),
                 ));
             }
         };
+        if let Err(e) = self.db_client.cache.clear_login_failures(&user.email).await {
+            error!("❌ Failed to clear login failures: {}", e);
+        }
         let refresh_token_value = match self
             .db_client
-            .surreal_client
+            .database
             .find_refresh_token_by_user_and_device(user.id.clone(), device.id.clone())
             .await?
         {
@@ -217,7 +455,7 @@ impl AuthService {
             None => {
                 let new_token_value = generate_refresh_token();
                 self.db_client
-                    .surreal_client
+                    .database
                     .create_refresh_token(user.id.clone(), device.id.clone(), &new_token_value)
                     .await?;
                 new_token_value
@@ -229,17 +467,20 @@ impl AuthService {
             .same_site(SameSite::Strict)
             .max_age(Duration::days(7))
             .build();
-        let jar = jar.add(refresh_token_cookie);
+        let jar = jar
+            .add(refresh_token_cookie)
+            .add(self.device_cookie(&device));
         let access_token = generate_access_token(
             user.id.clone(),
-            self.config.jwt_config.jwt_secret.as_bytes(),
-            self.config.jwt_config.jwt_expires_in_seconds,
+            self.config.load().jwt_config.jwt_secret.as_bytes(),
+            self.config.load().jwt_config.jwt_expires_in_seconds,
         )?;
         let mut response_headers = HeaderMap::new();
         response_headers.insert(
             AUTHORIZATION,
             format!("Bearer {}", access_token).parse().unwrap(),
         );
+        METRICS.record_login(LoginOutcome::Success);
         Ok((
             response_headers,
             jar,
@@ -250,6 +491,7 @@ impl AuthService {
                 Some(LoginResponse {
                     device: Some(device),
                     need_verification: false,
+                    deletion_scheduled_at: None,
                 }),
             ),
         ))
@@ -259,7 +501,7 @@ impl AuthService {
             let refresh_token = cookie.value().to_string();
             match self
                 .db_client
-                .surreal_client
+                .database
                 .delete_refresh_token(user.id.clone(), &refresh_token)
                 .await
             {
@@ -291,13 +533,14 @@ impl AuthService {
     pub async fn verify_email(
         &self,
         headers: HeaderMap,
+        jar: CookieJar,
         addr: SocketAddr,
         payload: VerifyUserRequest,
     ) -> AppResult<impl IntoResponse + use<>> {
         validate_verify_user_payload(&payload)?;
         let user_id = match self
             .db_client
-            .redis_client
+            .cache
             .use_email_token(EmailType::Verification, &payload.email_token)
             .await?
         {
@@ -306,7 +549,7 @@ impl AuthService {
         };
         let user = self
             .db_client
-            .surreal_client
+            .database
             .find_user_by_id(user_id.clone())
             .await?
             .ok_or(UserErrorKind::UserNotFound)?;
@@ -315,64 +558,413 @@ impl AuthService {
             return Err(UserErrorKind::Unauthorized.into());
         }
         self.db_client
-            .surreal_client
+            .database
             .user_verified(user.id.clone(), UserStatus::Active)
             .await?;
-        self.db_client.redis_client.delete_user(&user.id).await?;
+        self.db_client.cache.delete_user(&user.id).await?;
         let user_agent_str = match headers.get("User-Agent").and_then(|ua| ua.to_str().ok()) {
             Some(user_agent) => user_agent,
             None => return Err(UserErrorKind::MissingUserAgent.into()),
         };
-        let (user_agent, os, device) = parse_user_agent_detailed(user_agent_str);
+        let parsed_user_agent = self.user_agent_parser.parse(user_agent_str);
+        // The first device is the one the account was created on, there is nothing to report.
+        let known_devices = self
+            .db_client
+            .database
+            .find_devices_by_user_id(user.id.clone())
+            .await?;
+        // The code also answers risk step-ups on devices that are already trusted.
+        let trusted_devices = known_devices
+            .iter()
+            .filter(|d| d.is_trusted)
+            .cloned()
+            .collect();
+        if let Some(device) = self.find_known_device(&jar, &parsed_user_agent, trusted_devices) {
+            let ip = addr.ip().to_string();
+            let location = self.geoip.lookup(&ip);
+            let device = self
+                .db_client
+                .database
+                .record_device_login(device.id.clone(), &parsed_user_agent, ip, location)
+                .await?;
+            self.db_client
+                .cache
+                .set_step_up_passed(&user.id, &device.id, STEP_UP_PASSED_TTL_SECONDS)
+                .await?;
+            let jar = jar.add(self.device_cookie(&device));
+            return Ok((
+                jar,
+                AppResponse::<VerifyUserResponse>::success(
+                    StatusCode::OK.as_u16(),
+                    "Verify your login successfully",
+                    StatusCode::OK.canonical_reason().unwrap_or("OK"),
+                    Some(VerifyUserResponse { device }),
+                ),
+            ));
+        }
         let new_device = self
             .db_client
-            .surreal_client
+            .database
             .create_device(
                 user.id.clone(),
-                user_agent,
-                os,
-                device,
+                &parsed_user_agent,
                 addr.ip().to_string(),
+                self.geoip.lookup(&addr.ip().to_string()),
+            )
+            .await?;
+        if !known_devices.is_empty() {
+            self.alert_login(
+                &user,
+                &new_device,
+                vec![LoginAlertReason::NewDevice],
+                user_agent_str,
             )
+            .await;
+        }
+        self.db_client
+            .cache
+            .set_step_up_passed(&user.id, &new_device.id, STEP_UP_PASSED_TTL_SECONDS)
             .await?;
-        Ok(AppResponse::<VerifyUserResponse>::success(
+        let jar = jar.add(self.device_cookie(&new_device));
+        Ok((
+            jar,
+            AppResponse::<VerifyUserResponse>::success(
+                StatusCode::OK.as_u16(),
+                "Verify your account successfully",
+                StatusCode::OK.canonical_reason().unwrap_or("OK"),
+                Some(VerifyUserResponse { device: new_device }),
+            ),
+        ))
+    }
+    pub async fn report_login(&self, token: String) -> AppResult<impl IntoResponse + use<>> {
+        let device_id = match self
+            .db_client
+            .cache
+            .use_email_token(EmailType::LoginAlert, &token)
+            .await?
+        {
+            Some(device_id) => device_id,
+            None => return Err(EmailErrorKind::InvalidToken.into()),
+        };
+        let device = match self.db_client.database.find_device_by_id(device_id).await? {
+            Some(device) => device,
+            None => return Err(DeviceErrorKind::DeviceNotFound.into()),
+        };
+        self.db_client
+            .database
+            .delete_refresh_tokens_by_device_id(device.id.clone())
+            .await?;
+        if device.is_trusted {
+            self.db_client
+                .database
+                .distrust_device(device.id.clone(), device.user_id.clone())
+                .await?;
+        }
+        self.db_client.cache.delete_user(&device.user_id).await?;
+        if let Err(e) = self
+            .db_client
+            .database
+            .create_audit_log(
+                device.user_id.to_string(),
+                "login_reported",
+                "success",
+                Some(device.ip.clone()),
+                None,
+                Some(Detail {
+                    device_id: Some(device.id.clone()),
+                    device: Some(device.device.clone()),
+                    location: device.location.clone(),
+                    reasons: Vec::new(),
+                    risk_score: None,
+                }),
+            )
+            .await
+        {
+            error!("❌ Failed to write login report audit log: {}", e);
+        }
+        Ok(AppResponse::<()>::success(
             StatusCode::OK.as_u16(),
-            "Verify your account successfully",
+            "The device has been signed out and is no longer trusted, please change your password",
             StatusCode::OK.canonical_reason().unwrap_or("OK"),
-            Some(VerifyUserResponse { device: new_device }),
+            None,
         ))
     }
+    /// Picks the trusted device a request comes from. The signed device cookie identifies it
+    /// exactly, fuzzy matching on the parsed user agent covers browsers that lost the cookie.
+    fn find_known_device(
+        &self,
+        jar: &CookieJar,
+        parsed_user_agent: &ParsedUserAgent,
+        trusted_devices: Vec<Device>,
+    ) -> Option<Device> {
+        let cookie_device_id = jar
+            .get(DEVICE_COOKIE_NAME)
+            .and_then(|cookie| verify_device_cookie(cookie.value(), &self.device_cookie_secret()));
+        match cookie_device_id.and_then(|device_id| {
+            trusted_devices
+                .iter()
+                .find(|d| d.id.id.to_raw() == device_id)
+                .cloned()
+        }) {
+            Some(device) => Some(device),
+            None => trusted_devices
+                .into_iter()
+                .find(|d| parsed_user_agent.matches_device(d)),
+        }
+    }
+    /// Asks for a challenge before a password is checked against an account when the IP is
+    /// blacklisted or the email has failed too often. It runs before the user lookup, so the
+    /// challenge shows up the same way for unknown emails. Returns whether a challenge was
+    /// solved, so a risky login doesn't ask for a second one.
+    async fn require_password_challenge(
+        &self,
+        email: &str,
+        challenge: Option<&ChallengeSolution>,
+        ip: &str,
+    ) -> AppResult<bool> {
+        let ip_blacklisted = self
+            .db_client
+            .database
+            .find_active_ip_blacklist(ip)
+            .await?
+            .is_some();
+        if ip_blacklisted
+            || self.db_client.cache.get_login_failures(email).await?
+                >= self.config.load().challenge.challenge_login_failures
+        {
+            self.challenge.require_challenge(challenge, ip).await?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+    async fn record_login_failure(&self, email: &str) {
+        if let Err(e) = self
+            .db_client
+            .cache
+            .record_login_failure(
+                email,
+                self.config.load().risk.risk_failed_attempts_window_seconds,
+            )
+            .await
+        {
+            error!("❌ Failed to record login failure: {}", e);
+        }
+    }
+    async fn assess_login_risk(
+        &self,
+        user: &User,
+        known_device: bool,
+        ip: &str,
+        location: &Option<GeoLocation>,
+    ) -> AppResult<RiskAssessment> {
+        let (ip_blacklist, failed_attempts, devices) = tokio::try_join!(
+            self.db_client.database.find_active_ip_blacklist(ip),
+            self.db_client.cache.get_login_failures(&user.email),
+            self.db_client
+                .database
+                .find_devices_by_user_id(user.id.clone()),
+        )?;
+        let previous_login = devices
+            .into_iter()
+            .filter_map(|d| d.location.map(|location| (location, d.last_login_at)))
+            .max_by_key(|(_, last_login_at)| *last_login_at);
+        Ok(self.risk_engine.assess(&LoginRiskContext {
+            now: Utc::now(),
+            known_device,
+            ip_blacklisted: ip_blacklist.is_some(),
+            location: location.clone(),
+            previous_login,
+            failed_attempts,
+            timezone: user.timezone.clone(),
+        }))
+    }
+    async fn record_risk_decision(
+        &self,
+        user: &User,
+        device: Option<&Device>,
+        ip: &str,
+        location: &Option<GeoLocation>,
+        user_agent: &str,
+        assessment: &RiskAssessment,
+    ) {
+        if let Err(e) = self
+            .db_client
+            .database
+            .create_audit_log(
+                user.id.to_string(),
+                "login_risk",
+                &assessment.decision.to_string(),
+                Some(ip.to_string()),
+                Some(user_agent.to_string()),
+                Some(Detail {
+                    device_id: device.map(|d| d.id.clone()),
+                    device: device.map(|d| d.device.clone()),
+                    location: location.clone(),
+                    reasons: assessment
+                        .factors
+                        .iter()
+                        .map(|factor| factor.name.to_string())
+                        .collect(),
+                    risk_score: Some(assessment.score),
+                }),
+            )
+            .await
+        {
+            error!("❌ Failed to write login risk audit log: {}", e);
+        }
+    }
+    /// Records a login alert in the audit log and, unless the user opted out, emails them the
+    /// details with a link to revoke the device. Failures are logged, never returned.
+    async fn alert_login(
+        &self,
+        user: &User,
+        device: &Device,
+        reasons: Vec<LoginAlertReason>,
+        user_agent: &str,
+    ) {
+        let reasons_text = reasons.iter().map(ToString::to_string).collect::<Vec<_>>();
+        if let Err(e) = self
+            .db_client
+            .database
+            .create_audit_log(
+                user.id.to_string(),
+                "login_alert",
+                "warning",
+                Some(device.ip.clone()),
+                Some(user_agent.to_string()),
+                Some(Detail {
+                    device_id: Some(device.id.clone()),
+                    device: Some(device.device.clone()),
+                    location: device.location.clone(),
+                    reasons: reasons_text.clone(),
+                    risk_score: None,
+                }),
+            )
+            .await
+        {
+            error!("❌ Failed to write login alert audit log: {}", e);
+        }
+        let preferences = user.notification_preferences.clone().unwrap_or_default();
+        let wanted = reasons.iter().any(|reason| match reason {
+            LoginAlertReason::NewDevice => preferences.new_device,
+            LoginAlertReason::NewIpRange | LoginAlertReason::NewCountry => {
+                preferences.suspicious_login
+            }
+        });
+        if !wanted {
+            return;
+        }
+        let token = generate_email_token();
+        if let Err(e) = self
+            .db_client
+            .cache
+            .set_email_token(
+                EmailType::LoginAlert,
+                &token,
+                &device.id,
+                LOGIN_ALERT_TOKEN_TTL_SECONDS,
+            )
+            .await
+        {
+            error!("❌ Failed to store login alert token: {}", e);
+            return;
+        }
+        let not_me_url = format!(
+            "{}/api/v1/auth/not-me/{}",
+            self.config
+                .load()
+                .backend_server
+                .backend_address
+                .trim_end_matches('/'),
+            token
+        );
+        // The device fields come from the client's User-Agent, so every value is escaped.
+        let html = LOGIN_ALERT_EMAIL_HTML
+            .replace("{{username}}", &escape_html(&user.name))
+            .replace("{{reason}}", &escape_html(&reasons_text.join(" and ")))
+            .replace(
+                "{{device}}",
+                &escape_html(&format!(
+                    "{} on {} ({})",
+                    device.user_agent, device.os, device.device
+                )),
+            )
+            .replace(
+                "{{ip}}",
+                &escape_html(&match device.location.as_ref().and_then(|l| l.label()) {
+                    Some(place) => format!("{} ({})", device.ip, place),
+                    None => device.ip.clone(),
+                }),
+            )
+            .replace(
+                "{{time}}",
+                &device
+                    .last_login_at
+                    .format("%Y-%m-%d %H:%M UTC")
+                    .to_string(),
+            )
+            .replace("{{not_me_url}}", &escape_html(&not_me_url));
+        let resend = self.resend.clone();
+        let from_email = self.config.load().mail_server.from_email.clone();
+        let email = user.email.clone();
+        tokio::spawn(async move {
+            if let Err(e) = send_mail(
+                &resend,
+                &from_email,
+                vec![&email],
+                "New sign-in to your account",
+                &html,
+            )
+            .await
+            {
+                error!("❌ Failed to send login alert email: {}", e);
+            }
+        });
+    }
     pub async fn forget_password(
         &self,
+        addr: SocketAddr,
         payload: ForgetPasswordRequest,
     ) -> AppResult<impl IntoResponse + use<>> {
         validate_forget_password_payload(&payload)?;
-        let user = match self
+        if self.config.load().challenge.challenge_on_forget_password {
+            self.challenge
+                .require_challenge(payload.challenge.as_ref(), &addr.ip().to_string())
+                .await?;
+        }
+        let user = self
             .db_client
-            .surreal_client
+            .database
             .find_user_by_email(&payload.email)
-            .await?
-        {
+            .await?;
+        if self.config.load().security.anti_enumeration {
+            // The email is sent in the background, so known and unknown emails return at once
+            // with the same response.
+            if let Some(user) = user {
+                let config = self.config.load_full();
+                let db_client = self.db_client.clone();
+                let resend = self.resend.clone();
+                tokio::spawn(async move {
+                    if let Err(e) =
+                        send_reset_password_email(&config, &db_client, &resend, &user).await
+                    {
+                        error!("❌ Failed to send reset password email: {}", e);
+                    }
+                });
+            }
+            return Ok(AppResponse::<()>::success(
+                StatusCode::OK.as_u16(),
+                "If an account exists for this email, a reset password email has been sent",
+                StatusCode::OK.canonical_reason().unwrap_or("OK"),
+                None,
+            ));
+        }
+        let user = match user {
             Some(user) => user,
             None => return Err(UserErrorKind::UserNotFound.into()),
         };
-        let email_token = generate_email_token();
-        self.db_client
-            .redis_client
-            .set_email_token(EmailType::PasswordReset, &email_token, &user.id, 1800)
+        send_reset_password_email(&self.config.load(), &self.db_client, &self.resend, &user)
             .await?;
-        let html = RESET_PASSWORD_EMAIL_HTML
-            .replace("{{username}}", &user.name)
-            .replace("{{email_token}}", &email_token);
-        let _email = send_mail(
-            &self.resend,
-            &self.config.mail_server.from_email,
-            vec![&user.email],
-            "Reset password",
-            &html,
-        )
-        .await
-        .map_err(ExternalError::from)?;
         Ok(AppResponse::<()>::success(
             StatusCode::OK.as_u16(),
             "An reset password email has been sent, please check your email",
@@ -387,7 +979,7 @@ impl AuthService {
         validate_reset_password_payload(&payload)?;
         let user_id = match self
             .db_client
-            .redis_client
+            .cache
             .use_email_token(EmailType::PasswordReset, &payload.token)
             .await?
         {
@@ -396,7 +988,7 @@ impl AuthService {
         };
         let user = self
             .db_client
-            .surreal_client
+            .database
             .find_user_by_id(user_id.clone())
             .await?
             .ok_or(UserErrorKind::UserNotFound)?;
@@ -404,11 +996,27 @@ impl AuthService {
         if user.email != payload.email {
             return Err(UserErrorKind::Unauthorized.into());
         }
+        ensure_password_not_reused(
+            &self.config.load(),
+            &self.db_client,
+            &self.password_hasher,
+            &user,
+            &payload.new_password,
+        )
+        .await?;
+        self.password_policy
+            .check(&payload.new_password, &[&user.name, &user.email])
+            .await?;
+        let password_hash = self
+            .password_hasher
+            .hash_password(&payload.new_password)
+            .await?;
         self.db_client
-            .surreal_client
-            .reset_password(user.id.clone(), &payload.new_password)
+            .database
+            .reset_password(user.id.clone(), &password_hash)
             .await?;
-        self.db_client.redis_client.delete_user(&user.id).await?;
+        remember_previous_password(&self.config.load(), &self.db_client, &user).await?;
+        self.db_client.cache.delete_user(&user.id).await?;
         Ok(AppResponse::<()>::success(
             StatusCode::OK.as_u16(),
             "Reset your password successfully",
@@ -416,4 +1024,89 @@ impl AuthService {
             None,
         ))
     }
+    pub async fn restore_account(
+        &self,
+        addr: SocketAddr,
+        payload: RestoreAccountRequest,
+    ) -> AppResult<impl IntoResponse + use<>> {
+        validate_restore_account_payload(&payload)?;
+        let anti_enumeration = self.config.load().security.anti_enumeration;
+        let ip = addr.ip().to_string();
+        // The password is checked like in `login`, so this can't be used to guess it without
+        // the same challenge and failure counting.
+        self.require_password_challenge(&payload.email, payload.challenge.as_ref(), &ip)
+            .await?;
+        let user = match self
+            .db_client
+            .database
+            .find_user_by_email(&payload.email)
+            .await?
+        {
+            Some(user) => user,
+            None => {
+                self.record_login_failure(&payload.email).await;
+                if anti_enumeration {
+                    self.password_hasher.dummy_verify(&payload.password).await;
+                    return Err(UserErrorKind::InvalidCredentials.into());
+                }
+                return Err(UserErrorKind::UserNotFound.into());
+            }
+        };
+        // A wrong password and an account that isn't pending deletion look the same, otherwise
+        // the answer would tell whether the password was right.
+        if !self
+            .password_hasher
+            .verify_password(&payload.password, &user.password)
+            .await?
+            || user.status != UserStatus::Deleted
+        {
+            self.record_login_failure(&payload.email).await;
+            return Err(UserErrorKind::InvalidCredentials.into());
+        }
+        if let Err(e) = self.db_client.cache.clear_login_failures(&user.email).await {
+            error!("❌ Failed to clear login failures: {}", e);
+        }
+        let user_status = if user.is_verified {
+            UserStatus::Active
+        } else {
+            UserStatus::Inactive
+        };
+        self.db_client
+            .database
+            .restore_account(user.id.clone(), user_status)
+            .await?;
+        self.db_client.cache.delete_user(&user.id).await?;
+        Ok(AppResponse::<()>::success(
+            StatusCode::OK.as_u16(),
+            "Your account has been restored, please log in again",
+            StatusCode::OK.canonical_reason().unwrap_or("OK"),
+            None,
+        ))
+    }
+}
+
+async fn send_reset_password_email(
+    config: &AppConfig,
+    db_client: &DBClient,
+    resend: &Arc<Resend>,
+    user: &User,
+) -> AppResult<()> {
+    let email_token = generate_email_token();
+    db_client
+        .cache
+        .set_email_token(EmailType::PasswordReset, &email_token, &user.id, 1800)
+        .await?;
+    let html = RESET_PASSWORD_EMAIL_HTML
+        .replace("{{username}}", &escape_html(&user.name))
+        .replace("{{email_token}}", &email_token);
+    let _email = send_mail(
+        resend,
+        &config.mail_server.from_email,
+        vec![&user.email],
+        "Reset password",
+        &html,
+    )
+    .await
+    .map_err(ExternalError::from)?;
+    Ok(())
 }
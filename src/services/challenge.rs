@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use axum::{http::StatusCode, response::IntoResponse};
+use chrono::{Duration, Utc};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    config::challenge::ChallengeProvider,
+    core::{
+        config::SharedConfig, error::challenge::ChallengeErrorKind, response::AppResponse,
+        result::AppResult,
+    },
+    database::client::DBClient,
+    dto::request::auth::ChallengeSolution,
+    models::challenge::Challenge,
+    utils::challenge::{CaptchaVerifier, ChallengeVerifier, ProofOfWorkVerifier},
+};
+
+#[derive(Debug)]
+pub struct ChallengeService {
+    pub config: SharedConfig,
+    pub db_client: Arc<DBClient>,
+    /// `None` when challenges are turned off.
+    pub verifier: Option<Arc<dyn ChallengeVerifier>>,
+}
+
+impl ChallengeService {
+    pub fn new(config: SharedConfig, db_client: Arc<DBClient>) -> Self {
+        let snapshot = config.load();
+        let challenge = &snapshot.challenge;
+        let proof_of_work = || -> Arc<dyn ChallengeVerifier> {
+            Arc::new(ProofOfWorkVerifier {
+                difficulty: challenge.challenge_pow_difficulty,
+            })
+        };
+        let verifier = match challenge.challenge_provider {
+            ChallengeProvider::Pow => Some(proof_of_work()),
+            ChallengeProvider::Captcha => match (
+                &challenge.challenge_captcha_verify_url,
+                &challenge.challenge_captcha_secret,
+            ) {
+                (Some(verify_url), Some(secret)) => Some(Arc::new(CaptchaVerifier {
+                    http: reqwest::Client::new(),
+                    verify_url: verify_url.clone(),
+                    site_key: challenge.challenge_captcha_site_key.clone(),
+                    secret: secret.clone(),
+                })
+                    as Arc<dyn ChallengeVerifier>),
+                _ => {
+                    warn!(
+                        "⚠️ APP_CHALLENGE_CAPTCHA_VERIFY_URL or APP_CHALLENGE_CAPTCHA_SECRET is missing, falling back to proof of work"
+                    );
+                    Some(proof_of_work())
+                }
+            },
+            ChallengeProvider::None => None,
+        };
+        drop(snapshot);
+        Self {
+            config,
+            db_client,
+            verifier,
+        }
+    }
+    pub async fn issue_challenge(&self, ip: &str) -> AppResult<impl IntoResponse + use<>> {
+        let challenge = match &self.verifier {
+            Some(verifier) => {
+                let config = self.config.load();
+                let issued = self.db_client.cache.record_challenge_issued(ip, 60).await?;
+                if issued > config.challenge.challenge_issue_limit_per_minute {
+                    return Err(ChallengeErrorKind::TooManyChallenges.into());
+                }
+                let ttl_seconds = config.challenge.challenge_ttl_seconds;
+                let challenge = verifier.new_challenge(
+                    Uuid::new_v4().to_string(),
+                    Utc::now() + Duration::seconds(ttl_seconds as i64),
+                );
+                self.db_client
+                    .cache
+                    .set_challenge(&challenge, ttl_seconds)
+                    .await?;
+                Some(challenge)
+            }
+            None => None,
+        };
+        Ok(AppResponse::<Challenge>::success(
+            StatusCode::OK.as_u16(),
+            "OK",
+            StatusCode::OK.canonical_reason().unwrap_or("OK"),
+            challenge,
+        ))
+    }
+    /// Consumes a solved challenge. Every challenge can be answered once, right or wrong.
+    pub async fn require_challenge(
+        &self,
+        solution: Option<&ChallengeSolution>,
+        ip: &str,
+    ) -> AppResult<()> {
+        let Some(verifier) = &self.verifier else {
+            return Ok(());
+        };
+        let Some(solution) = solution else {
+            return Err(ChallengeErrorKind::ChallengeRequired.into());
+        };
+        let challenge = match self
+            .db_client
+            .cache
+            .take_challenge(&solution.challenge_id)
+            .await?
+        {
+            Some(challenge) if challenge.expires_at > Utc::now() => challenge,
+            _ => return Err(ChallengeErrorKind::InvalidChallenge.into()),
+        };
+        if !verifier.verify(&challenge, &solution.solution, ip).await? {
+            return Err(ChallengeErrorKind::InvalidChallenge.into());
+        }
+        Ok(())
+    }
+}
@@ -1,48 +1,281 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
 
 use axum::{http::StatusCode, response::IntoResponse};
+use chrono::Utc;
 
 use crate::{
-    core::error::other::OtherErrorKind,
-    core::{config::AppConfig, response::AppResponse, result::AppResult},
-    database::client::DBClient,
-    repositories::{
-        redis::health::HealthRepository,
-        surreal::health::HealthRepository as SurrealHealthRepository,
+    config::redis_server::CacheBackend,
+    core::{
+        config::SharedConfig,
+        error::{external::ExternalError, other::OtherErrorKind},
+        response::{AppResponse, AppResponseBody},
+        result::AppResult,
+    },
+    database::{
+        circuit_breaker::CircuitState,
+        client::DBClient,
+        surreal::migration::{MigrationRunner, MigrationState},
+    },
+    dto::response::health::{
+        ComponentError, ComponentHealth, ComponentStatus, ReadinessResponse, ReadinessStatus,
     },
 };
 
+/// Where Resend is reached when `resend_base_url` isn't set.
+const RESEND_API_URL: &str = "https://api.resend.com";
+/// How long the mail transport may take to answer a readiness probe.
+const MAIL_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
 #[derive(Debug)]
 pub struct HealthService {
-    pub config: Arc<AppConfig>,
+    pub config: SharedConfig,
     pub db_client: Arc<DBClient>,
+    http: reqwest::Client,
+    last_errors: Mutex<HashMap<&'static str, ComponentError>>,
+}
+
+/// Outcome of probing one component, before it's turned into a [`ComponentHealth`].
+struct Probe {
+    latency: Duration,
+    result: AppResult<Option<String>>,
+    detail: Option<String>,
+    circuit: Option<CircuitState>,
 }
 
 impl HealthService {
-    pub fn new(config: Arc<AppConfig>, db_client: Arc<DBClient>) -> Self {
-        Self { config, db_client }
+    pub fn new(config: SharedConfig, db_client: Arc<DBClient>) -> Self {
+        Self {
+            config,
+            db_client,
+            http: reqwest::Client::new(),
+            last_errors: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The process is up and serving requests. Dependencies are deliberately not checked, so
+    /// an outage of one doesn't get every instance restarted.
+    pub async fn liveness(&self) -> AppResult<impl IntoResponse + use<>> {
+        Ok(AppResponse::<()>::success(
+            StatusCode::OK.as_u16(),
+            "UP",
+            StatusCode::OK.canonical_reason().unwrap_or("OK"),
+            None,
+        ))
+    }
+
+    /// Whether the instance can serve traffic, with a breakdown per component. Answers 503 when
+    /// a required component is down and 200 otherwise, including when degraded.
+    pub async fn readiness(&self) -> AppResult<impl IntoResponse + use<>> {
+        let uses_redis = self.config.load().redis_server.cache_backend == CacheBackend::Redis;
+        let (surrealdb, redis, mail, migrations) = tokio::join!(
+            self.probe_surrealdb(),
+            async {
+                match uses_redis {
+                    true => Some(self.probe_redis().await),
+                    false => None,
+                }
+            },
+            self.probe_mail(),
+            self.probe_migrations(),
+        );
+        let components = vec![
+            self.component("surrealdb", true, Some(surrealdb)),
+            self.component("redis", true, redis),
+            self.component("mail", false, Some(mail)),
+            self.component("migrations", true, Some(migrations)),
+        ];
+        let status = if components
+            .iter()
+            .any(|c| c.required && c.status == ComponentStatus::Down)
+        {
+            ReadinessStatus::Down
+        } else if components.iter().any(|c| c.status == ComponentStatus::Down) {
+            ReadinessStatus::Degraded
+        } else {
+            ReadinessStatus::Up
+        };
+        let code = match status {
+            ReadinessStatus::Down => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::OK,
+        };
+        let body = AppResponseBody {
+            code: code.as_u16(),
+            message: match status {
+                ReadinessStatus::Up => "UP",
+                ReadinessStatus::Degraded => "DEGRADED",
+                ReadinessStatus::Down => "DOWN",
+            }
+            .to_string(),
+            status: code.canonical_reason().unwrap_or_default().to_string(),
+            data: Some(ReadinessResponse { status, components }),
+        };
+        // The breakdown is sent either way, it's what tells an operator what is down.
+        Ok(match status {
+            ReadinessStatus::Down => AppResponse::Error { error: body },
+            _ => AppResponse::Success { success: body },
+        })
     }
-    pub async fn health_check(&self) -> AppResult<impl IntoResponse + use<>> {
-        let checks: (bool, bool) = tokio::join!(
-            async { self.db_client.redis_client.health_check().await.is_ok() },
-            async { self.db_client.surreal_client.health_check().await.is_ok() }
+
+    async fn probe_surrealdb(&self) -> Probe {
+        let database = &self.db_client.database;
+        let circuit = database.circuit_state();
+        let started_at = Instant::now();
+        let result = match database.health_check().await {
+            Ok(true) => database.version().await.map(Some),
+            Ok(false) => Err(OtherErrorKind::Error("Health check failed".to_string()).into()),
+            Err(e) => Err(e),
+        };
+        Probe {
+            latency: started_at.elapsed(),
+            result,
+            detail: None,
+            circuit: Some(circuit),
+        }
+    }
+
+    async fn probe_redis(&self) -> Probe {
+        let cache = &self.db_client.cache;
+        let circuit = cache.circuit_state();
+        let started_at = Instant::now();
+        let result = match cache.health_check().await {
+            Ok(true) => cache.version().await.map(Some),
+            Ok(false) => Err(OtherErrorKind::Error("Health check failed".to_string()).into()),
+            Err(e) => Err(e),
+        };
+        Probe {
+            latency: started_at.elapsed(),
+            result,
+            detail: None,
+            circuit: Some(circuit),
+        }
+    }
+
+    /// Any HTTP answer from the mail API counts, only reaching it is checked. Probing with the
+    /// API key would fail for send-only keys.
+    async fn probe_mail(&self) -> Probe {
+        let base_url = self
+            .config
+            .load()
+            .mail_server
+            .resend_base_url
+            .clone()
+            .unwrap_or_else(|| RESEND_API_URL.to_string());
+        let started_at = Instant::now();
+        let result = match self
+            .http
+            .get(&base_url)
+            .timeout(MAIL_PROBE_TIMEOUT)
+            .send()
+            .await
+        {
+            Ok(_) => Ok(None),
+            Err(e) => Err(ExternalError::from(e).into()),
+        };
+        Probe {
+            latency: started_at.elapsed(),
+            result,
+            detail: None,
+            circuit: None,
+        }
+    }
+
+    /// Ready when every migration on disk is applied and none changed since. The version is
+    /// the newest applied migration.
+    async fn probe_migrations(&self) -> Probe {
+        let runner = MigrationRunner::new(&self.config.load().migration);
+        let started_at = Instant::now();
+        let status = runner.status(self.db_client.database.as_ref()).await;
+        let latency = started_at.elapsed();
+        let status = match status {
+            Ok(status) => status,
+            Err(e) => {
+                return Probe {
+                    latency,
+                    result: Err(e),
+                    detail: None,
+                    circuit: None,
+                };
+            }
+        };
+        let count = |state| status.iter().filter(|s| s.state == state).count();
+        let detail = format!(
+            "{} applied, {} pending, {} drifted",
+            count(MigrationState::Applied),
+            count(MigrationState::Pending),
+            count(MigrationState::Drifted)
         );
-        if !checks.0 && !checks.1 {
-            Err(
-                OtherErrorKind::Error("Redis server error and SurrealDB server error".to_string())
-                    .into(),
-            )
-        } else if !checks.0 && checks.1 {
-            Err(OtherErrorKind::Error("Redis server error".to_string()).into())
-        } else if checks.0 && !checks.1 {
-            Err(OtherErrorKind::Error("SurrealDB server error".to_string()).into())
+        let out_of_sync: Vec<String> = status
+            .iter()
+            .filter(|s| s.state != MigrationState::Applied)
+            .map(|s| format!("{} ({})", s.name, s.state))
+            .collect();
+        let result = if out_of_sync.is_empty() {
+            Ok(status.last().map(|s| s.name.clone()))
         } else {
-            Ok(AppResponse::<()>::success(
-                StatusCode::OK.as_u16(),
-                "UP",
-                StatusCode::OK.canonical_reason().unwrap_or("OK"),
-                None,
+            Err(OtherErrorKind::Error(format!(
+                "Migrations not applied: {}",
+                out_of_sync.join(", ")
             ))
+            .into())
+        };
+        Probe {
+            latency,
+            result,
+            detail: Some(detail),
+            circuit: None,
+        }
+    }
+
+    /// Turns a probe into the reported health, remembering its error as the last one. `None`
+    /// means the component isn't used.
+    fn component(
+        &self,
+        name: &'static str,
+        required: bool,
+        probe: Option<Probe>,
+    ) -> ComponentHealth {
+        let mut last_errors = self
+            .last_errors
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let Some(probe) = probe else {
+            return ComponentHealth {
+                name,
+                status: ComponentStatus::Disabled,
+                required,
+                latency_ms: None,
+                version: None,
+                detail: None,
+                circuit: None,
+                last_error: last_errors.get(name).cloned(),
+            };
+        };
+        let (status, version) = match probe.result {
+            Ok(version) => (ComponentStatus::Up, version),
+            Err(e) => {
+                last_errors.insert(
+                    name,
+                    ComponentError {
+                        message: e.to_string(),
+                        at: Utc::now(),
+                    },
+                );
+                (ComponentStatus::Down, None)
+            }
+        };
+        ComponentHealth {
+            name,
+            status,
+            required,
+            latency_ms: Some(probe.latency.as_millis() as u64),
+            version,
+            detail: probe.detail,
+            circuit: probe.circuit,
+            last_error: last_errors.get(name).cloned(),
         }
     }
 }
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use axum::{
+    http::{HeaderMap, HeaderValue, header},
+    response::IntoResponse,
+};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    core::{config::SharedConfig, error::metrics::MetricsErrorKind, result::AppResult},
+    utils::{metrics::METRICS, password::PasswordHasher},
+};
+
+/// Content type of the Prometheus text exposition format.
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+#[derive(Debug)]
+pub struct MetricsService {
+    pub config: SharedConfig,
+    pub password_hasher: Arc<PasswordHasher>,
+}
+
+impl MetricsService {
+    pub fn new(config: SharedConfig, password_hasher: Arc<PasswordHasher>) -> Self {
+        Self {
+            config,
+            password_hasher,
+        }
+    }
+
+    /// Every metric in the Prometheus text format, once the config allows it and the request
+    /// carries the metrics token when one is set.
+    pub async fn render(&self, headers: &HeaderMap) -> AppResult<impl IntoResponse + use<>> {
+        let config = self.config.load();
+        if !config.metrics.metrics_enabled {
+            return Err(MetricsErrorKind::Disabled.into());
+        }
+        if let Some(expected) = &config.metrics.metrics_token {
+            let token = headers
+                .get(header::AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .unwrap_or_default();
+            // Comparing digests keeps the time taken independent of how much of the token matched.
+            if Sha256::digest(token.as_bytes()) != Sha256::digest(expected.as_bytes()) {
+                return Err(MetricsErrorKind::Unauthorized.into());
+            }
+        }
+        let body = METRICS.render(&self.password_hasher.pool.metrics);
+        Ok((
+            [(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static(PROMETHEUS_CONTENT_TYPE),
+            )],
+            body,
+        ))
+    }
+}
@@ -1,34 +1,83 @@
 use std::sync::Arc;
 
-use resend_rs::Resend;
+use resend_rs::{ConfigBuilder, Resend};
 
 use crate::{
-    core::config::AppConfig,
+    core::config::SharedConfig,
     database::client::DBClient,
-    services::{auth::AuthService, health::HealthService, user::UserService},
+    services::{
+        auth::AuthService, health::HealthService, metrics::MetricsService, user::UserService,
+    },
+    storage::ObjectStorage,
+    utils::{
+        device::UserAgentParser, geoip::GeoIpResolver, password::PasswordHasher,
+        password_policy::PasswordPolicy,
+    },
 };
 
 pub mod admin;
 pub mod auth;
+pub mod challenge;
 pub mod health;
+pub mod metrics;
 pub mod user;
 
 #[derive(Debug)]
 pub struct Services {
     pub health: HealthService,
+    pub metrics: MetricsService,
     pub auth: AuthService,
     pub user: UserService,
     pub resend: Arc<Resend>,
 }
 
 impl Services {
-    pub fn new(config: Arc<AppConfig>, db_client: Arc<DBClient>) -> Self {
-        let resend = Arc::new(Resend::new(&config.mail_server.resend_api_key));
+    pub fn new(
+        config: SharedConfig,
+        db_client: Arc<DBClient>,
+        storage: Arc<dyn ObjectStorage>,
+        password_hasher: Arc<PasswordHasher>,
+        user_agent_parser: Arc<UserAgentParser>,
+        geoip: Arc<GeoIpResolver>,
+    ) -> Self {
+        let snapshot = config.load();
+        let mail_server = &snapshot.mail_server;
+        let resend = match &mail_server.resend_base_url {
+            Some(base_url) => Resend::with_config(
+                ConfigBuilder::new(&mail_server.resend_api_key)
+                    .base_url(
+                        base_url
+                            .parse()
+                            .expect("resend_base_url is checked when the config is loaded"),
+                    )
+                    .build(),
+            ),
+            None => Resend::new(&mail_server.resend_api_key),
+        };
+        let resend = Arc::new(resend);
+        let password_policy = Arc::new(PasswordPolicy::new(snapshot.password_policy.clone()));
         let health = HealthService::new(config.clone(), db_client.clone());
-        let auth = AuthService::new(config.clone(), db_client.clone(), resend.clone());
-        let user = UserService::new(config, db_client);
+        let metrics = MetricsService::new(config.clone(), password_hasher.clone());
+        let auth = AuthService::new(
+            config.clone(),
+            db_client.clone(),
+            resend.clone(),
+            password_policy.clone(),
+            password_hasher.clone(),
+            user_agent_parser,
+            geoip,
+        );
+        let user = UserService::new(
+            config,
+            db_client,
+            storage,
+            resend.clone(),
+            password_policy,
+            password_hasher,
+        );
         Self {
             health,
+            metrics,
             auth,
             user,
             resend,
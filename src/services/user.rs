@@ -1,31 +1,88 @@
-use std::sync::Arc;
+use std::{cmp::Reverse, sync::Arc};
 
-use axum::{http::StatusCode, response::IntoResponse};
+use axum::{
+    extract::{Multipart, multipart::MultipartError},
+    http::{
+        HeaderMap, HeaderValue, StatusCode,
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    },
+    response::IntoResponse,
+};
+use axum_extra::extract::{
+    CookieJar,
+    cookie::{Cookie, SameSite},
+};
+use chrono::{Duration, Utc};
+use resend_rs::Resend;
+use tracing::{error, info};
 
 use crate::{
     core::{
-        config::AppConfig, error::user::UserErrorKind, response::AppResponse, result::AppResult,
+        config::{AppConfig, SharedConfig},
+        error::{
+            avatar::AvatarErrorKind, data_export::DataExportErrorKind, external::ExternalError,
+            user::UserErrorKind,
+        },
+        response::AppResponse,
+        result::AppResult,
     },
     database::client::DBClient,
-    dto::{request::user::ChangePasswordRequest, response::user::MeResponse},
-    models::user::User,
-    repositories::{
-        redis::auth::AuthCacheRepository,
-        surreal::{auth::AuthRepository, user::UserRepository},
+    dto::{
+        request::user::{
+            ChangePasswordRequest, DeleteAccountRequest, NotificationPreferencesRequest,
+            UserProfileRequest,
+        },
+        response::{
+            export::{DataExportArchive, ExportSession},
+            user::{DeleteAccountResponse, DeviceResponse, MeResponse, SessionResponse},
+        },
+    },
+    models::user::{AvatarUrls, NotificationPreferences, User},
+    storage::ObjectStorage,
+    templates::data_export_email_html::DATA_EXPORT_EMAIL_HTML,
+    utils::{
+        avatar::{AVATAR_CONTENT_TYPES, AVATAR_SIZES, avatar_key, process_avatar},
+        mail::{escape_html, send_mail},
+        password::PasswordHasher,
+        password_policy::PasswordPolicy,
+        token::generate_email_token,
+    },
+    validation::user::{
+        validate_change_password_request, validate_delete_account_request,
+        validate_user_profile_request,
     },
-    utils::password::compare_hashed_password,
-    validation::user::validate_change_password_request,
 };
 
+/// A user can ask for one data export per this many seconds.
+const DATA_EXPORT_INTERVAL_SECONDS: u64 = 3600;
+
 #[derive(Debug)]
 pub struct UserService {
-    pub config: Arc<AppConfig>,
+    pub config: SharedConfig,
     pub db_client: Arc<DBClient>,
+    pub storage: Arc<dyn ObjectStorage>,
+    pub resend: Arc<Resend>,
+    pub password_policy: Arc<PasswordPolicy>,
+    pub password_hasher: Arc<PasswordHasher>,
 }
 
 impl UserService {
-    pub fn new(config: Arc<AppConfig>, db_client: Arc<DBClient>) -> Self {
-        Self { config, db_client }
+    pub fn new(
+        config: SharedConfig,
+        db_client: Arc<DBClient>,
+        storage: Arc<dyn ObjectStorage>,
+        resend: Arc<Resend>,
+        password_policy: Arc<PasswordPolicy>,
+        password_hasher: Arc<PasswordHasher>,
+    ) -> Self {
+        Self {
+            config,
+            db_client,
+            storage,
+            resend,
+            password_policy,
+            password_hasher,
+        }
     }
     pub async fn get_me(&self, user: User) -> AppResult<impl IntoResponse + use<>> {
         let me = MeResponse::from(user);
@@ -36,6 +93,170 @@ impl UserService {
             Some(me),
         ))
     }
+    pub async fn update_me(
+        &self,
+        user: User,
+        payload: UserProfileRequest,
+    ) -> AppResult<impl IntoResponse + use<>> {
+        validate_user_profile_request(&payload)?;
+        let updated_user = match self
+            .db_client
+            .database
+            .update_profile(user.id.clone(), payload)
+            .await?
+        {
+            Some(user) => user,
+            None => return Err(UserErrorKind::ProfileVersionConflict.into()),
+        };
+        self.db_client.cache.delete_user(&user.id).await?;
+        Ok(AppResponse::<MeResponse>::success(
+            StatusCode::OK.as_u16(),
+            "Update your profile successfully",
+            StatusCode::OK.canonical_reason().unwrap_or("OK"),
+            Some(MeResponse::from(updated_user)),
+        ))
+    }
+    pub async fn get_notification_preferences(
+        &self,
+        user: User,
+    ) -> AppResult<impl IntoResponse + use<>> {
+        Ok(AppResponse::<NotificationPreferences>::success(
+            StatusCode::OK.as_u16(),
+            "OK",
+            StatusCode::OK.canonical_reason().unwrap_or("OK"),
+            Some(user.notification_preferences.unwrap_or_default()),
+        ))
+    }
+    pub async fn update_notification_preferences(
+        &self,
+        user: User,
+        payload: NotificationPreferencesRequest,
+    ) -> AppResult<impl IntoResponse + use<>> {
+        let current = user.notification_preferences.unwrap_or_default();
+        let preferences = NotificationPreferences {
+            new_device: payload.new_device.unwrap_or(current.new_device),
+            suspicious_login: payload.suspicious_login.unwrap_or(current.suspicious_login),
+        };
+        let updated_user = self
+            .db_client
+            .database
+            .update_notification_preferences(user.id.clone(), preferences)
+            .await?;
+        self.db_client.cache.delete_user(&user.id).await?;
+        Ok(AppResponse::<NotificationPreferences>::success(
+            StatusCode::OK.as_u16(),
+            "Update your notification preferences successfully",
+            StatusCode::OK.canonical_reason().unwrap_or("OK"),
+            updated_user.notification_preferences,
+        ))
+    }
+    pub async fn upload_avatar(
+        &self,
+        user: User,
+        mut multipart: Multipart,
+    ) -> AppResult<impl IntoResponse + use<>> {
+        let mut upload = None;
+        while let Some(field) = multipart.next_field().await.map_err(multipart_error)? {
+            if field.name() == Some("avatar") {
+                let content_type = field.content_type().unwrap_or_default().to_string();
+                let bytes = field.bytes().await.map_err(multipart_error)?;
+                upload = Some((content_type, bytes));
+                break;
+            }
+        }
+        let (content_type, bytes) = upload.ok_or(AvatarErrorKind::MissingAvatar)?;
+        if !AVATAR_CONTENT_TYPES.contains(&content_type.as_str()) {
+            return Err(AvatarErrorKind::UnsupportedImageType.into());
+        }
+        if bytes.len() > self.config.load().storage.avatar_max_bytes {
+            return Err(AvatarErrorKind::AvatarTooLarge.into());
+        }
+        let images = tokio::task::spawn_blocking(move || process_avatar(&bytes))
+            .await
+            .map_err(ExternalError::from)??;
+        // Sizes are stored under stable keys, the version query parameter busts client caches.
+        let version = Utc::now().timestamp_millis();
+        let mut urls = Vec::with_capacity(images.len());
+        for (size, image) in images {
+            let key = avatar_key(&user.id, size);
+            self.storage.put_object(&key, image, "image/png").await?;
+            urls.push(format!("{}?v={}", self.storage.public_url(&key), version));
+        }
+        let [small, medium, large]: [String; 3] =
+            urls.try_into().map_err(|_| AvatarErrorKind::InvalidImage)?;
+        let updated_user = self
+            .db_client
+            .database
+            .update_avatar(
+                user.id.clone(),
+                AvatarUrls {
+                    small,
+                    medium,
+                    large,
+                },
+            )
+            .await?;
+        self.db_client.cache.delete_user(&user.id).await?;
+        Ok(AppResponse::<MeResponse>::success(
+            StatusCode::OK.as_u16(),
+            "Upload your avatar successfully",
+            StatusCode::OK.canonical_reason().unwrap_or("OK"),
+            Some(MeResponse::from(updated_user)),
+        ))
+    }
+    pub async fn list_devices(&self, user: User) -> AppResult<impl IntoResponse + use<>> {
+        let mut devices = self
+            .db_client
+            .database
+            .find_devices_by_user_id(user.id.clone())
+            .await?;
+        devices.sort_by_key(|device| Reverse(device.last_login_at));
+        Ok(AppResponse::<Vec<DeviceResponse>>::success(
+            StatusCode::OK.as_u16(),
+            "OK",
+            StatusCode::OK.canonical_reason().unwrap_or("OK"),
+            Some(devices.into_iter().map(Into::into).collect()),
+        ))
+    }
+    pub async fn list_sessions(
+        &self,
+        user: User,
+        jar: CookieJar,
+    ) -> AppResult<impl IntoResponse + use<>> {
+        let database = &self.db_client.database;
+        let (devices, refresh_tokens) = tokio::try_join!(
+            database.find_devices_by_user_id(user.id.clone()),
+            database.find_refresh_tokens_by_user_id(user.id.clone()),
+        )?;
+        let current_token = jar.get("refresh_token").map(|cookie| cookie.value());
+        let now = Utc::now();
+        let mut sessions: Vec<SessionResponse> = refresh_tokens
+            .into_iter()
+            .filter(|refresh_token| refresh_token.expires_at > now)
+            .map(|refresh_token| {
+                // Sessions show where they were opened from, taken from the device they belong to.
+                let device = devices.iter().find(|d| d.id == refresh_token.device_id);
+                SessionResponse {
+                    is_current: current_token == Some(refresh_token.token_value.as_str()),
+                    id: refresh_token.id,
+                    device_id: refresh_token.device_id,
+                    user_agent: device.map(|d| d.user_agent.clone()),
+                    os: device.map(|d| d.os.clone()),
+                    ip: device.map(|d| d.ip.clone()),
+                    location: device.and_then(|d| d.location.clone()),
+                    created_at: refresh_token.created_at,
+                    expires_at: refresh_token.expires_at,
+                }
+            })
+            .collect();
+        sessions.sort_by_key(|session| Reverse(session.created_at));
+        Ok(AppResponse::<Vec<SessionResponse>>::success(
+            StatusCode::OK.as_u16(),
+            "OK",
+            StatusCode::OK.canonical_reason().unwrap_or("OK"),
+            Some(sessions),
+        ))
+    }
     pub async fn change_password(
         &self,
         user: User,
@@ -44,21 +265,37 @@ impl UserService {
         validate_change_password_request(&payload)?;
         let user_detail = match self
             .db_client
-            .surreal_client
+            .database
             .find_user_by_id(user.id.clone())
             .await?
         {
             Some(user) => user,
             None => return Err(UserErrorKind::UserNotFound.into()),
         };
-        if compare_hashed_password(&payload.new_password, &user_detail.password)? {
-            return Err(UserErrorKind::PasswordMustBeDifferentFromLastPassword.into());
-        }
+        ensure_password_not_reused(
+            &self.config.load(),
+            &self.db_client,
+            &self.password_hasher,
+            &user_detail,
+            &payload.new_password,
+        )
+        .await?;
+        self.password_policy
+            .check(
+                &payload.new_password,
+                &[&user_detail.name, &user_detail.email],
+            )
+            .await?;
+        let password_hash = self
+            .password_hasher
+            .hash_password(&payload.new_password)
+            .await?;
         self.db_client
-            .surreal_client
-            .change_password(user.id.clone(), &payload.new_password)
+            .database
+            .change_password(user.id.clone(), &password_hash)
             .await?;
-        self.db_client.redis_client.delete_user(&user.id).await?;
+        remember_previous_password(&self.config.load(), &self.db_client, &user_detail).await?;
+        self.db_client.cache.delete_user(&user.id).await?;
         Ok(AppResponse::<()>::success(
             StatusCode::OK.as_u16(),
             "Reset your password successfully",
@@ -66,4 +303,278 @@ impl UserService {
             None,
         ))
     }
+    pub async fn delete_me(
+        &self,
+        jar: CookieJar,
+        user: User,
+        payload: DeleteAccountRequest,
+    ) -> AppResult<impl IntoResponse + use<>> {
+        validate_delete_account_request(&payload)?;
+        let user_detail = match self
+            .db_client
+            .database
+            .find_user_by_id(user.id.clone())
+            .await?
+        {
+            Some(user) => user,
+            None => return Err(UserErrorKind::UserNotFound.into()),
+        };
+        if !self
+            .password_hasher
+            .verify_password(&payload.password, &user_detail.password)
+            .await?
+        {
+            return Err(UserErrorKind::WrongPassword.into());
+        }
+        let deletion_scheduled_at =
+            Utc::now() + Duration::days(self.config.load().account.account_deletion_grace_days);
+        self.db_client
+            .database
+            .schedule_deletion(user.id.clone(), deletion_scheduled_at)
+            .await?;
+        self.db_client
+            .database
+            .delete_refresh_tokens_by_user_id(user.id.clone())
+            .await?;
+        self.db_client.cache.delete_user(&user.id).await?;
+        let new_refresh_token_cookie = Cookie::build(("refresh_token", ""))
+            .path("/")
+            .http_only(true)
+            .secure(true)
+            .same_site(SameSite::Strict)
+            .max_age(time::Duration::ZERO)
+            .build();
+        let updated_jar = jar.remove("refresh_token").add(new_refresh_token_cookie);
+        Ok((
+            updated_jar,
+            AppResponse::<DeleteAccountResponse>::success(
+                StatusCode::OK.as_u16(),
+                "Your account is scheduled for deletion, log in before then to restore it",
+                StatusCode::OK.canonical_reason().unwrap_or("OK"),
+                Some(DeleteAccountResponse {
+                    deletion_scheduled_at,
+                }),
+            ),
+        ))
+    }
+    pub async fn purge_deleted_accounts(&self) -> AppResult<usize> {
+        let users = self.db_client.database.find_users_due_for_purge().await?;
+        for user in &users {
+            self.db_client.database.purge_user(user.id.clone()).await?;
+            self.db_client.cache.delete_user(&user.id).await?;
+            for (size, _) in AVATAR_SIZES {
+                let key = avatar_key(&user.id, size);
+                if let Err(e) = self.storage.delete_object(&key).await {
+                    error!("❌ Failed to delete avatar {} of purged user: {}", key, e);
+                }
+            }
+            info!("✅ Purged deleted account {}", user.id);
+        }
+        Ok(users.len())
+    }
+    pub async fn request_data_export(&self, user: User) -> AppResult<impl IntoResponse + use<>> {
+        // Claimed atomically, so concurrent requests can't all pass the check. The database
+        // check still applies after a restart of the in-process cache.
+        if !self
+            .db_client
+            .cache
+            .claim_data_export(&user.id, DATA_EXPORT_INTERVAL_SECONDS)
+            .await?
+        {
+            return Err(DataExportErrorKind::DataExportAlreadyRequested.into());
+        }
+        if let Some(latest) = self
+            .db_client
+            .database
+            .find_latest_data_export_by_user_id(user.id.clone())
+            .await?
+            && latest.created_at
+                > Utc::now() - Duration::seconds(DATA_EXPORT_INTERVAL_SECONDS as i64)
+        {
+            return Err(DataExportErrorKind::DataExportAlreadyRequested.into());
+        }
+        let config = self.config.load_full();
+        let db_client = self.db_client.clone();
+        let resend = self.resend.clone();
+        tokio::spawn(async move {
+            let user_id = user.id.clone();
+            match deliver_data_export(config, db_client.clone(), resend, user).await {
+                Ok(_) => info!("✅ Delivered data export for user {}", user_id),
+                Err(e) => {
+                    error!(
+                        "❌ Failed to deliver data export for user {}: {}",
+                        user_id, e
+                    );
+                    // Nothing was delivered, so the user may ask again right away.
+                    if let Err(e) = db_client.cache.release_data_export(&user_id).await {
+                        error!("❌ Failed to release data export claim: {}", e);
+                    }
+                }
+            }
+        });
+        Ok(AppResponse::<()>::success(
+            StatusCode::ACCEPTED.as_u16(),
+            "Your data export is being prepared, a download link will be sent to your email",
+            StatusCode::ACCEPTED
+                .canonical_reason()
+                .unwrap_or("Accepted"),
+            None,
+        ))
+    }
+    pub async fn download_data_export(
+        &self,
+        token: String,
+    ) -> AppResult<impl IntoResponse + use<>> {
+        let data_export = self
+            .db_client
+            .database
+            .find_data_export_by_token(&token)
+            .await?
+            .ok_or(DataExportErrorKind::DataExportNotFound)?;
+        if data_export.expires_at <= Utc::now() {
+            return Err(DataExportErrorKind::DataExportExpired.into());
+        }
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers.insert(
+            CONTENT_DISPOSITION,
+            HeaderValue::from_static("attachment; filename=\"data-export.json\""),
+        );
+        Ok((headers, data_export.content))
+    }
+    pub async fn cleanup_expired_data_exports(&self) -> AppResult<usize> {
+        let data_exports = self.db_client.database.find_expired_data_exports().await?;
+        for data_export in &data_exports {
+            self.db_client
+                .database
+                .delete_data_export(data_export.id.clone())
+                .await?;
+        }
+        Ok(data_exports.len())
+    }
+}
+
+/// Rejects `new_password` when it matches the current password or one of the previous
+/// passwords kept in the history.
+pub async fn ensure_password_not_reused(
+    config: &AppConfig,
+    db_client: &DBClient,
+    password_hasher: &PasswordHasher,
+    user: &User,
+    new_password: &str,
+) -> AppResult<()> {
+    if password_hasher
+        .verify_password(new_password, &user.password)
+        .await?
+    {
+        return Err(UserErrorKind::PasswordMustBeDifferentFromLastPassword.into());
+    }
+    let history_size = config.password_policy.password_history_size;
+    if history_size <= 1 {
+        return Ok(());
+    }
+    let history = db_client
+        .database
+        .find_password_history_by_user_id(user.id.clone(), history_size - 1)
+        .await?;
+    for entry in history {
+        if password_hasher
+            .verify_password(new_password, &entry.password)
+            .await?
+        {
+            return Err(UserErrorKind::PasswordRecentlyUsed.into());
+        }
+    }
+    Ok(())
+}
+
+/// Moves the hash the user had before a password change into the history. The current
+/// password counts towards `password_history_size`, so one slot less is kept.
+pub async fn remember_previous_password(
+    config: &AppConfig,
+    db_client: &DBClient,
+    user: &User,
+) -> AppResult<()> {
+    let keep = config
+        .password_policy
+        .password_history_size
+        .saturating_sub(1);
+    if keep == 0 {
+        return Ok(());
+    }
+    db_client
+        .database
+        .add_password_history(user.id.clone(), &user.password, keep)
+        .await
+}
+
+async fn deliver_data_export(
+    config: Arc<AppConfig>,
+    db_client: Arc<DBClient>,
+    resend: Arc<Resend>,
+    user: User,
+) -> AppResult<()> {
+    let database = &db_client.database;
+    let (devices, refresh_tokens, audit_logs, emails) = tokio::try_join!(
+        database.find_devices_by_user_id(user.id.clone()),
+        database.find_refresh_tokens_by_user_id(user.id.clone()),
+        database.find_audit_logs_by_actor(user.id.to_string()),
+        database.find_emails_by_user_id(user.id.clone()),
+    )?;
+    // Sessions show where they were opened from, taken from the device they belong to.
+    let sessions = refresh_tokens
+        .into_iter()
+        .map(|refresh_token| {
+            let device = devices.iter().find(|d| d.id == refresh_token.device_id);
+            ExportSession {
+                ip: device.map(|d| d.ip.clone()),
+                location: device.and_then(|d| d.location.clone()),
+                ..refresh_token.into()
+            }
+        })
+        .collect();
+    let archive = DataExportArchive {
+        generated_at: Utc::now(),
+        profile: user.clone().into(),
+        devices,
+        sessions,
+        audit_logs,
+        emails: emails.into_iter().map(Into::into).collect(),
+    };
+    let content = serde_json::to_string_pretty(&archive).map_err(ExternalError::from)?;
+    let token = generate_email_token();
+    let expires_at = Utc::now() + Duration::hours(config.account.data_export_expires_hours);
+    database
+        .create_data_export(user.id.clone(), &token, &content, expires_at)
+        .await?;
+    let download_url = format!(
+        "{}/api/v1/exports/{}",
+        config.backend_server.backend_address.trim_end_matches('/'),
+        token
+    );
+    let html = DATA_EXPORT_EMAIL_HTML
+        .replace("{{username}}", &escape_html(&user.name))
+        .replace("{{download_url}}", &download_url)
+        .replace(
+            "{{expires_at}}",
+            &expires_at.format("%Y-%m-%d %H:%M UTC").to_string(),
+        );
+    send_mail(
+        &resend,
+        &config.mail_server.from_email,
+        vec![&user.email],
+        "Your data export",
+        &html,
+    )
+    .await
+    .map_err(ExternalError::from)?;
+    Ok(())
+}
+
+fn multipart_error(err: MultipartError) -> AvatarErrorKind {
+    if err.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        AvatarErrorKind::AvatarTooLarge
+    } else {
+        AvatarErrorKind::InvalidUpload
+    }
 }
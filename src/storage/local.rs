@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+
+use crate::{
+    config::storage::StorageConfig,
+    core::{error::external::ExternalError, result::AppResult},
+    storage::ObjectStorage,
+};
+
+#[derive(Debug)]
+pub struct LocalStorage {
+    pub root: PathBuf,
+    pub public_url: String,
+}
+
+impl LocalStorage {
+    pub fn new(config: &StorageConfig) -> Self {
+        Self {
+            root: PathBuf::from(&config.storage_local_path),
+            public_url: config.storage_public_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl ObjectStorage for LocalStorage {
+    async fn put_object(&self, key: &str, body: Vec<u8>, _content_type: &str) -> AppResult<()> {
+        let path = self.root.join(key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(ExternalError::from)?;
+        }
+        tokio::fs::write(path, body)
+            .await
+            .map_err(ExternalError::from)?;
+        Ok(())
+    }
+    async fn get_object(&self, key: &str) -> AppResult<Option<Vec<u8>>> {
+        match tokio::fs::read(self.root.join(key)).await {
+            Ok(body) => Ok(Some(body)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(ExternalError::from(e).into()),
+        }
+    }
+    async fn delete_object(&self, key: &str) -> AppResult<()> {
+        match tokio::fs::remove_file(self.root.join(key)).await {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(ExternalError::from(e).into()),
+        }
+    }
+    fn public_url(&self, key: &str) -> String {
+        format!("{}/{}", self.public_url, key)
+    }
+}
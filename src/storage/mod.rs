@@ -0,0 +1,27 @@
+pub mod local;
+pub mod s3;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    config::storage::{StorageBackend, StorageConfig},
+    core::result::AppResult,
+    storage::{local::LocalStorage, s3::S3Storage},
+};
+
+#[async_trait]
+pub trait ObjectStorage: std::fmt::Debug + Send + Sync {
+    async fn put_object(&self, key: &str, body: Vec<u8>, content_type: &str) -> AppResult<()>;
+    async fn get_object(&self, key: &str) -> AppResult<Option<Vec<u8>>>;
+    async fn delete_object(&self, key: &str) -> AppResult<()>;
+    fn public_url(&self, key: &str) -> String;
+}
+
+pub fn init_storage(config: &StorageConfig) -> AppResult<Arc<dyn ObjectStorage>> {
+    match config.storage_backend {
+        StorageBackend::Local => Ok(Arc::new(LocalStorage::new(config))),
+        StorageBackend::S3 => Ok(Arc::new(S3Storage::new(config)?)),
+    }
+}
@@ -0,0 +1,207 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::{Client, Method, StatusCode};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    config::storage::StorageConfig,
+    core::{
+        error::{external::ExternalError, storage::StorageErrorKind},
+        result::AppResult,
+    },
+    storage::ObjectStorage,
+};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Path-style S3 client signed with AWS Signature V4, so it works against AWS as well as
+/// S3-compatible servers such as MinIO or R2.
+#[derive(Debug)]
+pub struct S3Storage {
+    pub http: Client,
+    pub endpoint: String,
+    pub host: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key: String,
+    pub secret_key: String,
+    pub public_url: String,
+}
+
+impl S3Storage {
+    pub fn new(config: &StorageConfig) -> AppResult<Self> {
+        let endpoint = config
+            .s3_endpoint
+            .clone()
+            .ok_or(StorageErrorKind::MissingConfig("S3_ENDPOINT"))?
+            .trim_end_matches('/')
+            .to_string();
+        let bucket = config
+            .s3_bucket
+            .clone()
+            .ok_or(StorageErrorKind::MissingConfig("S3_BUCKET"))?;
+        let access_key = config
+            .s3_access_key
+            .clone()
+            .ok_or(StorageErrorKind::MissingConfig("S3_ACCESS_KEY"))?;
+        let secret_key = config
+            .s3_secret_key
+            .clone()
+            .ok_or(StorageErrorKind::MissingConfig("S3_SECRET_KEY"))?;
+        let host = reqwest::Url::parse(&endpoint)
+            .ok()
+            .and_then(|url| {
+                url.host_str().map(|host| match url.port() {
+                    Some(port) => format!("{}:{}", host, port),
+                    None => host.to_string(),
+                })
+            })
+            .ok_or(StorageErrorKind::MissingConfig("a valid S3_ENDPOINT"))?;
+        // When no dedicated public URL is configured, objects are addressed through the endpoint.
+        let public_url = if config.storage_public_url.starts_with("http") {
+            config.storage_public_url.trim_end_matches('/').to_string()
+        } else {
+            format!("{}/{}", endpoint, bucket)
+        };
+        Ok(Self {
+            http: Client::new(),
+            endpoint,
+            host,
+            bucket,
+            region: config.s3_region.clone(),
+            access_key,
+            secret_key,
+            public_url,
+        })
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        body: Vec<u8>,
+        content_type: Option<&str>,
+    ) -> AppResult<reqwest::Response> {
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let canonical_uri = format!("/{}/{}", uri_encode(&self.bucket), encode_key(key));
+        let payload_hash = hex::encode(Sha256::digest(&body));
+
+        let mut headers = vec![
+            ("host", self.host.clone()),
+            ("x-amz-content-sha256", payload_hash.clone()),
+            ("x-amz-date", amz_date.clone()),
+        ];
+        if let Some(content_type) = content_type {
+            headers.push(("content-type", content_type.to_string()));
+        }
+        headers.sort_by(|a, b| a.0.cmp(b.0));
+        let canonical_headers: String = headers
+            .iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+            .collect();
+        let signed_headers = headers
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(";");
+        let canonical_request = format!(
+            "{}\n{}\n\n{}\n{}\n{}",
+            method.as_str(),
+            canonical_uri,
+            canonical_headers,
+            signed_headers,
+            payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+        let signing_key = [self.region.as_str(), "s3", "aws4_request"].iter().fold(
+            hmac_sha256(
+                format!("AWS4{}", self.secret_key).as_bytes(),
+                date.as_bytes(),
+            ),
+            |key, part| hmac_sha256(&key, part.as_bytes()),
+        );
+        let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key, scope, signed_headers, signature
+        );
+
+        let mut request = self
+            .http
+            .request(method, format!("{}{}", self.endpoint, canonical_uri))
+            .header("authorization", authorization);
+        for (name, value) in headers.into_iter().filter(|(name, _)| *name != "host") {
+            request = request.header(name, value);
+        }
+        Ok(request
+            .body(body)
+            .send()
+            .await
+            .map_err(ExternalError::from)?)
+    }
+}
+
+#[async_trait]
+impl ObjectStorage for S3Storage {
+    async fn put_object(&self, key: &str, body: Vec<u8>, content_type: &str) -> AppResult<()> {
+        let response = self
+            .send(Method::PUT, key, body, Some(content_type))
+            .await?;
+        if !response.status().is_success() {
+            return Err(StorageErrorKind::UploadFailed(response.status().to_string()).into());
+        }
+        Ok(())
+    }
+    async fn get_object(&self, key: &str) -> AppResult<Option<Vec<u8>>> {
+        let response = self.send(Method::GET, key, Vec::new(), None).await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(StorageErrorKind::DownloadFailed(response.status().to_string()).into());
+        }
+        let body = response.bytes().await.map_err(ExternalError::from)?;
+        Ok(Some(body.to_vec()))
+    }
+    async fn delete_object(&self, key: &str) -> AppResult<()> {
+        let response = self.send(Method::DELETE, key, Vec::new(), None).await?;
+        if !response.status().is_success() {
+            return Err(StorageErrorKind::DeleteFailed(response.status().to_string()).into());
+        }
+        Ok(())
+    }
+    fn public_url(&self, key: &str) -> String {
+        format!("{}/{}", self.public_url, key)
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn uri_encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn encode_key(key: &str) -> String {
+    key.split('/').map(uri_encode).collect::<Vec<_>>().join("/")
+}
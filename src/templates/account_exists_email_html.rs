@@ -0,0 +1,64 @@
+pub const ACCOUNT_EXISTS_EMAIL_HTML: &str = r#"
+    <!DOCTYPE html>
+    <html lang="en">
+        <head>
+            <meta charset="UTF-8">
+            <meta name="viewport" content="width=device-width, initial-scale=1.0">
+            <title>Account Already Exists</title>
+            <style>
+                body {
+                    font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, Helvetica, Arial, sans-serif, 'Apple Color Emoji', 'Segoe UI Emoji', 'Segoe UI Symbol';
+                    background-color: #f4f4f4;
+                    margin: 0;
+                    padding: 0;
+                    -webkit-font-smoothing: antialiased;
+                }
+                .container {
+                    max-width: 600px;
+                    margin: 20px auto;
+                    background-color: #ffffff;
+                    padding: 30px;
+                    border-radius: 8px;
+                    box-shadow: 0 4px 12px rgba(0,0,0,0.05);
+                }
+                .header {
+                    border-bottom: 1px solid #e9e9e9;
+                    padding-bottom: 20px;
+                    margin-bottom: 20px;
+                    text-align: center;
+                }
+                .header h1 {
+                    color: #333333;
+                    font-size: 24px;
+                    margin: 0;
+                }
+                .content p {
+                    color: #555555;
+                    line-height: 1.6;
+                }
+                .footer {
+                    margin-top: 20px;
+                    text-align: center;
+                    font-size: 12px;
+                    color: #999999;
+                }
+            </style>
+        </head>
+        <body>
+            <div class="container">
+                <div class="header">
+                    <h1>You Already Have An Account</h1>
+                </div>
+                <div class="content">
+                    <p>Hello, {{username}}!</p>
+                    <p>Someone just tried to register a new account with this email address, but an account for it already exists.</p>
+                    <p>If this was you, simply log in. If you forgot your password, you can reset it from the login page.</p>
+                    <p>If this wasn't you, you can safely ignore this email.</p>
+                </div>
+                <div class="footer">
+                    <p>Best regards,<br>The Application Team</p>
+                </div>
+            </div>
+        </body>
+    </html>
+"#;
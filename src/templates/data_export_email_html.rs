@@ -0,0 +1,79 @@
+pub const DATA_EXPORT_EMAIL_HTML: &str = r#"
+    <!DOCTYPE html>
+    <html lang="en">
+        <head>
+            <meta charset="UTF-8">
+            <meta name="viewport" content="width=device-width, initial-scale=1.0">
+            <title>Your Data Export</title>
+            <style>
+                body {
+                    font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, Helvetica, Arial, sans-serif, 'Apple Color Emoji', 'Segoe UI Emoji', 'Segoe UI Symbol';
+                    background-color: #f4f4f4;
+                    margin: 0;
+                    padding: 0;
+                    -webkit-font-smoothing: antialiased;
+                }
+                .container {
+                    max-width: 600px;
+                    margin: 20px auto;
+                    background-color: #ffffff;
+                    padding: 30px;
+                    border-radius: 8px;
+                    box-shadow: 0 4px 12px rgba(0,0,0,0.05);
+                }
+                .header {
+                    border-bottom: 1px solid #e9e9e9;
+                    padding-bottom: 20px;
+                    margin-bottom: 20px;
+                    text-align: center;
+                }
+                .header h1 {
+                    color: #333333;
+                    font-size: 24px;
+                    margin: 0;
+                }
+                .content p {
+                    color: #555555;
+                    line-height: 1.6;
+                }
+                .token-display {
+                    background-color: #f0f0f0;
+                    padding: 15px;
+                    margin: 20px 0;
+                    text-align: center;
+                    border-radius: 4px;
+                }
+                .token-display a {
+                    font-size: 16px;
+                    color: #0080ff;
+                    word-break: break-all;
+                }
+                .footer {
+                    margin-top: 20px;
+                    text-align: center;
+                    font-size: 12px;
+                    color: #999999;
+                }
+            </style>
+        </head>
+        <body>
+            <div class="container">
+                <div class="header">
+                    <h1>Your Data Export Is Ready</h1>
+                </div>
+                <div class="content">
+                    <p>Hello, {{username}}!</p>
+                    <p>The export of the data we store about you is ready. Please use the following link to download it:</p>
+                    <div class="token-display">
+                        <a href="{{download_url}}">{{download_url}}</a>
+                    </div>
+                    <p>This link will expire at {{expires_at}}.</p>
+                    <p>If you did not request a data export, please change your password.</p>
+                </div>
+                <div class="footer">
+                    <p>Best regards,<br>The Application Team</p>
+                </div>
+            </div>
+        </body>
+    </html>
+"#;
@@ -0,0 +1,83 @@
+pub const LOGIN_ALERT_EMAIL_HTML: &str = r#"
+    <!DOCTYPE html>
+    <html lang="en">
+        <head>
+            <meta charset="UTF-8">
+            <meta name="viewport" content="width=device-width, initial-scale=1.0">
+            <title>New Sign-in To Your Account</title>
+            <style>
+                body {
+                    font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, Helvetica, Arial, sans-serif, 'Apple Color Emoji', 'Segoe UI Emoji', 'Segoe UI Symbol';
+                    background-color: #f4f4f4;
+                    margin: 0;
+                    padding: 0;
+                    -webkit-font-smoothing: antialiased;
+                }
+                .container {
+                    max-width: 600px;
+                    margin: 20px auto;
+                    background-color: #ffffff;
+                    padding: 30px;
+                    border-radius: 8px;
+                    box-shadow: 0 4px 12px rgba(0,0,0,0.05);
+                }
+                .header {
+                    border-bottom: 1px solid #e9e9e9;
+                    padding-bottom: 20px;
+                    margin-bottom: 20px;
+                    text-align: center;
+                }
+                .header h1 {
+                    color: #333333;
+                    font-size: 24px;
+                    margin: 0;
+                }
+                .content p {
+                    color: #555555;
+                    line-height: 1.6;
+                }
+                .token-display {
+                    background-color: #f0f0f0;
+                    padding: 15px;
+                    margin: 20px 0;
+                    text-align: center;
+                    border-radius: 4px;
+                }
+                .token-display a {
+                    font-size: 16px;
+                    color: #0080ff;
+                    word-break: break-all;
+                }
+                .footer {
+                    margin-top: 20px;
+                    text-align: center;
+                    font-size: 12px;
+                    color: #999999;
+                }
+            </style>
+        </head>
+        <body>
+            <div class="container">
+                <div class="header">
+                    <h1>New Sign-in To Your Account</h1>
+                </div>
+                <div class="content">
+                    <p>Hello, {{username}}!</p>
+                    <p>Your account was just signed in to from a {{reason}}:</p>
+                    <ul>
+                        <li>Device: {{device}}</li>
+                        <li>IP address: {{ip}}</li>
+                        <li>Time: {{time}}</li>
+                    </ul>
+                    <p>If this was you, you can ignore this email. If it wasn't, use the following link to sign that device out and stop trusting it, then change your password:</p>
+                    <div class="token-display">
+                        <a href="{{not_me_url}}">{{not_me_url}}</a>
+                    </div>
+                </div>
+                <div class="footer">
+                    <p>Best regards,<br>The Application Team</p>
+                </div>
+            </div>
+        </body>
+    </html>
+"#;
@@ -1,2 +1,6 @@
+pub mod account_exists_email_html;
+pub mod data_export_email_html;
+pub mod login_alert_email_html;
+pub mod report_login_html;
 pub mod reset_password_email_html;
 pub mod verification_email_html;
@@ -0,0 +1,55 @@
+pub const REPORT_LOGIN_HTML: &str = r#"
+    <!DOCTYPE html>
+    <html lang="en">
+        <head>
+            <meta charset="UTF-8">
+            <meta name="viewport" content="width=device-width, initial-scale=1.0">
+            <meta name="referrer" content="no-referrer">
+            <title>Wasn't You?</title>
+            <style>
+                body {
+                    font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, Helvetica, Arial, sans-serif, 'Apple Color Emoji', 'Segoe UI Emoji', 'Segoe UI Symbol';
+                    background-color: #f4f4f4;
+                    margin: 0;
+                    padding: 0;
+                    -webkit-font-smoothing: antialiased;
+                }
+                .container {
+                    max-width: 600px;
+                    margin: 20px auto;
+                    background-color: #ffffff;
+                    padding: 30px;
+                    border-radius: 8px;
+                    box-shadow: 0 4px 12px rgba(0,0,0,0.05);
+                    text-align: center;
+                }
+                h1 {
+                    color: #333333;
+                    font-size: 24px;
+                }
+                p {
+                    color: #555555;
+                    line-height: 1.6;
+                }
+                button {
+                    background-color: #d93025;
+                    color: #ffffff;
+                    border: none;
+                    border-radius: 4px;
+                    padding: 12px 24px;
+                    font-size: 16px;
+                    cursor: pointer;
+                }
+            </style>
+        </head>
+        <body>
+            <div class="container">
+                <h1>Wasn't You?</h1>
+                <p>Confirm to sign that device out and stop trusting it. Change your password afterwards.</p>
+                <form method="post">
+                    <button type="submit">Sign the device out</button>
+                </form>
+            </div>
+        </body>
+    </html>
+"#;
@@ -0,0 +1,53 @@
+use std::io::Cursor;
+
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits, imageops::FilterType};
+
+use surrealdb::sql::Thing;
+
+use crate::core::{error::avatar::AvatarErrorKind, result::AppResult};
+
+pub const AVATAR_CONTENT_TYPES: [&str; 3] = ["image/png", "image/jpeg", "image/webp"];
+pub const AVATAR_SIZES: [(&str, u32); 3] = [("small", 64), ("medium", 128), ("large", 256)];
+const AVATAR_MAX_DIMENSION: u32 = 8192;
+
+pub fn avatar_key(user_id: &Thing, size: &str) -> String {
+    format!("avatars/{}/{}.png", user_id.id.to_raw(), size)
+}
+
+/// Decodes an uploaded image and re-encodes it as PNG in every size of `AVATAR_SIZES`.
+/// Re-encoding from raw pixels drops EXIF, XMP and any other embedded metadata.
+pub fn process_avatar(bytes: &[u8]) -> AppResult<Vec<(&'static str, Vec<u8>)>> {
+    let format = image::guess_format(bytes).map_err(|_| AvatarErrorKind::UnsupportedImageType)?;
+    if !matches!(
+        format,
+        ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::WebP
+    ) {
+        return Err(AvatarErrorKind::UnsupportedImageType.into());
+    }
+    let mut reader = ImageReader::with_format(Cursor::new(bytes), format);
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(AVATAR_MAX_DIMENSION);
+    limits.max_image_height = Some(AVATAR_MAX_DIMENSION);
+    reader.limits(limits);
+    let mut decoder = reader
+        .into_decoder()
+        .map_err(|_| AvatarErrorKind::InvalidImage)?;
+    let orientation = decoder
+        .orientation()
+        .map_err(|_| AvatarErrorKind::InvalidImage)?;
+    let mut image =
+        DynamicImage::from_decoder(decoder).map_err(|_| AvatarErrorKind::InvalidImage)?;
+    image.apply_orientation(orientation);
+
+    AVATAR_SIZES
+        .iter()
+        .map(|(name, size)| {
+            let mut encoded = Cursor::new(Vec::new());
+            image
+                .resize_to_fill(*size, *size, FilterType::Lanczos3)
+                .write_to(&mut encoded, ImageFormat::Png)
+                .map_err(|_| AvatarErrorKind::InvalidImage)?;
+            Ok((*name, encoded.into_inner()))
+        })
+        .collect()
+}
@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use reqwest::Client;
+use sha1::{Digest, Sha1};
+
+use crate::core::{error::external::ExternalError, result::AppResult};
+
+/// Source of SHA-1 hash ranges in the k-anonymity format: given the first five hex characters
+/// of a hash, return `SUFFIX:COUNT` lines. Only the prefix ever leaves the process.
+#[async_trait]
+pub trait BreachedPasswordSource: std::fmt::Debug + Send + Sync {
+    async fn fetch_range(&self, prefix: &str) -> AppResult<String>;
+}
+
+#[derive(Debug)]
+pub struct LocalRangeSource {
+    pub dir: PathBuf,
+}
+
+#[async_trait]
+impl BreachedPasswordSource for LocalRangeSource {
+    async fn fetch_range(&self, prefix: &str) -> AppResult<String> {
+        match tokio::fs::read_to_string(self.dir.join(format!("{}.txt", prefix))).await {
+            Ok(range) => Ok(range),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+            Err(e) => Err(ExternalError::from(e).into()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct HibpRangeSource {
+    pub http: Client,
+    pub api_url: String,
+}
+
+#[async_trait]
+impl BreachedPasswordSource for HibpRangeSource {
+    async fn fetch_range(&self, prefix: &str) -> AppResult<String> {
+        Ok(self
+            .http
+            .get(format!("{}/{}", self.api_url.trim_end_matches('/'), prefix))
+            .header("Add-Padding", "true")
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(ExternalError::from)?
+            .text()
+            .await
+            .map_err(ExternalError::from)?)
+    }
+}
+
+pub async fn is_password_breached(
+    source: &dyn BreachedPasswordSource,
+    password: &str,
+) -> AppResult<bool> {
+    let hash = hex::encode_upper(Sha1::digest(password.as_bytes()));
+    let (prefix, suffix) = hash.split_at(5);
+    let range = source.fetch_range(prefix).await?;
+    // Padding entries returned by the API have a count of 0 and must not match.
+    Ok(range.lines().any(|line| {
+        line.split_once(':').is_some_and(|(candidate, count)| {
+            candidate.trim().eq_ignore_ascii_case(suffix) && count.trim() != "0"
+        })
+    }))
+}
@@ -0,0 +1,102 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{
+    core::{error::external::ExternalError, result::AppResult},
+    models::challenge::{Challenge, ChallengeKind},
+};
+
+/// Issues challenges and checks their answers. Issued challenges are stored by the caller, a
+/// verifier only describes what the client has to solve and whether a solution is right.
+#[async_trait]
+pub trait ChallengeVerifier: std::fmt::Debug + Send + Sync {
+    fn new_challenge(&self, id: String, expires_at: DateTime<Utc>) -> Challenge;
+    async fn verify(&self, challenge: &Challenge, solution: &str, ip: &str) -> AppResult<bool>;
+}
+
+#[derive(Debug)]
+pub struct ProofOfWorkVerifier {
+    pub difficulty: u32,
+}
+
+#[async_trait]
+impl ChallengeVerifier for ProofOfWorkVerifier {
+    fn new_challenge(&self, id: String, expires_at: DateTime<Utc>) -> Challenge {
+        Challenge {
+            id,
+            kind: ChallengeKind::ProofOfWork,
+            nonce: Some(Uuid::new_v4().simple().to_string()),
+            difficulty: Some(self.difficulty),
+            site_key: None,
+            expires_at,
+        }
+    }
+    async fn verify(&self, challenge: &Challenge, solution: &str, _ip: &str) -> AppResult<bool> {
+        let (Some(nonce), Some(difficulty)) = (&challenge.nonce, challenge.difficulty) else {
+            return Ok(false);
+        };
+        let hash = Sha256::digest(format!("{}{}", nonce, solution).as_bytes());
+        Ok(leading_zero_bits(&hash) >= difficulty)
+    }
+}
+
+/// hCaptcha, Turnstile and reCAPTCHA share the same `siteverify` form protocol.
+#[derive(Debug)]
+pub struct CaptchaVerifier {
+    pub http: Client,
+    pub verify_url: String,
+    pub site_key: Option<String>,
+    pub secret: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SiteVerifyResponse {
+    success: bool,
+}
+
+#[async_trait]
+impl ChallengeVerifier for CaptchaVerifier {
+    fn new_challenge(&self, id: String, expires_at: DateTime<Utc>) -> Challenge {
+        Challenge {
+            id,
+            kind: ChallengeKind::Captcha,
+            nonce: None,
+            difficulty: None,
+            site_key: self.site_key.clone(),
+            expires_at,
+        }
+    }
+    async fn verify(&self, _challenge: &Challenge, solution: &str, ip: &str) -> AppResult<bool> {
+        let response: SiteVerifyResponse = self
+            .http
+            .post(&self.verify_url)
+            .form(&[
+                ("secret", self.secret.as_str()),
+                ("response", solution),
+                ("remoteip", ip),
+            ])
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(ExternalError::from)?
+            .json()
+            .await
+            .map_err(ExternalError::from)?;
+        Ok(response.success)
+    }
+}
+
+fn leading_zero_bits(hash: &[u8]) -> u32 {
+    let mut bits = 0;
+    for byte in hash {
+        bits += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+    bits
+}
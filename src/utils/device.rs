@@ -1,31 +1,207 @@
-use uaparser_rs::UAParser;
-
-pub fn parse_user_agent_detailed(user_agent: &str) -> (String, String, String) {
-    let uap = UAParser::from_yaml("./regexes.yaml").unwrap();
-    let client = uap.parse(user_agent);
-    let user_agent = format!(
-        "{} {}.{}.{}.{}",
-        client.user_agent.family,
-        client.user_agent.major.unwrap_or("Unknown".to_string()),
-        client.user_agent.minor.unwrap_or("Unknown".to_string()),
-        client.user_agent.patch.unwrap_or("Unknown".to_string()),
-        client
-            .user_agent
-            .patch_minor
-            .unwrap_or("Unknown".to_string()),
-    );
-    let os = format!(
-        "{} {}.{}.{}",
-        client.os.family,
-        client.os.major.unwrap_or("Unknown".to_string()),
-        client.os.minor.unwrap_or("Unknown".to_string()),
-        client.os.patch.unwrap_or("Unknown".to_string()),
-    );
-    let device = format!(
-        "{}: {} {}",
-        client.device.family,
-        client.device.brand.unwrap_or("Unknown".to_string()),
-        client.device.model.unwrap_or("Unknown".to_string()),
-    );
-    (user_agent, os, device)
+use std::{num::NonZeroUsize, sync::Mutex};
+
+use hmac::{Hmac, Mac};
+use lru::LruCache;
+use serde::Serialize;
+use sha2::Sha256;
+use surrealdb::sql::Thing;
+use uaparser_rs::{Client, UAParser};
+use uuid::Uuid;
+
+use crate::{
+    config::user_agent::UserAgentConfig,
+    core::{
+        error::{external::ExternalError, other::OtherErrorKind},
+        result::AppResult,
+    },
+    models::device::Device,
+};
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const DEVICE_COOKIE_NAME: &str = "device_id";
+
+/// uap-core regexes shipped with the binary, so parsing doesn't depend on the working directory.
+const EMBEDDED_REGEXES_YAML: &str = include_str!("../../regexes.yaml");
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParsedSoftware {
+    pub family: String,
+    pub major: Option<String>,
+    pub minor: Option<String>,
+    pub patch: Option<String>,
+    pub patch_minor: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParsedDevice {
+    pub family: String,
+    pub brand: Option<String>,
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParsedUserAgent {
+    pub browser: ParsedSoftware,
+    pub os: ParsedSoftware,
+    pub device: ParsedDevice,
+}
+
+impl ParsedUserAgent {
+    /// Browser label as stored on devices, e.g. `Chrome 126.0.0.0`.
+    pub fn browser_label(&self) -> String {
+        format!(
+            "{} {}.{}.{}.{}",
+            self.browser.family,
+            self.browser.major.as_deref().unwrap_or("Unknown"),
+            self.browser.minor.as_deref().unwrap_or("Unknown"),
+            self.browser.patch.as_deref().unwrap_or("Unknown"),
+            self.browser.patch_minor.as_deref().unwrap_or("Unknown"),
+        )
+    }
+    /// OS label as stored on devices, e.g. `Mac OS X 10.15.7`.
+    pub fn os_label(&self) -> String {
+        format!(
+            "{} {}.{}.{}",
+            self.os.family,
+            self.os.major.as_deref().unwrap_or("Unknown"),
+            self.os.minor.as_deref().unwrap_or("Unknown"),
+            self.os.patch.as_deref().unwrap_or("Unknown"),
+        )
+    }
+    /// Whether a stored device looks like the same browser install: same browser family and
+    /// major version on the same OS family. Minor and patch updates don't matter.
+    pub fn matches_device(&self, device: &Device) -> bool {
+        match (&device.browser_family, &device.os_family) {
+            (Some(browser_family), Some(os_family)) => {
+                *browser_family == self.browser.family
+                    && device.browser_major == self.browser.major
+                    && *os_family == self.os.family
+            }
+            // Devices recorded before the families were stored only have the labels.
+            _ => {
+                device.user_agent == self.browser_label()
+                    && device.os == self.os_label()
+                    && device.device == self.device_label()
+            }
+        }
+    }
+    /// Device label as stored on devices, e.g. `Mac: Apple Mac`.
+    pub fn device_label(&self) -> String {
+        format!(
+            "{}: {} {}",
+            self.device.family,
+            self.device.brand.as_deref().unwrap_or("Unknown"),
+            self.device.model.as_deref().unwrap_or("Unknown"),
+        )
+    }
+}
+
+impl From<Client> for ParsedUserAgent {
+    fn from(client: Client) -> Self {
+        Self {
+            browser: ParsedSoftware {
+                family: client.user_agent.family,
+                major: client.user_agent.major,
+                minor: client.user_agent.minor,
+                patch: client.user_agent.patch,
+                patch_minor: client.user_agent.patch_minor,
+            },
+            os: ParsedSoftware {
+                family: client.os.family,
+                major: client.os.major,
+                minor: client.os.minor,
+                patch: client.os.patch,
+                patch_minor: client.os.patch_minor,
+            },
+            device: ParsedDevice {
+                family: client.device.family,
+                brand: client.device.brand,
+                model: client.device.model,
+            },
+        }
+    }
+}
+
+/// User agent parser built once at startup, with an LRU cache in front of the regex matching.
+#[derive(Debug)]
+pub struct UserAgentParser {
+    parser: UAParser,
+    cache: Mutex<LruCache<String, ParsedUserAgent>>,
+}
+
+impl UserAgentParser {
+    pub fn new(config: &UserAgentConfig) -> AppResult<Self> {
+        let parser = match &config.user_agent_regexes_path {
+            Some(path) => load_parser(path)?,
+            None => {
+                // The parser can only read from a file, so the embedded copy is staged in a
+                // temporary one.
+                let path =
+                    std::env::temp_dir().join(format!("uap-regexes-{}.yaml", Uuid::new_v4()));
+                std::fs::write(&path, EMBEDDED_REGEXES_YAML).map_err(ExternalError::from)?;
+                let parser = load_parser(&path.to_string_lossy());
+                let _ = std::fs::remove_file(&path);
+                parser?
+            }
+        };
+        let cache_size =
+            NonZeroUsize::new(config.user_agent_cache_size).unwrap_or(NonZeroUsize::MIN);
+        Ok(Self {
+            parser,
+            cache: Mutex::new(LruCache::new(cache_size)),
+        })
+    }
+
+    pub fn parse(&self, user_agent: &str) -> ParsedUserAgent {
+        if let Some(parsed) = self
+            .cache
+            .lock()
+            .ok()
+            .and_then(|mut cache| cache.get(user_agent).cloned())
+        {
+            return parsed;
+        }
+        let parsed = ParsedUserAgent::from(self.parser.parse(user_agent));
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.put(user_agent.to_string(), parsed.clone());
+        }
+        parsed
+    }
+}
+
+fn load_parser(path: &str) -> AppResult<UAParser> {
+    UAParser::from_yaml(path).map_err(|e| {
+        OtherErrorKind::Error(format!(
+            "Failed to load user agent regexes {}: {:?}",
+            path, e
+        ))
+        .into()
+    })
+}
+
+/// Value of the long-lived device cookie: the device id followed by its HMAC-SHA256 signature.
+pub fn sign_device_id(device_id: &Thing, secret: &[u8]) -> String {
+    let device_id = device_id.id.to_raw();
+    format!(
+        "{}.{}",
+        device_id,
+        hex::encode(device_signature(&device_id, secret))
+    )
+}
+
+/// Returns the raw device id of a device cookie whose signature is valid.
+pub fn verify_device_cookie(value: &str, secret: &[u8]) -> Option<String> {
+    let (device_id, signature) = value.rsplit_once('.')?;
+    let signature = hex::decode(signature).ok()?;
+    let mut mac = HmacSha256::new_from_slice(secret).ok()?;
+    mac.update(device_id.as_bytes());
+    mac.verify_slice(&signature).ok()?;
+    Some(device_id.to_string())
+}
+
+fn device_signature(device_id: &str, secret: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(device_id.as_bytes());
+    mac.finalize().into_bytes().to_vec()
 }
@@ -0,0 +1,131 @@
+use std::{
+    net::IpAddr,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::SystemTime,
+};
+
+use maxminddb::{Reader, geoip2};
+
+use crate::{
+    config::geoip::GeoIpConfig,
+    core::{error::other::OtherErrorKind, result::AppResult},
+    models::geo_location::GeoLocation,
+};
+
+/// An opened database file and the modification time it was read at.
+#[derive(Debug)]
+struct GeoIpDatabase {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    reader: Arc<Reader<Vec<u8>>>,
+}
+
+impl GeoIpDatabase {
+    fn open(path: &Path) -> AppResult<Self> {
+        let modified = file_modified(path);
+        let reader = Reader::open_readfile(path).map_err(|e| {
+            OtherErrorKind::Error(format!(
+                "Failed to open GeoIP database {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            modified,
+            reader: Arc::new(reader),
+        })
+    }
+}
+
+/// Resolves IP addresses with local MaxMind-format databases, no request leaves the server.
+/// The files are read into memory and swapped in place when they change on disk.
+#[derive(Debug)]
+pub struct GeoIpResolver {
+    city: RwLock<Option<GeoIpDatabase>>,
+    asn: RwLock<Option<GeoIpDatabase>>,
+}
+
+impl GeoIpResolver {
+    pub fn new(config: &GeoIpConfig) -> AppResult<Self> {
+        let open = |path: &Option<String>| {
+            path.as_deref()
+                .map(|path| GeoIpDatabase::open(Path::new(path)))
+                .transpose()
+        };
+        Ok(Self {
+            city: RwLock::new(open(&config.geoip_city_db_path)?),
+            asn: RwLock::new(open(&config.geoip_asn_db_path)?),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        current_reader(&self.city).is_some() || current_reader(&self.asn).is_some()
+    }
+
+    /// Location of an IP address, `None` for unparsable, private or unknown addresses.
+    pub fn lookup(&self, ip: &str) -> Option<GeoLocation> {
+        let ip: IpAddr = ip.parse().ok()?;
+        let mut location = GeoLocation::default();
+        if let Some(reader) = current_reader(&self.city)
+            && let Ok(Some(city)) = reader.lookup::<geoip2::City>(ip)
+        {
+            location.country = city
+                .country
+                .and_then(|country| country.iso_code)
+                .map(str::to_string);
+            location.city = city
+                .city
+                .and_then(|city| city.names)
+                .and_then(|names| names.get("en").map(|name| name.to_string()));
+            if let Some(coordinates) = city.location {
+                location.latitude = coordinates.latitude;
+                location.longitude = coordinates.longitude;
+            }
+        }
+        if let Some(reader) = current_reader(&self.asn)
+            && let Ok(Some(asn)) = reader.lookup::<geoip2::Asn>(ip)
+        {
+            location.asn = asn.autonomous_system_number;
+            location.as_organization = asn.autonomous_system_organization.map(str::to_string);
+        }
+        (location != GeoLocation::default()).then_some(location)
+    }
+
+    /// Re-reads the database files whose modification time changed and returns how many were
+    /// swapped in. A file that fails to load keeps the previous copy in use.
+    pub fn reload_if_changed(&self) -> AppResult<usize> {
+        let mut reloaded = 0;
+        for database in [&self.city, &self.asn] {
+            let path = match database.read() {
+                Ok(guard) => match guard.as_ref() {
+                    Some(current) if file_modified(&current.path) != current.modified => {
+                        current.path.clone()
+                    }
+                    _ => continue,
+                },
+                Err(_) => continue,
+            };
+            let fresh = GeoIpDatabase::open(&path)?;
+            if let Ok(mut guard) = database.write() {
+                *guard = Some(fresh);
+                reloaded += 1;
+            }
+        }
+        Ok(reloaded)
+    }
+}
+
+fn current_reader(database: &RwLock<Option<GeoIpDatabase>>) -> Option<Arc<Reader<Vec<u8>>>> {
+    database
+        .read()
+        .ok()
+        .and_then(|guard| guard.as_ref().map(|database| database.reader.clone()))
+}
+
+fn file_modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
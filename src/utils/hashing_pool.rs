@@ -0,0 +1,131 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Receiver, Sender},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use tokio::sync::{Semaphore, oneshot};
+use tracing::{debug, error, warn};
+
+use crate::core::{error::hashing::HashingErrorKind, result::AppResult};
+use crate::utils::metrics::LatencyHistogram;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Upper bounds in milliseconds of the hash latency histogram buckets.
+pub const HASH_LATENCY_BUCKETS_MS: [u64; 8] = [10, 25, 50, 100, 250, 500, 1000, 2500];
+
+#[derive(Debug)]
+pub struct HashingMetrics {
+    /// Time spent computing hashes and verifications on the pool.
+    pub latency: LatencyHistogram,
+    /// Time spent waiting for a free worker.
+    pub queue_wait: LatencyHistogram,
+    /// Requests turned away because no worker freed up within the queue timeout.
+    pub rejected: AtomicU64,
+}
+
+impl Default for HashingMetrics {
+    fn default() -> Self {
+        Self {
+            latency: LatencyHistogram::new(&HASH_LATENCY_BUCKETS_MS),
+            queue_wait: LatencyHistogram::new(&HASH_LATENCY_BUCKETS_MS),
+            rejected: AtomicU64::new(0),
+        }
+    }
+}
+
+/// Dedicated threads for password hashing, so Argon2 never runs on the Tokio workers.
+/// At most `concurrency` jobs are admitted at once; callers wait up to `queue_timeout` for a
+/// slot and get `HashingErrorKind::Overloaded` after that.
+#[derive(Debug)]
+pub struct HashingPool {
+    sender: Sender<Job>,
+    permits: Arc<Semaphore>,
+    queue_timeout: Duration,
+    pub metrics: Arc<HashingMetrics>,
+}
+
+impl HashingPool {
+    pub fn new(concurrency: usize, queue_timeout: Duration) -> Self {
+        let concurrency = concurrency.max(1);
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for index in 0..concurrency {
+            let receiver = receiver.clone();
+            if let Err(e) = thread::Builder::new()
+                .name(format!("password-hasher-{}", index))
+                .spawn(move || run_worker(receiver))
+            {
+                error!("❌ Failed to start password hashing worker: {}", e);
+            }
+        }
+        Self {
+            sender,
+            permits: Arc::new(Semaphore::new(concurrency)),
+            queue_timeout,
+            metrics: Arc::new(HashingMetrics::default()),
+        }
+    }
+
+    pub async fn run<T, F>(&self, job: F) -> AppResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> AppResult<T> + Send + 'static,
+    {
+        let queued_at = Instant::now();
+        let permit =
+            match tokio::time::timeout(self.queue_timeout, self.permits.clone().acquire_owned())
+                .await
+            {
+                Ok(Ok(permit)) => permit,
+                Ok(Err(_)) => return Err(HashingErrorKind::WorkerStopped.into()),
+                Err(_) => {
+                    self.metrics.rejected.fetch_add(1, Ordering::Relaxed);
+                    warn!("⚠️ Password hashing pool is saturated, rejecting request");
+                    return Err(HashingErrorKind::Overloaded.into());
+                }
+            };
+        self.metrics.queue_wait.observe(queued_at.elapsed());
+
+        let (tx, rx) = oneshot::channel();
+        let metrics = self.metrics.clone();
+        let job: Job = Box::new(move || {
+            let started_at = Instant::now();
+            let result = job();
+            let elapsed = started_at.elapsed();
+            metrics.latency.observe(elapsed);
+            debug!("Password hashing took {:?}", elapsed);
+            // The permit is only released once the worker is free again.
+            drop(permit);
+            let _ = tx.send(result);
+        });
+        if self.sender.send(job).is_err() {
+            return Err(HashingErrorKind::WorkerStopped.into());
+        }
+        rx.await.map_err(|_| HashingErrorKind::WorkerStopped)?
+    }
+}
+
+fn run_worker(receiver: Arc<Mutex<Receiver<Job>>>) {
+    loop {
+        let job = match receiver.lock() {
+            Ok(receiver) => receiver.recv(),
+            Err(_) => return,
+        };
+        match job {
+            // A panicking job drops its reply channel, the worker itself keeps serving.
+            Ok(job) => {
+                if panic::catch_unwind(AssertUnwindSafe(job)).is_err() {
+                    error!("❌ Password hashing job panicked");
+                }
+            }
+            Err(_) => return,
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use std::net::IpAddr;
+
+/// Whether two addresses belong to the same network, taken as the /24 for IPv4 and the /48
+/// for IPv6. Unparsable addresses are never in the same network.
+pub fn same_network(a: &str, b: &str) -> bool {
+    match (a.parse::<IpAddr>(), b.parse::<IpAddr>()) {
+        (Ok(IpAddr::V4(a)), Ok(IpAddr::V4(b))) => a.octets()[..3] == b.octets()[..3],
+        (Ok(IpAddr::V6(a)), Ok(IpAddr::V6(b))) => a.segments()[..3] == b.segments()[..3],
+        _ => false,
+    }
+}
@@ -14,3 +14,20 @@ pub async fn send_mail(
     let params = CreateEmailBaseOptions::new(from, to, subject).with_html(html);
     resend.emails.send(params).await
 }
+
+/// Escapes a value interpolated into an email template, so user-controlled text such as a
+/// User-Agent can't add markup or links.
+pub fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,339 @@
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    sync::{
+        Mutex, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use once_cell::sync::Lazy;
+
+use crate::utils::hashing_pool::HashingMetrics;
+
+/// Upper bounds in milliseconds of the HTTP request and database query latency buckets.
+pub const LATENCY_BUCKETS_MS: [u64; 11] = [1, 5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000];
+
+/// Process-wide counters, rendered in the Prometheus text format by `/metrics`. The password
+/// hashing pool keeps its own and is rendered along with them.
+pub static METRICS: Lazy<Metrics> = Lazy::new(Metrics::default);
+
+#[derive(Debug)]
+pub struct LatencyHistogram {
+    bounds_ms: &'static [u64],
+    /// One counter per bound, plus a last one for slower observations.
+    pub buckets: Vec<AtomicU64>,
+    pub count: AtomicU64,
+    pub sum_micros: AtomicU64,
+}
+
+impl LatencyHistogram {
+    pub fn new(bounds_ms: &'static [u64]) -> Self {
+        Self {
+            bounds_ms,
+            buckets: (0..=bounds_ms.len()).map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+
+    pub fn observe(&self, elapsed: Duration) {
+        let millis = elapsed.as_millis() as u64;
+        let bucket = self
+            .bounds_ms
+            .iter()
+            .position(|bound| millis <= *bound)
+            .unwrap_or(self.bounds_ms.len());
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Writes the `_bucket`, `_sum` and `_count` series, in seconds. `labels` is either empty
+    /// or a list like `route="/health",status="200"`.
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let separator = if labels.is_empty() { "" } else { "," };
+        let mut cumulative = 0;
+        for (bound, bucket) in self.bounds_ms.iter().zip(&self.buckets) {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let le = *bound as f64 / 1000.0;
+            let _ = writeln!(
+                out,
+                "{name}_bucket{{{labels}{separator}le=\"{le}\"}} {cumulative}"
+            );
+        }
+        let count = self.count.load(Ordering::Relaxed);
+        let sum = self.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        let _ = writeln!(
+            out,
+            "{name}_bucket{{{labels}{separator}le=\"+Inf\"}} {count}"
+        );
+        let labels = if labels.is_empty() {
+            String::new()
+        } else {
+            format!("{{{labels}}}")
+        };
+        let _ = writeln!(out, "{name}_sum{labels} {sum}");
+        let _ = writeln!(out, "{name}_count{labels} {count}");
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum LoginOutcome {
+    Success,
+    /// Unknown email or wrong password.
+    Failed,
+    /// Refused by the risk assessment.
+    Blocked,
+    /// A code was emailed for an unverified account, a new device or a risky login.
+    VerificationRequired,
+    DeletionScheduled,
+}
+
+impl LoginOutcome {
+    const ALL: [LoginOutcome; 5] = [
+        LoginOutcome::Success,
+        LoginOutcome::Failed,
+        LoginOutcome::Blocked,
+        LoginOutcome::VerificationRequired,
+        LoginOutcome::DeletionScheduled,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            LoginOutcome::Success => "success",
+            LoginOutcome::Failed => "failed",
+            LoginOutcome::Blocked => "blocked",
+            LoginOutcome::VerificationRequired => "verification_required",
+            LoginOutcome::DeletionScheduled => "deletion_scheduled",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct HttpRequestKey {
+    method: String,
+    route: String,
+    status: u16,
+}
+
+#[derive(Debug, Default)]
+pub struct Metrics {
+    http_requests: Mutex<HashMap<HttpRequestKey, LatencyHistogram>>,
+    /// `429 Too Many Requests` answers per route, e.g. data exports requested too often.
+    rate_limit_hits: Mutex<HashMap<String, u64>>,
+    /// Query latency per database, e.g. `SurrealDB` or `Redis`.
+    db_queries: Mutex<HashMap<&'static str, LatencyHistogram>>,
+    registrations: AtomicU64,
+    logins: [AtomicU64; LoginOutcome::ALL.len()],
+    verification_emails_sent: AtomicU64,
+}
+
+impl Metrics {
+    /// `route` is the route template, e.g. `/api/v1/user/me`, so ids in paths don't each get
+    /// their own series.
+    pub fn record_http_request(&self, method: &str, route: &str, status: u16, elapsed: Duration) {
+        if status == 429 {
+            *lock(&self.rate_limit_hits)
+                .entry(route.to_string())
+                .or_default() += 1;
+        }
+        lock(&self.http_requests)
+            .entry(HttpRequestKey {
+                method: method.to_string(),
+                route: route.to_string(),
+                status,
+            })
+            .or_insert_with(|| LatencyHistogram::new(&LATENCY_BUCKETS_MS))
+            .observe(elapsed);
+    }
+
+    pub fn record_db_query(&self, database: &'static str, elapsed: Duration) {
+        lock(&self.db_queries)
+            .entry(database)
+            .or_insert_with(|| LatencyHistogram::new(&LATENCY_BUCKETS_MS))
+            .observe(elapsed);
+    }
+
+    pub fn record_registration(&self) {
+        self.registrations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_login(&self, outcome: LoginOutcome) {
+        self.logins[outcome as usize].fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_verification_email_sent(&self) {
+        self.verification_emails_sent
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn render(&self, hashing: &HashingMetrics) -> String {
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "http_requests_total",
+            "counter",
+            "HTTP requests by method, route and status.",
+        );
+        let http_requests = lock(&self.http_requests);
+        let mut keys: Vec<&HttpRequestKey> = http_requests.keys().collect();
+        keys.sort_by(|a, b| (&a.route, &a.method, a.status).cmp(&(&b.route, &b.method, b.status)));
+        for key in &keys {
+            let _ = writeln!(
+                out,
+                "http_requests_total{{{}}} {}",
+                http_labels(key),
+                http_requests[*key].count.load(Ordering::Relaxed)
+            );
+        }
+        header(
+            &mut out,
+            "http_request_duration_seconds",
+            "histogram",
+            "HTTP request latency by method, route and status.",
+        );
+        for key in &keys {
+            http_requests[*key].render(
+                &mut out,
+                "http_request_duration_seconds",
+                &http_labels(key),
+            );
+        }
+        drop(http_requests);
+
+        header(
+            &mut out,
+            "rate_limit_hits_total",
+            "counter",
+            "Requests answered with 429 Too Many Requests, by route.",
+        );
+        let rate_limit_hits = lock(&self.rate_limit_hits);
+        let mut routes: Vec<&String> = rate_limit_hits.keys().collect();
+        routes.sort();
+        for route in routes {
+            let _ = writeln!(
+                out,
+                "rate_limit_hits_total{{route=\"{}\"}} {}",
+                escape(route),
+                rate_limit_hits[route]
+            );
+        }
+        drop(rate_limit_hits);
+
+        header(
+            &mut out,
+            "registrations_total",
+            "counter",
+            "Accounts created.",
+        );
+        let _ = writeln!(
+            out,
+            "registrations_total {}",
+            self.registrations.load(Ordering::Relaxed)
+        );
+        header(
+            &mut out,
+            "logins_total",
+            "counter",
+            "Login attempts by outcome.",
+        );
+        for outcome in LoginOutcome::ALL {
+            let _ = writeln!(
+                out,
+                "logins_total{{outcome=\"{}\"}} {}",
+                outcome.label(),
+                self.logins[outcome as usize].load(Ordering::Relaxed)
+            );
+        }
+        header(
+            &mut out,
+            "verification_emails_sent_total",
+            "counter",
+            "Verification codes emailed for logins.",
+        );
+        let _ = writeln!(
+            out,
+            "verification_emails_sent_total {}",
+            self.verification_emails_sent.load(Ordering::Relaxed)
+        );
+
+        header(
+            &mut out,
+            "db_query_duration_seconds",
+            "histogram",
+            "Query latency by database, timeouts included.",
+        );
+        let db_queries = lock(&self.db_queries);
+        let mut databases: Vec<&&'static str> = db_queries.keys().collect();
+        databases.sort();
+        for database in databases {
+            db_queries[*database].render(
+                &mut out,
+                "db_query_duration_seconds",
+                &format!("database=\"{}\"", database),
+            );
+        }
+        drop(db_queries);
+
+        header(
+            &mut out,
+            "password_hashing_duration_seconds",
+            "histogram",
+            "Time spent computing Argon2 hashes and verifications.",
+        );
+        hashing
+            .latency
+            .render(&mut out, "password_hashing_duration_seconds", "");
+        header(
+            &mut out,
+            "password_hashing_queue_wait_seconds",
+            "histogram",
+            "Time spent waiting for a free password hashing worker.",
+        );
+        hashing
+            .queue_wait
+            .render(&mut out, "password_hashing_queue_wait_seconds", "");
+        header(
+            &mut out,
+            "password_hashing_rejected_total",
+            "counter",
+            "Requests turned away because every password hashing worker was busy.",
+        );
+        let _ = writeln!(
+            out,
+            "password_hashing_rejected_total {}",
+            hashing.rejected.load(Ordering::Relaxed)
+        );
+        out
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn http_labels(key: &HttpRequestKey) -> String {
+    format!(
+        "method=\"{}\",route=\"{}\",status=\"{}\"",
+        escape(&key.method),
+        escape(&key.route),
+        key.status
+    )
+}
+
+/// Escapes a label value as the text format requires.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -1,7 +1,16 @@
+pub mod avatar;
+pub mod breached_password;
+pub mod challenge;
 pub mod color;
 pub mod device;
+pub mod geoip;
+pub mod hashing_pool;
+pub mod ip;
 pub mod mail;
+pub mod metrics;
 pub mod password;
+pub mod password_policy;
 pub mod regex;
+pub mod risk;
 pub mod shutdown;
 pub mod token;
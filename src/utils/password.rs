@@ -1,27 +1,219 @@
-use argon2::Argon2;
-use argon2::PasswordHash;
-use argon2::PasswordHasher;
-use argon2::PasswordVerifier;
-use argon2::password_hash::SaltString;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
 use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher as _, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, KeyId, Params, ParamsBuilder, Version};
+use pbkdf2::Pbkdf2;
+use scrypt::Scrypt;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+use uuid::Uuid;
 
+use crate::config::password_hashing::PasswordHashingConfig;
 use crate::core::error::validation::ValidationErrorKind;
 use crate::core::result::AppResult;
+use crate::utils::hashing_pool::HashingPool;
+
+/// Hashes passwords with Argon2id under the configured cost and pepper, and verifies both
+/// current hashes and legacy bcrypt, scrypt and PBKDF2 hashes imported from older systems.
+/// All the work runs on a dedicated `HashingPool`, never on the async runtime.
+#[derive(Debug)]
+pub struct PasswordHasher {
+    scheme: Arc<HashingScheme>,
+    pub pool: HashingPool,
+    /// Hash of a random password, verified against when the user doesn't exist so that
+    /// unknown emails take as long as wrong passwords.
+    dummy_hash: String,
+}
+
+impl PasswordHasher {
+    pub fn new(config: &PasswordHashingConfig) -> AppResult<Self> {
+        let scheme = HashingScheme::new(config)?;
+        let dummy_hash = scheme.hash_password(&Uuid::new_v4().to_string())?;
+        let concurrency = config.password_hashing_concurrency.unwrap_or_else(|| {
+            thread::available_parallelism()
+                .map(|parallelism| parallelism.get())
+                .unwrap_or(1)
+        });
+        Ok(Self {
+            scheme: Arc::new(scheme),
+            pool: HashingPool::new(
+                concurrency,
+                Duration::from_millis(config.password_hashing_queue_timeout_ms),
+            ),
+            dummy_hash,
+        })
+    }
+
+    pub async fn hash_password(&self, password: &str) -> AppResult<String> {
+        let scheme = self.scheme.clone();
+        let password = password.to_string();
+        self.pool.run(move || scheme.hash_password(&password)).await
+    }
+
+    pub async fn verify_password(&self, password: &str, hashed_password: &str) -> AppResult<bool> {
+        let scheme = self.scheme.clone();
+        let password = password.to_string();
+        let hashed_password = hashed_password.to_string();
+        self.pool
+            .run(move || scheme.verify_password(&password, &hashed_password))
+            .await
+    }
+
+    /// Whether the hash was made with another algorithm, cost or pepper than the current ones
+    /// and should be replaced after the next successful verification.
+    pub fn needs_rehash(&self, hashed_password: &str) -> bool {
+        self.scheme.needs_rehash(hashed_password)
+    }
+
+    pub async fn dummy_verify(&self, password: &str) {
+        let _ = self.verify_password(password, &self.dummy_hash).await;
+    }
+}
+
+#[derive(Debug)]
+struct HashingScheme {
+    params: Params,
+    pepper: Option<Vec<u8>>,
+    /// Current and previous peppers by fingerprint, the keyid of the hashes made under them.
+    peppers: HashMap<Vec<u8>, Vec<u8>>,
+}
+
+impl HashingScheme {
+    fn new(config: &PasswordHashingConfig) -> AppResult<Self> {
+        let pepper = config
+            .password_pepper
+            .as_ref()
+            .filter(|pepper| !pepper.is_empty())
+            .map(|pepper| pepper.as_bytes().to_vec());
+        let peppers = config
+            .password_previous_peppers
+            .iter()
+            .map(|pepper| pepper.as_bytes().to_vec())
+            .chain(pepper.clone())
+            .map(|pepper| (pepper_fingerprint(&pepper), pepper))
+            .collect();
+        let mut builder = ParamsBuilder::new();
+        builder
+            .m_cost(config.argon2_memory_kib)
+            .t_cost(config.argon2_iterations)
+            .p_cost(config.argon2_parallelism);
+        // Peppered hashes are tagged with a fingerprint of the pepper, so hashes made before
+        // it was set (or under another one) can be told apart.
+        if let Some(pepper) = &pepper {
+            builder.keyid(
+                KeyId::new(&pepper_fingerprint(pepper))
+                    .map_err(argon2::password_hash::Error::from)
+                    .map_err(ValidationErrorKind::PasswordHashingError)?,
+            );
+        }
+        let params = builder
+            .build()
+            .map_err(argon2::password_hash::Error::from)
+            .map_err(ValidationErrorKind::PasswordHashingError)?;
+        Ok(Self {
+            params,
+            pepper,
+            peppers,
+        })
+    }
+
+    fn argon2<'a>(&'a self, pepper: Option<&'a [u8]>) -> AppResult<Argon2<'a>> {
+        match pepper {
+            Some(pepper) => Ok(Argon2::new_with_secret(
+                pepper,
+                Algorithm::Argon2id,
+                Version::V0x13,
+                self.params.clone(),
+            )
+            .map_err(argon2::password_hash::Error::from)
+            .map_err(ValidationErrorKind::PasswordHashingError)?),
+            None => Ok(Argon2::new(
+                Algorithm::Argon2id,
+                Version::V0x13,
+                self.params.clone(),
+            )),
+        }
+    }
+
+    fn hash_password(&self, password: &str) -> AppResult<String> {
+        let salt = SaltString::generate(&mut OsRng);
+        let hashed_password = self
+            .argon2(self.pepper.as_deref())?
+            .hash_password(password.as_bytes(), &salt)
+            .map_err(ValidationErrorKind::PasswordHashingError)?
+            .to_string();
+        Ok(hashed_password)
+    }
+
+    fn verify_password(&self, password: &str, hashed_password: &str) -> AppResult<bool> {
+        if hashed_password.starts_with("$2") {
+            return Ok(bcrypt::verify(password, hashed_password).unwrap_or(false));
+        }
+        let parsed_hash = PasswordHash::new(hashed_password)
+            .map_err(ValidationErrorKind::PasswordHashingError)?;
+        let is_password_match = match parsed_hash.algorithm.as_str() {
+            "argon2id" | "argon2i" | "argon2d" => {
+                let keyid = Params::try_from(&parsed_hash)
+                    .map(|params| params.keyid().to_vec())
+                    .unwrap_or_default();
+                // An unpeppered hash has no keyid, a peppered one names its pepper.
+                let pepper = match keyid.is_empty() {
+                    true => None,
+                    false => match self.peppers.get(&keyid) {
+                        Some(pepper) => Some(pepper.as_slice()),
+                        None => {
+                            warn!("⚠️ Password hash was made with an unknown pepper");
+                            return Ok(false);
+                        }
+                    },
+                };
+                self.argon2(pepper)?
+                    .verify_password(password.as_bytes(), &parsed_hash)
+                    .is_ok()
+            }
+            "scrypt" => Scrypt
+                .verify_password(password.as_bytes(), &parsed_hash)
+                .is_ok(),
+            "pbkdf2" | "pbkdf2-sha256" | "pbkdf2-sha512" => Pbkdf2
+                .verify_password(password.as_bytes(), &parsed_hash)
+                .is_ok(),
+            _ => {
+                warn!(
+                    "⚠️ Unsupported password hash algorithm {}",
+                    parsed_hash.algorithm
+                );
+                false
+            }
+        };
+        Ok(is_password_match)
+    }
 
-pub fn hash_password(password: String) -> AppResult<(String, String)> {
-    let salt = SaltString::generate(&mut OsRng);
-    let hashed_password = Argon2::default()
-        .hash_password(password.as_bytes(), &salt)
-        .map_err(ValidationErrorKind::PasswordHashingError)?
-        .to_string();
-    Ok((hashed_password, salt.to_string()))
+    fn needs_rehash(&self, hashed_password: &str) -> bool {
+        let Ok(parsed_hash) = PasswordHash::new(hashed_password) else {
+            return true;
+        };
+        if parsed_hash.algorithm != Algorithm::Argon2id.ident()
+            || parsed_hash.version != Some(Version::V0x13.into())
+        {
+            return true;
+        }
+        match Params::try_from(&parsed_hash) {
+            Ok(params) => {
+                params.m_cost() != self.params.m_cost()
+                    || params.t_cost() != self.params.t_cost()
+                    || params.p_cost() != self.params.p_cost()
+                    || params.keyid() != self.params.keyid()
+            }
+            Err(_) => true,
+        }
+    }
 }
 
-pub fn compare_hashed_password(password: &str, hashed_password: &str) -> AppResult<bool> {
-    let parsed_hash =
-        PasswordHash::new(hashed_password).map_err(ValidationErrorKind::PasswordHashingError)?;
-    let is_password_match = Argon2::default()
-        .verify_password(password.as_bytes(), &parsed_hash)
-        .is_ok_and(|_| true);
-    Ok(is_password_match)
+/// Identifies a pepper in the hashes made under it without revealing it.
+fn pepper_fingerprint(pepper: &[u8]) -> Vec<u8> {
+    Sha256::digest(pepper)[..4].to_vec()
 }
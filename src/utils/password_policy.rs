@@ -0,0 +1,107 @@
+use std::{path::PathBuf, sync::Arc};
+
+use tracing::warn;
+
+use crate::{
+    config::password_policy::PasswordPolicyConfig,
+    core::{error::validation::ValidationErrorKind, result::AppResult},
+    utils::breached_password::{
+        BreachedPasswordSource, HibpRangeSource, LocalRangeSource, is_password_breached,
+    },
+};
+
+#[derive(Debug)]
+pub struct PasswordPolicy {
+    pub config: PasswordPolicyConfig,
+    pub breached_source: Option<Arc<dyn BreachedPasswordSource>>,
+}
+
+impl PasswordPolicy {
+    pub fn new(config: PasswordPolicyConfig) -> Self {
+        let breached_source: Option<Arc<dyn BreachedPasswordSource>> = match (
+            &config.breached_password_range_dir,
+            &config.breached_password_api_url,
+        ) {
+            (Some(dir), _) => Some(Arc::new(LocalRangeSource {
+                dir: PathBuf::from(dir),
+            })),
+            (None, Some(api_url)) => Some(Arc::new(HibpRangeSource {
+                http: reqwest::Client::new(),
+                api_url: api_url.clone(),
+            })),
+            (None, None) => None,
+        };
+        Self {
+            config,
+            breached_source,
+        }
+    }
+
+    /// Checks a new password against every rule of the policy. `user_inputs` are values such
+    /// as the name and email that must not make the password easier to guess.
+    pub async fn check(&self, password: &str, user_inputs: &[&str]) -> AppResult<()> {
+        let length = password.chars().count();
+        if length < self.config.password_min_length {
+            return Err(ValidationErrorKind::ValidationFailed(format!(
+                "Password must be at least {} characters long",
+                self.config.password_min_length
+            ))
+            .into());
+        }
+        if length > self.config.password_max_length {
+            return Err(ValidationErrorKind::ValidationFailed(format!(
+                "Password must be at most {} characters long",
+                self.config.password_max_length
+            ))
+            .into());
+        }
+        if self.config.password_require_letter && !password.chars().any(char::is_alphabetic) {
+            return Err(ValidationErrorKind::ValidationFailed(
+                "Password must contain a letter".to_string(),
+            )
+            .into());
+        }
+        if self.config.password_require_mixed_case
+            && !(password.chars().any(char::is_uppercase)
+                && password.chars().any(char::is_lowercase))
+        {
+            return Err(ValidationErrorKind::ValidationFailed(
+                "Password must contain upper and lower case letters".to_string(),
+            )
+            .into());
+        }
+        if self.config.password_require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            return Err(ValidationErrorKind::ValidationFailed(
+                "Password must contain a number".to_string(),
+            )
+            .into());
+        }
+        if self.config.password_require_special
+            && !password
+                .chars()
+                .any(|c| !c.is_alphanumeric() && !c.is_whitespace())
+        {
+            return Err(ValidationErrorKind::ValidationFailed(
+                "Password must contain a special character".to_string(),
+            )
+            .into());
+        }
+        let entropy = zxcvbn::zxcvbn(password, user_inputs);
+        if u8::from(entropy.score()) < self.config.password_min_strength_score {
+            let message = match entropy.feedback().and_then(|feedback| feedback.warning()) {
+                Some(warning) => format!("Password is too weak: {}", warning),
+                None => "Password is too weak".to_string(),
+            };
+            return Err(ValidationErrorKind::ValidationFailed(message).into());
+        }
+        if let Some(source) = &self.breached_source {
+            match is_password_breached(source.as_ref(), password).await {
+                Ok(true) => return Err(ValidationErrorKind::BreachedPassword.into()),
+                Ok(false) => {}
+                // An unreachable breach source must not lock users out of changing passwords.
+                Err(e) => warn!("⚠️ Breached password check failed, skipping it: {}", e),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,237 @@
+use chrono::{DateTime, Timelike, Utc};
+use chrono_tz::Tz;
+
+use crate::{config::risk::RiskConfig, models::geo_location::GeoLocation};
+
+/// Everything known about a login attempt once the password has been checked.
+#[derive(Debug, Clone)]
+pub struct LoginRiskContext {
+    pub now: DateTime<Utc>,
+    pub known_device: bool,
+    pub ip_blacklisted: bool,
+    pub location: Option<GeoLocation>,
+    /// Location and time of the user's most recent login on any device.
+    pub previous_login: Option<(GeoLocation, DateTime<Utc>)>,
+    pub failed_attempts: u64,
+    pub timezone: Option<String>,
+}
+
+/// A signal that fired, with the points it added.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskFactor {
+    pub name: &'static str,
+    pub score: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RiskDecision {
+    Allow,
+    StepUp,
+    Block,
+}
+
+impl std::fmt::Display for RiskDecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RiskDecision::Allow => write!(f, "allow"),
+            RiskDecision::StepUp => write!(f, "step_up"),
+            RiskDecision::Block => write!(f, "block"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RiskAssessment {
+    pub score: u32,
+    pub factors: Vec<RiskFactor>,
+    pub decision: RiskDecision,
+}
+
+/// One input of the risk engine. Signals only look at the context, so they stay cheap and
+/// the data they need is loaded once per login.
+pub trait RiskSignal: Send + Sync + std::fmt::Debug {
+    fn evaluate(&self, context: &LoginRiskContext) -> Option<RiskFactor>;
+    /// Signals anyone can trigger for someone else's account, such as failed attempts on their
+    /// email, must only lead to a step-up: counting them towards a block would let an attacker
+    /// lock the owner out.
+    fn can_block(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug)]
+pub struct NewDeviceSignal {
+    pub score: u32,
+}
+
+impl RiskSignal for NewDeviceSignal {
+    fn evaluate(&self, context: &LoginRiskContext) -> Option<RiskFactor> {
+        (!context.known_device).then_some(RiskFactor {
+            name: "new_device",
+            score: self.score,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct IpReputationSignal {
+    pub score: u32,
+}
+
+impl RiskSignal for IpReputationSignal {
+    fn evaluate(&self, context: &LoginRiskContext) -> Option<RiskFactor> {
+        context.ip_blacklisted.then_some(RiskFactor {
+            name: "blacklisted_ip",
+            score: self.score,
+        })
+    }
+}
+
+/// Fires when the user would have had to travel faster than `max_speed_kmh` since their
+/// previous login.
+#[derive(Debug)]
+pub struct ImpossibleTravelSignal {
+    pub score: u32,
+    pub max_speed_kmh: f64,
+}
+
+/// GeoIP coordinates are rough, shorter distances never count as travel.
+const MIN_TRAVEL_DISTANCE_KM: f64 = 200.0;
+
+impl RiskSignal for ImpossibleTravelSignal {
+    fn evaluate(&self, context: &LoginRiskContext) -> Option<RiskFactor> {
+        let location = context.location.as_ref()?;
+        let (previous_location, previous_at) = context.previous_login.as_ref()?;
+        let distance_km = previous_location.distance_km(location)?;
+        if distance_km < MIN_TRAVEL_DISTANCE_KM {
+            return None;
+        }
+        let hours = (context.now - *previous_at).num_seconds().max(1) as f64 / 3600.0;
+        (distance_km / hours > self.max_speed_kmh).then_some(RiskFactor {
+            name: "impossible_travel",
+            score: self.score,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct FailedAttemptsSignal {
+    pub score_per_attempt: u32,
+    pub max_score: u32,
+}
+
+impl RiskSignal for FailedAttemptsSignal {
+    fn evaluate(&self, context: &LoginRiskContext) -> Option<RiskFactor> {
+        (context.failed_attempts > 0).then(|| RiskFactor {
+            name: "failed_attempts",
+            score: self
+                .score_per_attempt
+                .saturating_mul(u32::try_from(context.failed_attempts).unwrap_or(u32::MAX))
+                .min(self.max_score),
+        })
+    }
+    fn can_block(&self) -> bool {
+        false
+    }
+}
+
+/// Fires for logins at night in the user's timezone, UTC when they haven't set one.
+#[derive(Debug)]
+pub struct UnusualHourSignal {
+    pub score: u32,
+    pub start_hour: u32,
+    pub end_hour: u32,
+}
+
+impl RiskSignal for UnusualHourSignal {
+    fn evaluate(&self, context: &LoginRiskContext) -> Option<RiskFactor> {
+        let hour = match context
+            .timezone
+            .as_deref()
+            .and_then(|timezone| timezone.parse::<Tz>().ok())
+        {
+            Some(timezone) => context.now.with_timezone(&timezone).hour(),
+            None => context.now.hour(),
+        };
+        let unusual = if self.start_hour <= self.end_hour {
+            (self.start_hour..self.end_hour).contains(&hour)
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        };
+        unusual.then_some(RiskFactor {
+            name: "unusual_hour",
+            score: self.score,
+        })
+    }
+}
+
+/// Adds up the scores of every signal and maps the total to a decision. Only the signals that
+/// can block count towards `block_score`.
+#[derive(Debug)]
+pub struct RiskEngine {
+    pub step_up_score: u32,
+    pub block_score: u32,
+    pub signals: Vec<Box<dyn RiskSignal>>,
+}
+
+impl RiskEngine {
+    pub fn new(config: &RiskConfig) -> Self {
+        Self {
+            step_up_score: config.risk_step_up_score,
+            block_score: config.risk_block_score,
+            signals: vec![
+                Box::new(NewDeviceSignal {
+                    score: config.risk_new_device_score,
+                }),
+                Box::new(IpReputationSignal {
+                    score: config.risk_blacklisted_ip_score,
+                }),
+                Box::new(ImpossibleTravelSignal {
+                    score: config.risk_impossible_travel_score,
+                    max_speed_kmh: config.risk_max_travel_speed_kmh,
+                }),
+                Box::new(FailedAttemptsSignal {
+                    score_per_attempt: config.risk_failed_attempt_score,
+                    max_score: config.risk_failed_attempts_max_score,
+                }),
+                Box::new(UnusualHourSignal {
+                    score: config.risk_unusual_hour_score,
+                    start_hour: config.risk_unusual_hours_start,
+                    end_hour: config.risk_unusual_hours_end,
+                }),
+            ],
+        }
+    }
+
+    pub fn with_signal(mut self, signal: Box<dyn RiskSignal>) -> Self {
+        self.signals.push(signal);
+        self
+    }
+
+    pub fn assess(&self, context: &LoginRiskContext) -> RiskAssessment {
+        let mut factors = Vec::new();
+        let mut score = 0u32;
+        let mut blocking_score = 0u32;
+        for signal in &self.signals {
+            if let Some(factor) = signal.evaluate(context) {
+                score = score.saturating_add(factor.score);
+                if signal.can_block() {
+                    blocking_score = blocking_score.saturating_add(factor.score);
+                }
+                factors.push(factor);
+            }
+        }
+        let decision = if blocking_score >= self.block_score {
+            RiskDecision::Block
+        } else if score >= self.step_up_score {
+            RiskDecision::StepUp
+        } else {
+            RiskDecision::Allow
+        };
+        RiskAssessment {
+            score,
+            factors,
+            decision,
+        }
+    }
+}
@@ -5,9 +5,9 @@ use crate::{
     core::result::AppResult,
     dto::request::auth::{
         ForgetPasswordRequest, LoginRequest, RegisterRequest, ResetPasswordRequest,
-        VerifyUserRequest,
+        RestoreAccountRequest, VerifyUserRequest,
     },
-    utils::regex::{NAME_REGEX, PASSWORD_REGEX},
+    utils::regex::NAME_REGEX,
 };
 
 pub fn validate_register_payload(payload: &RegisterRequest) -> AppResult<()> {
@@ -50,51 +50,12 @@ pub fn validate_register_payload(payload: &RegisterRequest) -> AppResult<()> {
             ValidationErrorKind::ValidationFailed("Password can't be empty".to_string()).into(),
         );
     }
-    if payload.password.len() < 8 {
-        return Err(ValidationErrorKind::ValidationFailed(
-            "Password must be at least 8 characters long".to_string(),
-        )
-        .into());
-    }
-    if payload.password.len() > 20 {
-        return Err(ValidationErrorKind::ValidationFailed(
-            "Password must be at most 20 characters long".to_string(),
-        )
-        .into());
-    }
-    if !PASSWORD_REGEX.is_match(&payload.password).unwrap_or(false) {
-        return Err(ValidationErrorKind::ValidationFailed(
-            "Password must contain letters, numbers and special characters".to_string(),
-        )
-        .into());
-    }
     if payload.confirm_password.is_empty() {
         return Err(ValidationErrorKind::ValidationFailed(
             "Confirm password cannot be empty".to_string(),
         )
         .into());
     }
-    if payload.confirm_password.len() < 8 {
-        return Err(ValidationErrorKind::ValidationFailed(
-            "Confirm password must be at least 8 characters long".to_string(),
-        )
-        .into());
-    }
-    if payload.confirm_password.len() > 20 {
-        return Err(ValidationErrorKind::ValidationFailed(
-            "Confirm password must be at most 20 characters long".to_string(),
-        )
-        .into());
-    }
-    if !PASSWORD_REGEX
-        .is_match(&payload.confirm_password)
-        .unwrap_or(false)
-    {
-        return Err(ValidationErrorKind::ValidationFailed(
-            "Confirm password must contain letters, numbers and special characters".to_string(),
-        )
-        .into());
-    }
     if payload.password != payload.confirm_password {
         return Err(
             ValidationErrorKind::ValidationFailed("Passwords do not match".to_string()).into(),
@@ -120,24 +81,6 @@ pub fn validate_login_payload(payload: &LoginRequest) -> AppResult<()> {
             ValidationErrorKind::ValidationFailed("Password can't be empty".to_string()).into(),
         );
     }
-    if payload.password.len() < 8 {
-        return Err(ValidationErrorKind::ValidationFailed(
-            "Password must be at least 8 characters long".to_string(),
-        )
-        .into());
-    }
-    if payload.password.len() > 20 {
-        return Err(ValidationErrorKind::ValidationFailed(
-            "Password must be at most 20 characters long".to_string(),
-        )
-        .into());
-    }
-    if !PASSWORD_REGEX.is_match(&payload.password).unwrap_or(false) {
-        return Err(ValidationErrorKind::ValidationFailed(
-            "Password must contain letters, numbers and special characters".to_string(),
-        )
-        .into());
-    }
     Ok(())
 }
 
@@ -193,57 +136,35 @@ pub fn validate_reset_password_payload(payload: &ResetPasswordRequest) -> AppRes
             ValidationErrorKind::ValidationFailed("Password can't be empty".to_string()).into(),
         );
     }
-    if payload.new_password.len() < 8 {
-        return Err(ValidationErrorKind::ValidationFailed(
-            "Password must be at least 8 characters long".to_string(),
-        )
-        .into());
-    }
-    if payload.new_password.len() > 20 {
-        return Err(ValidationErrorKind::ValidationFailed(
-            "Password must be at most 20 characters long".to_string(),
-        )
-        .into());
-    }
-    if !PASSWORD_REGEX
-        .is_match(&payload.new_password)
-        .unwrap_or(false)
-    {
-        return Err(ValidationErrorKind::ValidationFailed(
-            "Password must contain letters, numbers and special characters".to_string(),
-        )
-        .into());
-    }
     if payload.confirm_password.is_empty() {
         return Err(ValidationErrorKind::ValidationFailed(
             "Confirm password cannot be empty".to_string(),
         )
         .into());
     }
-    if payload.confirm_password.len() < 8 {
-        return Err(ValidationErrorKind::ValidationFailed(
-            "Confirm password must be at least 8 characters long".to_string(),
-        )
-        .into());
+    if payload.new_password != payload.confirm_password {
+        return Err(
+            ValidationErrorKind::ValidationFailed("Passwords do not match".to_string()).into(),
+        );
     }
-    if payload.confirm_password.len() > 20 {
-        return Err(ValidationErrorKind::ValidationFailed(
-            "Confirm password must be at most 20 characters long".to_string(),
-        )
-        .into());
+    Ok(())
+}
+
+pub fn validate_restore_account_payload(payload: &RestoreAccountRequest) -> AppResult<()> {
+    if payload.email.is_empty() {
+        return Err(
+            ValidationErrorKind::ValidationFailed("Email can't be empty".to_string()).into(),
+        );
     }
-    if !PASSWORD_REGEX
-        .is_match(&payload.confirm_password)
-        .unwrap_or(false)
-    {
+    if !ValidateEmail::validate_email(&payload.email) {
         return Err(ValidationErrorKind::ValidationFailed(
-            "Confirm password must contain letters, numbers and special characters".to_string(),
+            "Email must be a valid email address".to_string(),
         )
         .into());
     }
-    if payload.new_password != payload.confirm_password {
+    if payload.password.is_empty() {
         return Err(
-            ValidationErrorKind::ValidationFailed("Passwords do not match".to_string()).into(),
+            ValidationErrorKind::ValidationFailed("Password can't be empty".to_string()).into(),
         );
     }
     Ok(())
@@ -1,7 +1,10 @@
+use chrono_tz::Tz;
+use reqwest::Url;
+
 use crate::{
     core::{error::validation::ValidationErrorKind, result::AppResult},
-    dto::request::user::ChangePasswordRequest,
-    utils::regex::PASSWORD_REGEX,
+    dto::request::user::{ChangePasswordRequest, DeleteAccountRequest, UserProfileRequest},
+    utils::regex::{LOCALE_REGEX, NAME_REGEX},
 };
 
 pub fn validate_change_password_request(payload: &ChangePasswordRequest) -> AppResult<()> {
@@ -10,83 +13,101 @@ pub fn validate_change_password_request(payload: &ChangePasswordRequest) -> AppR
             ValidationErrorKind::ValidationFailed("Password can't be empty".to_string()).into(),
         );
     }
-    if payload.old_password.len() < 8 {
-        return Err(ValidationErrorKind::ValidationFailed(
-            "Password must be at least 8 characters long".to_string(),
-        )
-        .into());
-    }
-    if payload.old_password.len() > 20 {
-        return Err(ValidationErrorKind::ValidationFailed(
-            "Password must be at most 20 characters long".to_string(),
-        )
-        .into());
-    }
-    if !PASSWORD_REGEX
-        .is_match(&payload.old_password)
-        .unwrap_or(false)
-    {
-        return Err(ValidationErrorKind::ValidationFailed(
-            "Password must contain letters, numbers and special characters".to_string(),
-        )
-        .into());
-    }
     if payload.new_password.is_empty() {
         return Err(
             ValidationErrorKind::ValidationFailed("Password can't be empty".to_string()).into(),
         );
     }
-    if payload.new_password.len() < 8 {
+    if payload.new_confirm_password.is_empty() {
         return Err(ValidationErrorKind::ValidationFailed(
-            "Password must be at least 8 characters long".to_string(),
+            "Confirm password cannot be empty".to_string(),
         )
         .into());
     }
-    if payload.new_password.len() > 20 {
-        return Err(ValidationErrorKind::ValidationFailed(
-            "Password must be at most 20 characters long".to_string(),
-        )
-        .into());
+    if payload.new_password != payload.new_confirm_password {
+        return Err(
+            ValidationErrorKind::ValidationFailed("Passwords do not match".to_string()).into(),
+        );
     }
-    if !PASSWORD_REGEX
-        .is_match(&payload.new_password)
-        .unwrap_or(false)
+    Ok(())
+}
+
+pub fn validate_user_profile_request(payload: &UserProfileRequest) -> AppResult<()> {
+    if payload.name.is_none()
+        && payload.locale.is_none()
+        && payload.timezone.is_none()
+        && payload.avatar_url.is_none()
     {
         return Err(ValidationErrorKind::ValidationFailed(
-            "Password must contain letters, numbers and special characters".to_string(),
+            "At least one profile field must be provided".to_string(),
         )
         .into());
     }
-    if payload.new_confirm_password.is_empty() {
-        return Err(ValidationErrorKind::ValidationFailed(
-            "Confirm password cannot be empty".to_string(),
-        )
-        .into());
+    if let Some(name) = &payload.name {
+        if name.is_empty() {
+            return Err(
+                ValidationErrorKind::ValidationFailed("Name can't be empty".to_string()).into(),
+            );
+        }
+        if name.len() < 3 {
+            return Err(ValidationErrorKind::ValidationFailed(
+                "Name can't be shorter than 3 characters".to_string(),
+            )
+            .into());
+        }
+        if name.len() > 20 {
+            return Err(ValidationErrorKind::ValidationFailed(
+                "Name can't be longer than 20 characters".to_string(),
+            )
+            .into());
+        }
+        if !NAME_REGEX.is_match(name).unwrap_or(false) {
+            return Err(ValidationErrorKind::ValidationFailed(
+                "Name must be letters, numbers or letters with numbers".to_string(),
+            )
+            .into());
+        }
     }
-    if payload.new_confirm_password.len() < 8 {
-        return Err(ValidationErrorKind::ValidationFailed(
-            "Confirm password must be at least 8 characters long".to_string(),
-        )
-        .into());
-    }
-    if payload.new_confirm_password.len() > 20 {
+    if let Some(Some(locale)) = &payload.locale
+        && !LOCALE_REGEX.is_match(locale).unwrap_or(false)
+    {
         return Err(ValidationErrorKind::ValidationFailed(
-            "Confirm password must be at most 20 characters long".to_string(),
+            "Locale must be a valid language tag, e.g. en-US".to_string(),
         )
         .into());
     }
-    if !PASSWORD_REGEX
-        .is_match(&payload.new_confirm_password)
-        .unwrap_or(false)
+    if let Some(Some(timezone)) = &payload.timezone
+        && timezone.parse::<Tz>().is_err()
     {
         return Err(ValidationErrorKind::ValidationFailed(
-            "Confirm password must contain letters, numbers and special characters".to_string(),
+            "Timezone must be a valid IANA timezone, e.g. Europe/Berlin".to_string(),
         )
         .into());
     }
-    if payload.new_password != payload.new_confirm_password {
+    if let Some(Some(avatar_url)) = &payload.avatar_url {
+        if avatar_url.len() > 2048 {
+            return Err(ValidationErrorKind::ValidationFailed(
+                "Avatar URL can't be longer than 2048 characters".to_string(),
+            )
+            .into());
+        }
+        // Clients render it as an image source, so `javascript:`, `data:` and the like are out.
+        let is_web_url = Url::parse(avatar_url)
+            .is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.has_host());
+        if !is_web_url {
+            return Err(ValidationErrorKind::ValidationFailed(
+                "Avatar URL must be an http or https URL".to_string(),
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+pub fn validate_delete_account_request(payload: &DeleteAccountRequest) -> AppResult<()> {
+    if payload.password.is_empty() {
         return Err(
-            ValidationErrorKind::ValidationFailed("Passwords do not match".to_string()).into(),
+            ValidationErrorKind::ValidationFailed("Password can't be empty".to_string()).into(),
         );
     }
     Ok(())
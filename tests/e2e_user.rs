@@ -0,0 +1,1668 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, PoisonError, atomic::Ordering};
+use std::time::Duration;
+
+use axum::{
+    Json, Router,
+    body::Bytes,
+    extract::{Form, Path, State},
+    http::{HeaderMap, Method, StatusCode, Uri},
+    response::IntoResponse,
+    routing::{any, get, post},
+};
+use axum_extra::extract::cookie::Cookie;
+use axum_test::{
+    TestServer,
+    multipart::{MultipartForm, Part},
+};
+use hmac::{Hmac, Mac};
+use image::{ImageFormat, RgbImage};
+use serde_json::{Value, json};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use surrealdb::sql::Thing;
+use tokio::net::TcpListener;
+use uuid::Uuid;
+
+use backend::{
+    config::{challenge::ChallengeProvider, storage::StorageBackend},
+    core::{config::AppConfig, state::AppState},
+    database::{
+        circuit_breaker::{CircuitBreaker, CircuitState},
+        client::DBClient,
+        ephemeral::EphemeralStore,
+        memory::client::MemoryCacheClient,
+        surreal::migration::{MigrationRunner, MigrationState},
+    },
+    models::geo_location::GeoLocation,
+    routers::api_routers,
+    storage::init_storage,
+    utils::{
+        device::{DEVICE_COOKIE_NAME, UserAgentParser, sign_device_id, verify_device_cookie},
+        geoip::GeoIpResolver,
+        password::PasswordHasher,
+        risk::{LoginRiskContext, RiskDecision, RiskEngine},
+    },
+};
+
+const USER_AGENT: &str = "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0";
+const PASSWORD: &str = "Tr1cky-Lantern-Orbit!";
+
+/// Html bodies of the emails the app sent, newest last.
+type Outbox = Arc<Mutex<Vec<String>>>;
+
+async fn send_email(State(outbox): State<Outbox>, Json(email): Json<Value>) -> Json<Value> {
+    let html = email["html"].as_str().unwrap_or_default().to_string();
+    outbox
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(html);
+    Json(json!({ "id": "00000000-0000-0000-0000-000000000000" }))
+}
+
+/// Stands in for the Resend API and keeps every email it is asked to send.
+async fn mock_resend() -> (String, Outbox) {
+    let outbox = Outbox::default();
+    let router = Router::new()
+        .route("/emails", post(send_email))
+        .with_state(outbox.clone());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    (format!("http://{}", address), outbox)
+}
+
+const S3_BUCKET: &str = "avatars";
+const S3_REGION: &str = "eu-central-1";
+const S3_ACCESS_KEY: &str = "test-access-key";
+const S3_SECRET_KEY: &str = "test-secret-key";
+const S3_PUBLIC_URL: &str = "https://cdn.example.com/avatars";
+
+/// Objects a mock S3 bucket holds, by key, with their content type.
+type Bucket = Arc<Mutex<HashMap<String, (String, Vec<u8>)>>>;
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).unwrap();
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Recomputes the AWS Signature V4 of a request the way S3 does, from what was received.
+fn signature_matches(method: &Method, uri: &Uri, headers: &HeaderMap, body: &[u8]) -> bool {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let Some(authorization) = header("authorization") else {
+        return false;
+    };
+    let Some(fields) = authorization.strip_prefix("AWS4-HMAC-SHA256 ") else {
+        return false;
+    };
+    let fields: HashMap<&str, &str> = fields
+        .split(", ")
+        .filter_map(|field| field.split_once('='))
+        .collect();
+    let (Some(credential), Some(signed_headers), Some(signature)) = (
+        fields.get("Credential"),
+        fields.get("SignedHeaders"),
+        fields.get("Signature"),
+    ) else {
+        return false;
+    };
+    let Some((access_key, scope)) = credential.split_once('/') else {
+        return false;
+    };
+    let payload_hash = hex::encode(Sha256::digest(body));
+    if access_key != S3_ACCESS_KEY || header("x-amz-content-sha256") != Some(&payload_hash) {
+        return false;
+    }
+    let canonical_headers: String = signed_headers
+        .split(';')
+        .map(|name| format!("{}:{}\n", name, header(name).unwrap_or_default().trim()))
+        .collect();
+    let canonical_request = format!(
+        "{}\n{}\n\n{}\n{}\n{}",
+        method,
+        uri.path(),
+        canonical_headers,
+        signed_headers,
+        payload_hash
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        header("x-amz-date").unwrap_or_default(),
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    let mut scope_parts = scope.split('/');
+    let date = scope_parts.next().unwrap_or_default();
+    if scope_parts.collect::<Vec<_>>() != [S3_REGION, "s3", "aws4_request"] {
+        return false;
+    }
+    let signing_key = [S3_REGION, "s3", "aws4_request"].iter().fold(
+        hmac_sha256(format!("AWS4{}", S3_SECRET_KEY).as_bytes(), date.as_bytes()),
+        |key, part| hmac_sha256(&key, part.as_bytes()),
+    );
+    hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes())) == *signature
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match (bytes[i], value.get(i + 1..i + 3)) {
+            (b'%', Some(hex)) if u8::from_str_radix(hex, 16).is_ok() => {
+                decoded.push(u8::from_str_radix(hex, 16).unwrap());
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).unwrap()
+}
+
+async fn s3_object(
+    State(bucket): State<Bucket>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, Vec<u8>) {
+    if !signature_matches(&method, &uri, &headers, &body) {
+        return (StatusCode::FORBIDDEN, Vec::new());
+    }
+    let Some(key) = uri.path().strip_prefix(&format!("/{}/", S3_BUCKET)) else {
+        return (StatusCode::NOT_FOUND, Vec::new());
+    };
+    let key = percent_decode(key);
+    let key = key.as_str();
+    let mut bucket = bucket.lock().unwrap_or_else(PoisonError::into_inner);
+    match method {
+        Method::PUT => {
+            let content_type = headers
+                .get("content-type")
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default()
+                .to_string();
+            bucket.insert(key.to_string(), (content_type, body.to_vec()));
+            (StatusCode::OK, Vec::new())
+        }
+        Method::GET => match bucket.get(key) {
+            Some((_, body)) => (StatusCode::OK, body.clone()),
+            None => (StatusCode::NOT_FOUND, Vec::new()),
+        },
+        Method::DELETE => {
+            bucket.remove(key);
+            (StatusCode::NO_CONTENT, Vec::new())
+        }
+        _ => (StatusCode::METHOD_NOT_ALLOWED, Vec::new()),
+    }
+}
+
+/// Stands in for an S3-compatible server with a single bucket. Requests whose signature doesn't
+/// verify are refused with 403, like S3 does.
+async fn mock_s3() -> (String, Bucket) {
+    let bucket = Bucket::default();
+    let router = Router::new()
+        .route("/{*path}", any(s3_object))
+        .with_state(bucket.clone());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    (format!("http://{}", address), bucket)
+}
+
+/// The `test` profile with objects stored in a mock S3 bucket and served from a CDN.
+async fn s3_config() -> (AppConfig, Bucket) {
+    let (endpoint, bucket) = mock_s3().await;
+    let mut config = AppConfig::load("test").unwrap();
+    config.storage.storage_backend = StorageBackend::S3;
+    config.storage.s3_endpoint = Some(endpoint);
+    config.storage.s3_bucket = Some(S3_BUCKET.to_string());
+    config.storage.s3_region = S3_REGION.to_string();
+    config.storage.s3_access_key = Some(S3_ACCESS_KEY.to_string());
+    config.storage.s3_secret_key = Some(S3_SECRET_KEY.to_string());
+    config.storage.storage_public_url = S3_PUBLIC_URL.to_string();
+    (config, bucket)
+}
+
+/// The app on the in-memory repositories with the `test` profile, and the emails it sends.
+async fn test_server() -> (TestServer, Outbox) {
+    test_server_with(DBClient::memory(), AppConfig::load("test").unwrap()).await
+}
+
+/// Same as [`test_server`] on the embedded SurrealDB and in-process cache of the `test`
+/// profile, schema applied.
+async fn embedded_test_server() -> (TestServer, Outbox) {
+    let config = AppConfig::load("test").unwrap();
+    let db_client = DBClient::new(config.clone()).await.unwrap();
+    MigrationRunner::new(&config.migration)
+        .up(db_client.database.as_ref(), false)
+        .await
+        .unwrap();
+    test_server_with(db_client, config).await
+}
+
+async fn test_server_with(db_client: DBClient, config: AppConfig) -> (TestServer, Outbox) {
+    let (server, outbox, _app_state) = test_app(db_client, config).await;
+    (server, outbox)
+}
+
+/// Same as [`test_server_with`], also handing out the state so tests can run background jobs.
+async fn test_app(
+    db_client: DBClient,
+    mut config: AppConfig,
+) -> (TestServer, Outbox, Arc<AppState>) {
+    let (resend_base_url, outbox) = mock_resend().await;
+    config.mail_server.resend_base_url = Some(resend_base_url);
+    config.storage.storage_local_path = std::env::temp_dir()
+        .join("backend-e2e-uploads")
+        .to_string_lossy()
+        .into_owned();
+    let storage = init_storage(&config.storage).unwrap();
+    let password_hasher = Arc::new(PasswordHasher::new(&config.password_hashing).unwrap());
+    let user_agent_parser = Arc::new(UserAgentParser::new(&config.user_agent).unwrap());
+    let geoip = Arc::new(GeoIpResolver::new(&config.geoip).unwrap());
+    let app_state = Arc::new(AppState::new(
+        config,
+        db_client,
+        storage,
+        password_hasher,
+        user_agent_parser,
+        geoip,
+    ));
+    let router = api_routers(app_state.clone()).into_make_service_with_connect_info::<SocketAddr>();
+    let mut server = TestServer::new(router).unwrap();
+    server.save_cookies();
+    server.add_header("User-Agent", USER_AGENT);
+    (server, outbox, app_state)
+}
+
+fn last_email_token(outbox: &Outbox) -> String {
+    let outbox = outbox.lock().unwrap_or_else(PoisonError::into_inner);
+    let html = outbox.last().expect("an email was sent");
+    let start = html.find("<code>").expect("the email holds a code") + "<code>".len();
+    let end = start + html[start..].find("</code>").unwrap();
+    html[start..end].to_string()
+}
+
+async fn register(server: &TestServer, email: &str) {
+    server
+        .post("/api/v1/auth/register")
+        .json(&json!({
+            "name": "tester",
+            "email": email,
+            "password": PASSWORD,
+            "confirm_password": PASSWORD,
+        }))
+        .await
+        .assert_status_ok();
+}
+
+async fn login(server: &TestServer, email: &str, password: &str) -> axum_test::TestResponse {
+    server
+        .post("/api/v1/auth/login")
+        .json(&json!({ "email": email, "password": password }))
+        .await
+}
+
+/// Registers, verifies the emailed code and logs in, returning the access token.
+async fn sign_up(server: &TestServer, outbox: &Outbox, email: &str) -> String {
+    register(server, email).await;
+    let response = login(server, email, PASSWORD).await;
+    response.assert_status_ok();
+    assert_eq!(
+        response.json::<Value>()["success"]["data"]["need_verification"],
+        true
+    );
+    server
+        .post("/api/v1/auth/verify-email")
+        .json(&json!({ "email": email, "email_token": last_email_token(outbox) }))
+        .await
+        .assert_status_ok();
+    let response = login(server, email, PASSWORD).await;
+    response.assert_status_ok();
+    response
+        .header("Authorization")
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn startup_requires_an_explicit_profile() {
+    // No test reads APP_PROFILE, they all load the test profile by name.
+    unsafe { std::env::remove_var("APP_PROFILE") };
+    let error = AppConfig::init().unwrap_err();
+    assert!(error.to_string().contains("APP_PROFILE is not set"));
+}
+
+#[tokio::test]
+async fn register_verify_login_and_read_profile() {
+    let (server, outbox) = test_server().await;
+    let authorization = sign_up(&server, &outbox, "tester@example.com").await;
+
+    let response = server
+        .get("/api/v1/user/me")
+        .add_header("Authorization", authorization)
+        .await;
+    response.assert_status_ok();
+    let me = response.json::<Value>();
+    assert_eq!(me["success"]["data"]["name"], "tester");
+    assert_eq!(me["success"]["data"]["is_verified"], true);
+}
+
+#[tokio::test]
+async fn register_verify_login_and_read_profile_on_embedded_surrealdb() {
+    let (server, outbox) = embedded_test_server().await;
+    let authorization = sign_up(&server, &outbox, "tester@example.com").await;
+
+    let response = server
+        .get("/api/v1/user/me")
+        .add_header("Authorization", authorization)
+        .await;
+    response.assert_status_ok();
+    assert_eq!(
+        response.json::<Value>()["success"]["data"]["name"],
+        "tester"
+    );
+}
+
+#[tokio::test]
+async fn profile_fields_are_cleared_by_null_and_kept_when_missing() {
+    let (server, outbox) = embedded_test_server().await;
+    let authorization = sign_up(&server, &outbox, "tester@example.com").await;
+    let me = |server: &TestServer| {
+        server
+            .get("/api/v1/user/me")
+            .add_header("Authorization", authorization.clone())
+    };
+
+    let updated_at = me(&server).await.json::<Value>()["success"]["data"]["updated_at"].clone();
+    let response = server
+        .patch("/api/v1/user/me")
+        .add_header("Authorization", authorization.clone())
+        .json(&json!({
+            "locale": "en-US",
+            "timezone": "Europe/Berlin",
+            "updated_at": updated_at,
+        }))
+        .await;
+    response.assert_status_ok();
+
+    let updated_at = me(&server).await.json::<Value>()["success"]["data"]["updated_at"].clone();
+    server
+        .patch("/api/v1/user/me")
+        .add_header("Authorization", authorization.clone())
+        .json(&json!({ "locale": null, "updated_at": updated_at }))
+        .await
+        .assert_status_ok();
+
+    let profile = me(&server).await.json::<Value>();
+    assert!(profile["success"]["data"]["locale"].is_null());
+    assert_eq!(profile["success"]["data"]["timezone"], "Europe/Berlin");
+}
+
+#[tokio::test]
+async fn devices_and_sessions_are_listed_with_their_location() {
+    let (server, outbox) = embedded_test_server().await;
+    let authorization = sign_up(&server, &outbox, "tester@example.com").await;
+
+    let response = server
+        .get("/api/v1/user/devices")
+        .add_header("Authorization", authorization.clone())
+        .await;
+    response.assert_status_ok();
+    let devices = response.json::<Value>()["success"]["data"].clone();
+    assert_eq!(devices.as_array().unwrap().len(), 1);
+    assert!(
+        devices[0]["user_agent"]
+            .as_str()
+            .unwrap()
+            .starts_with("Firefox")
+    );
+    assert_eq!(devices[0]["is_trusted"], true);
+    assert!(devices[0].as_object().unwrap().contains_key("location"));
+    assert!(devices[0].get("user_id").is_none());
+
+    let response = server
+        .get("/api/v1/user/sessions")
+        .add_header("Authorization", authorization)
+        .await;
+    response.assert_status_ok();
+    let sessions = response.json::<Value>()["success"]["data"].clone();
+    assert_eq!(sessions.as_array().unwrap().len(), 1);
+    assert_eq!(sessions[0]["device_id"], devices[0]["id"]);
+    assert_eq!(sessions[0]["ip"], devices[0]["ip"]);
+    assert_eq!(sessions[0]["is_current"], true);
+    assert!(sessions[0].as_object().unwrap().contains_key("location"));
+    assert!(sessions[0].get("token_value").is_none());
+}
+
+/// Ranges a mock HIBP-compatible API serves by hash prefix, and the prefixes asked for.
+type BreachedRangeApi = Arc<Mutex<(HashMap<String, String>, Vec<String>)>>;
+
+async fn breached_range_api(
+    State(api): State<BreachedRangeApi>,
+    Path(prefix): Path<String>,
+) -> String {
+    let mut api = api.lock().unwrap_or_else(PoisonError::into_inner);
+    api.1.push(prefix.clone());
+    api.0.get(&prefix).cloned().unwrap_or_default()
+}
+
+/// Range of a k-anonymity breach list holding `password`, plus a padding entry.
+fn breached_range(password: &str) -> (String, String) {
+    let hash = hex::encode_upper(Sha1::digest(password.as_bytes()));
+    let (prefix, suffix) = hash.split_at(5);
+    (
+        prefix.to_string(),
+        format!("{}:42\r\n{}:0\r\n", suffix, "0".repeat(35)),
+    )
+}
+
+async fn register_with(
+    server: &TestServer,
+    email: &str,
+    password: &str,
+) -> axum_test::TestResponse {
+    server
+        .post("/api/v1/auth/register")
+        .json(&json!({
+            "name": "tester",
+            "email": email,
+            "password": password,
+            "confirm_password": password,
+        }))
+        .await
+}
+
+#[tokio::test]
+async fn password_policy_is_applied_on_register_and_change_password() {
+    const BREACHED: &str = "Quiet-Harbor-Sail-77";
+    let dir = std::env::temp_dir().join(format!("backend-e2e-breached-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let (prefix, range) = breached_range(BREACHED);
+    std::fs::write(dir.join(format!("{}.txt", prefix)), range).unwrap();
+    let mut config = AppConfig::load("test").unwrap();
+    config.password_policy.breached_password_range_dir = Some(dir.to_string_lossy().into_owned());
+    let (server, outbox) = test_server_with(DBClient::memory(), config).await;
+
+    for (password, reason) in [
+        ("Sh0rt!", "at least"),
+        ("No-Digits-In-This-One", "number"),
+        ("NoSpecial4Characters9", "special"),
+        ("Password1!", "weak"),
+    ] {
+        let response = register_with(&server, "tester@example.com", password).await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        assert!(response.text().contains(reason), "{}", response.text());
+    }
+    let response = register_with(&server, "tester@example.com", BREACHED).await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert!(response.text().contains("data breach"));
+    // Long passphrases are fine, the old 20 character cap is gone.
+    register_with(
+        &server,
+        "phrase@example.com",
+        "correct horse battery staple 4 ever & ever",
+    )
+    .await
+    .assert_status_ok();
+
+    let authorization = sign_up(&server, &outbox, "tester@example.com").await;
+    let response = server
+        .post("/api/v1/user/change-password")
+        .add_header("Authorization", authorization)
+        .json(&json!({
+            "old_password": PASSWORD,
+            "new_password": BREACHED,
+            "new_confirm_password": BREACHED,
+        }))
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert!(response.text().contains("data breach"));
+}
+
+#[tokio::test]
+async fn breached_passwords_are_checked_against_a_range_api() {
+    const BREACHED: &str = "Quiet-Harbor-Sail-77";
+    let (prefix, range) = breached_range(BREACHED);
+    let api = BreachedRangeApi::default();
+    api.lock().unwrap().0.insert(prefix.clone(), range);
+    let router = Router::new()
+        .route("/range/{prefix}", get(breached_range_api))
+        .with_state(api.clone());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    let mut config = AppConfig::load("test").unwrap();
+    config.password_policy.breached_password_api_url = Some(format!("http://{}/range", address));
+    let (server, _outbox) = test_server_with(DBClient::memory(), config).await;
+
+    let response = register_with(&server, "tester@example.com", BREACHED).await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert!(response.text().contains("data breach"));
+    register(&server, "tester@example.com").await;
+    // Only the five character hash prefixes are ever sent.
+    let requested = api.lock().unwrap().1.clone();
+    assert_eq!(requested.len(), 2);
+    assert_eq!(requested[0], prefix);
+    assert!(requested.iter().all(|asked| asked.len() == 5));
+}
+
+#[tokio::test]
+async fn recent_passwords_cant_be_reused_and_older_ones_are_pruned() {
+    let mut config = AppConfig::load("test").unwrap();
+    config.password_policy.password_history_size = 3;
+    let db_client = DBClient::new(config.clone()).await.unwrap();
+    MigrationRunner::new(&config.migration)
+        .up(db_client.database.as_ref(), false)
+        .await
+        .unwrap();
+    let (server, outbox, app_state) = test_app(db_client, config).await;
+    let authorization = sign_up(&server, &outbox, "tester@example.com").await;
+    let passwords = [
+        PASSWORD,
+        "Tr1cky-Lantern-Orbit-One!",
+        "Tr1cky-Lantern-Orbit-Two!",
+        "Tr1cky-Lantern-Orbit-Three!",
+    ];
+    let change_password = |old_password: &str, new_password: &str| {
+        server
+            .post("/api/v1/user/change-password")
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({
+                "old_password": old_password,
+                "new_password": new_password,
+                "new_confirm_password": new_password,
+            }))
+    };
+    for pair in passwords.windows(2) {
+        change_password(pair[0], pair[1]).await.assert_status_ok();
+    }
+
+    // The current password and the two before it are remembered, the first one was pruned.
+    change_password(passwords[3], passwords[3])
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    for recent in &passwords[1..3] {
+        change_password(passwords[3], recent)
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+    let me = server
+        .get("/api/v1/user/me")
+        .add_header("Authorization", authorization.clone())
+        .await
+        .json::<Value>();
+    let user_id: Thing = serde_json::from_value(me["success"]["data"]["id"].clone()).unwrap();
+    let history = app_state
+        .db_client
+        .database
+        .find_password_history_by_user_id(user_id, 100)
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 2);
+
+    // Resetting the password goes through the same check.
+    server
+        .post("/api/v1/auth/forget-password")
+        .json(&json!({ "email": "tester@example.com" }))
+        .await
+        .assert_status_ok();
+    let reset_password = |token: String, new_password: &str| {
+        server.post("/api/v1/auth/reset-password").json(&json!({
+            "email": "tester@example.com",
+            "token": token,
+            "new_password": new_password,
+            "confirm_password": new_password,
+        }))
+    };
+    reset_password(last_email_token(&outbox), passwords[2])
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    server
+        .post("/api/v1/auth/forget-password")
+        .json(&json!({ "email": "tester@example.com" }))
+        .await
+        .assert_status_ok();
+    reset_password(last_email_token(&outbox), passwords[0])
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn devices_are_recognized_by_signed_cookie_or_browser_family() {
+    const CHROME: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0.0.0 Safari/537.36";
+    const FIREFOX_UPDATED: &str =
+        "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0.3";
+    let config = AppConfig::load("test").unwrap();
+    let secret = config.jwt_config.jwt_secret.clone();
+    let (server, outbox) = test_server_with(DBClient::memory(), config).await;
+    let authorization = sign_up(&server, &outbox, "tester@example.com").await;
+    let devices = server
+        .get("/api/v1/user/devices")
+        .add_header("Authorization", authorization)
+        .await
+        .json::<Value>();
+    let device_id: Thing =
+        serde_json::from_value(devices["success"]["data"][0]["id"].clone()).unwrap();
+    let signed = sign_device_id(&device_id, secret.as_bytes());
+    assert_eq!(
+        verify_device_cookie(&signed, secret.as_bytes()),
+        Some(device_id.id.to_raw())
+    );
+    assert_eq!(verify_device_cookie(&signed, b"another secret"), None);
+    assert_eq!(
+        verify_device_cookie("not-a-cookie", secret.as_bytes()),
+        None
+    );
+
+    let needs_verification = |user_agent: &'static str, cookie: Option<String>| {
+        let mut request = server
+            .post("/api/v1/auth/login")
+            .clear_cookies()
+            .clear_headers()
+            .add_header("User-Agent", user_agent)
+            .json(&json!({ "email": "tester@example.com", "password": PASSWORD }));
+        if let Some(cookie) = cookie {
+            request = request.add_cookie(Cookie::new(DEVICE_COOKIE_NAME, cookie));
+        }
+        async move {
+            let response = request.await;
+            response.assert_status_ok();
+            response.json::<Value>()["success"]["data"]["need_verification"] == true
+        }
+    };
+    // A forged or tampered signature isn't recognized.
+    let forged = sign_device_id(&device_id, b"guessed secret");
+    assert!(needs_verification(CHROME, Some(forged)).await);
+    let last = if signed.ends_with('0') { '1' } else { '0' };
+    let tampered = format!("{}{}", &signed[..signed.len() - 1], last);
+    assert!(needs_verification(CHROME, Some(tampered)).await);
+    // Without the cookie, a browser update within the same major version still matches.
+    assert!(!needs_verification(FIREFOX_UPDATED, None).await);
+    // Another browser is recognized by the signed cookie alone.
+    assert!(!needs_verification(CHROME, Some(signed)).await);
+}
+
+#[tokio::test]
+async fn login_with_wrong_password_is_rejected() {
+    let (server, outbox) = test_server().await;
+    sign_up(&server, &outbox, "tester@example.com").await;
+
+    login(&server, "tester@example.com", "Wr0ng-Lantern-Orbit!")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn anti_enumeration_answers_alike_for_known_and_unknown_emails() {
+    let mut config = AppConfig::load("test").unwrap();
+    config.security.anti_enumeration = true;
+    let (server, outbox, app_state) = test_app(DBClient::memory(), config).await;
+    register(&server, "tester@example.com").await;
+    let sent = || outbox.lock().unwrap_or_else(PoisonError::into_inner).len();
+    let hashed = || {
+        app_state
+            .services
+            .metrics
+            .password_hasher
+            .pool
+            .metrics
+            .latency
+            .count
+            .load(Ordering::Relaxed)
+    };
+
+    // Registering a taken email looks like a new registration and tells the owner instead.
+    let emails_before = sent();
+    let hashed_before = hashed();
+    let taken = server
+        .post("/api/v1/auth/register")
+        .json(&json!({
+            "name": "tester",
+            "email": "tester@example.com",
+            "password": PASSWORD,
+            "confirm_password": PASSWORD,
+        }))
+        .await;
+    taken.assert_status_ok();
+    let fresh = server
+        .post("/api/v1/auth/register")
+        .json(&json!({
+            "name": "tester",
+            "email": "fresh@example.com",
+            "password": PASSWORD,
+            "confirm_password": PASSWORD,
+        }))
+        .await;
+    assert_eq!(taken.text(), fresh.text());
+    assert_eq!(hashed() - hashed_before, 2);
+    for _ in 0..50 {
+        if sent() > emails_before {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    let outbox_html = outbox
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    assert!(
+        outbox_html
+            .last()
+            .unwrap()
+            .contains("Account Already Exists")
+    );
+
+    // An unknown email runs the same Argon2 verification as a wrong password.
+    let hashed_before = hashed();
+    let wrong_password = login(&server, "tester@example.com", "Wr0ng-Lantern-Orbit!").await;
+    assert_eq!(hashed() - hashed_before, 1);
+    let unknown_email = login(&server, "nobody@example.com", "Wr0ng-Lantern-Orbit!").await;
+    assert_eq!(hashed() - hashed_before, 2);
+    wrong_password.assert_status(StatusCode::UNAUTHORIZED);
+    unknown_email.assert_status(StatusCode::UNAUTHORIZED);
+    assert_eq!(wrong_password.text(), unknown_email.text());
+
+    let forget = |email: &'static str| {
+        server
+            .post("/api/v1/auth/forget-password")
+            .json(&json!({ "email": email }))
+    };
+    let known = forget("tester@example.com").await;
+    let unknown = forget("nobody@example.com").await;
+    known.assert_status_ok();
+    assert_eq!(known.text(), unknown.text());
+}
+
+#[tokio::test]
+async fn failed_logins_are_challenged_alike_for_unknown_emails() {
+    let mut config = AppConfig::load("test").unwrap();
+    config.challenge.challenge_provider = ChallengeProvider::Pow;
+    config.challenge.challenge_on_register = false;
+    config.security.anti_enumeration = true;
+    let (server, _outbox) = test_server_with(DBClient::memory(), config).await;
+    register(&server, "tester@example.com").await;
+
+    for email in ["tester@example.com", "nobody@example.com"] {
+        for _ in 0..3 {
+            login(&server, email, "Wr0ng-Lantern-Orbit!")
+                .await
+                .assert_status(StatusCode::UNAUTHORIZED);
+        }
+        login(&server, email, "Wr0ng-Lantern-Orbit!")
+            .await
+            .assert_status(StatusCode::PRECONDITION_REQUIRED);
+    }
+}
+
+#[tokio::test]
+async fn restore_account_answers_alike_and_counts_failures() {
+    let mut config = AppConfig::load("test").unwrap();
+    config.challenge.challenge_provider = ChallengeProvider::Pow;
+    config.challenge.challenge_on_register = false;
+    let (server, outbox) = test_server_with(DBClient::memory(), config).await;
+    let authorization = sign_up(&server, &outbox, "tester@example.com").await;
+    let restore = |password: &str| {
+        server
+            .post("/api/v1/auth/restore-account")
+            .json(&json!({ "email": "tester@example.com", "password": password }))
+    };
+
+    // The account isn't scheduled for deletion: a right password answers like a wrong one.
+    restore(PASSWORD)
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    restore("Wr0ng-Lantern-Orbit!")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    server
+        .delete("/api/v1/user/me")
+        .add_header("Authorization", authorization)
+        .json(&json!({ "password": PASSWORD }))
+        .await
+        .assert_status_ok();
+    restore("Wr0ng-Lantern-Orbit!")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    restore(PASSWORD)
+        .await
+        .assert_status(StatusCode::PRECONDITION_REQUIRED);
+
+    let (server, outbox) = test_server().await;
+    let authorization = sign_up(&server, &outbox, "tester@example.com").await;
+    server
+        .delete("/api/v1/user/me")
+        .add_header("Authorization", authorization)
+        .json(&json!({ "password": PASSWORD }))
+        .await
+        .assert_status_ok();
+    server
+        .post("/api/v1/auth/restore-account")
+        .json(&json!({ "email": "tester@example.com", "password": PASSWORD }))
+        .await
+        .assert_status_ok();
+}
+
+fn solve_proof_of_work(challenge: &Value) -> String {
+    let nonce = challenge["nonce"].as_str().unwrap();
+    let difficulty = challenge["difficulty"].as_u64().unwrap() as u32;
+    (0u64..)
+        .map(|attempt| attempt.to_string())
+        .find(|solution| {
+            let hash = Sha256::digest(format!("{}{}", nonce, solution).as_bytes());
+            let zero_bits = hash
+                .iter()
+                .position(|byte| *byte != 0)
+                .map_or(256, |i| i as u32 * 8 + hash[i].leading_zeros());
+            zero_bits >= difficulty
+        })
+        .unwrap()
+}
+
+async fn new_challenge(server: &TestServer) -> Value {
+    let response = server.get("/api/v1/auth/challenge").await;
+    response.assert_status_ok();
+    response.json::<Value>()["success"]["data"].clone()
+}
+
+fn register_request(email: &str, challenge: Value) -> Value {
+    json!({
+        "name": "tester",
+        "email": email,
+        "password": PASSWORD,
+        "confirm_password": PASSWORD,
+        "challenge": challenge,
+    })
+}
+
+#[tokio::test]
+async fn proof_of_work_challenges_are_answered_once_and_issued_sparingly() {
+    let mut config = AppConfig::load("test").unwrap();
+    config.challenge.challenge_provider = ChallengeProvider::Pow;
+    config.challenge.challenge_pow_difficulty = 8;
+    config.challenge.challenge_issue_limit_per_minute = 3;
+    let (server, _outbox) = test_server_with(DBClient::memory(), config).await;
+
+    server
+        .post("/api/v1/auth/register")
+        .json(&register_request("tester@example.com", Value::Null))
+        .await
+        .assert_status(StatusCode::PRECONDITION_REQUIRED);
+
+    let challenge = new_challenge(&server).await;
+    assert_eq!(challenge["kind"], "proof_of_work");
+    assert_eq!(challenge["difficulty"], 8);
+    let solution = solve_proof_of_work(&challenge);
+    let wrong = (0u64..)
+        .map(|attempt| format!("wrong-{}", attempt))
+        .find(|wrong| {
+            let hash = Sha256::digest(format!("{}{}", challenge["nonce"].as_str().unwrap(), wrong));
+            hash[0] != 0
+        })
+        .unwrap();
+    // A wrong answer uses the challenge up as well.
+    for (answer, status) in [
+        (wrong, StatusCode::BAD_REQUEST),
+        (solution, StatusCode::BAD_REQUEST),
+    ] {
+        server
+            .post("/api/v1/auth/register")
+            .json(&register_request(
+                "tester@example.com",
+                json!({ "challenge_id": challenge["id"], "solution": answer }),
+            ))
+            .await
+            .assert_status(status);
+    }
+
+    let challenge = new_challenge(&server).await;
+    let answer = json!({
+        "challenge_id": challenge["id"],
+        "solution": solve_proof_of_work(&challenge),
+    });
+    server
+        .post("/api/v1/auth/register")
+        .json(&register_request("tester@example.com", answer.clone()))
+        .await
+        .assert_status_ok();
+    server
+        .post("/api/v1/auth/register")
+        .json(&register_request("other@example.com", answer))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    new_challenge(&server).await;
+    server
+        .get("/api/v1/auth/challenge")
+        .await
+        .assert_status(StatusCode::TOO_MANY_REQUESTS);
+}
+
+type SiteVerifyRequests = Arc<Mutex<Vec<HashMap<String, String>>>>;
+
+async fn siteverify(
+    State(requests): State<SiteVerifyRequests>,
+    Form(form): Form<HashMap<String, String>>,
+) -> Json<Value> {
+    let success = form.get("secret").map(String::as_str) == Some("captcha-secret")
+        && form.get("response").map(String::as_str) == Some("captcha-token");
+    requests
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(form);
+    Json(json!({ "success": success }))
+}
+
+#[tokio::test]
+async fn captcha_tokens_are_checked_with_the_provider() {
+    let requests = SiteVerifyRequests::default();
+    let router = Router::new()
+        .route("/siteverify", post(siteverify))
+        .with_state(requests.clone());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    let mut config = AppConfig::load("test").unwrap();
+    config.challenge.challenge_provider = ChallengeProvider::Captcha;
+    config.challenge.challenge_captcha_verify_url = Some(format!("http://{}/siteverify", address));
+    config.challenge.challenge_captcha_site_key = Some("site-key".to_string());
+    config.challenge.challenge_captcha_secret = Some("captcha-secret".to_string());
+    let (server, _outbox) = test_server_with(DBClient::memory(), config).await;
+
+    let challenge = new_challenge(&server).await;
+    assert_eq!(challenge["kind"], "captcha");
+    assert_eq!(challenge["site_key"], "site-key");
+    server
+        .post("/api/v1/auth/register")
+        .json(&register_request(
+            "tester@example.com",
+            json!({ "challenge_id": challenge["id"], "solution": "forged-token" }),
+        ))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    let challenge = new_challenge(&server).await;
+    server
+        .post("/api/v1/auth/register")
+        .json(&register_request(
+            "tester@example.com",
+            json!({ "challenge_id": challenge["id"], "solution": "captcha-token" }),
+        ))
+        .await
+        .assert_status_ok();
+
+    let requests = requests.lock().unwrap_or_else(PoisonError::into_inner);
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[1]["response"], "captcha-token");
+    assert!(!requests[1]["remoteip"].is_empty());
+}
+
+#[tokio::test]
+async fn risky_logins_need_a_challenge() {
+    let mut config = AppConfig::load("test").unwrap();
+    config.challenge.challenge_provider = ChallengeProvider::Pow;
+    config.challenge.challenge_pow_difficulty = 8;
+    config.challenge.challenge_on_register = false;
+    // Only the risk assessment asks for a challenge here.
+    config.challenge.challenge_login_failures = 100;
+    let (server, outbox) = test_server_with(DBClient::memory(), config.clone()).await;
+    sign_up(&server, &outbox, "tester@example.com").await;
+
+    let failures = config.risk.risk_step_up_score / config.risk.risk_failed_attempt_score;
+    for _ in 0..failures {
+        login(&server, "tester@example.com", "Wr0ng-Lantern-Orbit!")
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+    }
+    login(&server, "tester@example.com", PASSWORD)
+        .await
+        .assert_status(StatusCode::PRECONDITION_REQUIRED);
+
+    // The code confirmed while signing up still covers this device, only the challenge is asked.
+    let challenge = new_challenge(&server).await;
+    let response = server
+        .post("/api/v1/auth/login")
+        .json(&json!({
+            "email": "tester@example.com",
+            "password": PASSWORD,
+            "challenge": {
+                "challenge_id": challenge["id"],
+                "solution": solve_proof_of_work(&challenge),
+            },
+        }))
+        .await;
+    response.assert_status_ok();
+}
+
+#[test]
+fn failed_attempts_combined_with_other_signals_only_step_up() {
+    let config = AppConfig::load("test").unwrap();
+    let engine = RiskEngine::new(&config.risk);
+    let berlin = GeoLocation {
+        latitude: Some(52.52),
+        longitude: Some(13.40),
+        ..Default::default()
+    };
+    let sydney = GeoLocation {
+        latitude: Some(-33.87),
+        longitude: Some(151.21),
+        ..Default::default()
+    };
+    let now = "2026-10-19T12:00:00Z".parse().unwrap();
+    // A trip to the other side of the world in an hour, on a known device, after an attacker
+    // failed enough logins on the email.
+    let context = LoginRiskContext {
+        now,
+        known_device: true,
+        ip_blacklisted: false,
+        location: Some(sydney),
+        previous_login: Some((berlin, now - chrono::Duration::hours(1))),
+        failed_attempts: 20,
+        timezone: None,
+    };
+    let assessment = engine.assess(&context);
+    assert!(assessment.score >= config.risk.risk_block_score);
+    assert_eq!(assessment.decision, RiskDecision::StepUp);
+    let assessment = engine.assess(&LoginRiskContext {
+        ip_blacklisted: true,
+        location: None,
+        previous_login: None,
+        ..context.clone()
+    });
+    assert!(assessment.score >= config.risk.risk_block_score);
+    assert_eq!(assessment.decision, RiskDecision::StepUp);
+
+    // The same login from a new device on a blacklisted IP is still refused.
+    let assessment = engine.assess(&LoginRiskContext {
+        known_device: false,
+        ip_blacklisted: true,
+        failed_attempts: 0,
+        ..context
+    });
+    assert_eq!(assessment.decision, RiskDecision::Block);
+}
+
+#[tokio::test]
+async fn failed_attempts_alone_never_block_the_owner() {
+    let (server, outbox) = test_server().await;
+    sign_up(&server, &outbox, "tester@example.com").await;
+
+    for _ in 0..20 {
+        login(&server, "tester@example.com", "Wr0ng-Lantern-Orbit!")
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+    }
+    login(&server, "tester@example.com", PASSWORD)
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn login_before_registering_is_rejected() {
+    let (server, _outbox) = test_server().await;
+
+    login(&server, "nobody@example.com", PASSWORD)
+        .await
+        .assert_status_not_ok();
+}
+
+#[tokio::test]
+async fn profile_requires_access_token() {
+    let (server, _outbox) = test_server().await;
+
+    server
+        .get("/api/v1/user/me")
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn liveness_does_not_check_dependencies() {
+    let (server, _outbox) = test_server().await;
+
+    server.get("/api/v1/health/live").await.assert_status_ok();
+}
+
+#[tokio::test]
+async fn readiness_reports_every_component() {
+    let (server, _outbox) = embedded_test_server().await;
+
+    let response = server.get("/api/v1/health/ready").await;
+    response.assert_status_ok();
+    let readiness = &response.json::<Value>()["success"]["data"];
+    assert_eq!(readiness["status"], "up");
+    let component = |name: &str| {
+        readiness["components"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["name"] == name)
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(component("surrealdb")["status"], "up");
+    assert!(component("surrealdb")["version"].is_string());
+    assert_eq!(component("surrealdb")["circuit"], "closed");
+    assert_eq!(component("redis")["status"], "disabled");
+    assert_eq!(component("mail")["status"], "up");
+    assert_eq!(component("migrations")["status"], "up");
+}
+
+#[tokio::test]
+async fn readiness_is_down_while_migrations_are_pending() {
+    let (server, _outbox) = test_server().await;
+
+    let response = server.get("/api/v1/health/ready").await;
+    response.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+    let readiness = &response.json::<Value>()["error"]["data"];
+    assert_eq!(readiness["status"], "down");
+    let migrations = readiness["components"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["name"] == "migrations")
+        .unwrap();
+    assert_eq!(migrations["status"], "down");
+    assert!(migrations["last_error"]["message"].is_string());
+}
+
+#[tokio::test]
+async fn metrics_are_not_served_unless_enabled() {
+    let (server, _outbox) = test_server().await;
+
+    server
+        .get("/metrics")
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn metrics_require_the_token_and_count_requests_by_route() {
+    let mut config = AppConfig::load("test").unwrap();
+    config.metrics.metrics_enabled = true;
+    config.metrics.metrics_token = Some("scrape-token".to_string());
+    let (server, outbox) = test_server_with(DBClient::memory(), config).await;
+    let authorization = sign_up(&server, &outbox, "tester@example.com").await;
+    for status in [StatusCode::ACCEPTED, StatusCode::TOO_MANY_REQUESTS] {
+        server
+            .post("/api/v1/user/export")
+            .add_header("Authorization", authorization.clone())
+            .await
+            .assert_status(status);
+    }
+    server
+        .method(Method::from_bytes(b"PURGE").unwrap(), "/api/v1/user/me")
+        .await;
+
+    server
+        .get("/metrics")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    server
+        .get("/metrics")
+        .add_header("Authorization", "Bearer wrong-token")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    let response = server
+        .get("/metrics")
+        .add_header("Authorization", "Bearer scrape-token")
+        .await;
+    response.assert_status_ok();
+    assert!(
+        response
+            .header("Content-Type")
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain; version=0.0.4")
+    );
+    let metrics = response.text();
+    assert!(metrics.contains(
+        r#"http_requests_total{method="POST",route="/api/v1/auth/register",status="200"}"#
+    ));
+    assert!(metrics.contains("# TYPE http_request_duration_seconds histogram"));
+    assert!(metrics.contains(r#"rate_limit_hits_total{route="/api/v1/user/export"}"#));
+    assert!(metrics.contains(r#"method="OTHER""#));
+    assert!(!metrics.contains("PURGE"));
+    assert!(metrics.contains(r#"logins_total{outcome="success"}"#));
+    assert!(metrics.contains("password_hashing_duration_seconds_count"));
+}
+
+#[tokio::test]
+async fn avatar_upload_stores_every_size_in_s3() {
+    let (config, bucket) = s3_config().await;
+    let (server, outbox) = test_server_with(DBClient::memory(), config).await;
+    let authorization = sign_up(&server, &outbox, "tester@example.com").await;
+    let mut upload = Cursor::new(Vec::new());
+    RgbImage::from_pixel(300, 200, image::Rgb([200, 40, 40]))
+        .write_to(&mut upload, ImageFormat::Png)
+        .unwrap();
+
+    let response = server
+        .put("/api/v1/user/avatar")
+        .add_header("Authorization", authorization)
+        .multipart(
+            MultipartForm::new().add_part(
+                "avatar",
+                Part::bytes(upload.into_inner())
+                    .file_name("avatar.png")
+                    .mime_type("image/png"),
+            ),
+        )
+        .await;
+    response.assert_status_ok();
+    let avatar_urls = response.json::<Value>()["success"]["data"]["avatar_urls"].clone();
+
+    let bucket = bucket.lock().unwrap_or_else(PoisonError::into_inner);
+    assert_eq!(bucket.len(), 3);
+    for (size, pixels) in [("small", 64), ("medium", 128), ("large", 256)] {
+        let (key, (content_type, body)) = bucket
+            .iter()
+            .find(|(key, _)| key.ends_with(&format!("/{}.png", size)))
+            .unwrap();
+        assert!(key.starts_with("avatars/"));
+        assert_eq!(content_type, "image/png");
+        let image = image::load_from_memory_with_format(body, ImageFormat::Png).unwrap();
+        assert_eq!((image.width(), image.height()), (pixels, pixels));
+        let url = avatar_urls[size].as_str().unwrap();
+        assert!(url.starts_with(&format!("{}/{}?v=", S3_PUBLIC_URL, key)));
+    }
+}
+
+#[tokio::test]
+async fn avatar_url_must_be_a_web_url_and_replaces_the_upload() {
+    let (server, outbox) = test_server().await;
+    let authorization = sign_up(&server, &outbox, "tester@example.com").await;
+    let mut upload = Cursor::new(Vec::new());
+    RgbImage::from_pixel(64, 64, image::Rgb([40, 200, 40]))
+        .write_to(&mut upload, ImageFormat::Png)
+        .unwrap();
+    let response = server
+        .put("/api/v1/user/avatar")
+        .add_header("Authorization", authorization.clone())
+        .multipart(MultipartForm::new().add_part(
+            "avatar",
+            Part::bytes(upload.into_inner()).mime_type("image/png"),
+        ))
+        .await;
+    response.assert_status_ok();
+    let me = response.json::<Value>()["success"]["data"].clone();
+    assert_eq!(me["avatar_url"], me["avatar_urls"]["large"]);
+
+    for avatar_url in [
+        "javascript:alert(1)",
+        "data:image/png;base64,AAAA",
+        "file:///etc/passwd",
+    ] {
+        server
+            .patch("/api/v1/user/me")
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({ "avatar_url": avatar_url, "updated_at": me["updated_at"] }))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    let response = server
+        .patch("/api/v1/user/me")
+        .add_header("Authorization", authorization.clone())
+        .json(&json!({
+            "avatar_url": "https://example.com/avatar.png",
+            "updated_at": me["updated_at"],
+        }))
+        .await;
+    response.assert_status_ok();
+    let me = server
+        .get("/api/v1/user/me")
+        .add_header("Authorization", authorization)
+        .await;
+    let updated = me.json::<Value>()["success"]["data"].clone();
+    assert_eq!(updated["avatar_url"], "https://example.com/avatar.png");
+    assert!(updated["avatar_urls"].is_null());
+}
+
+#[tokio::test]
+async fn s3_storage_signs_put_get_and_delete() {
+    let (config, bucket) = s3_config().await;
+    let storage = init_storage(&config.storage).unwrap();
+
+    storage
+        .put_object("exports/a b+c.json", b"{}".to_vec(), "application/json")
+        .await
+        .unwrap();
+    assert_eq!(
+        storage.get_object("exports/a b+c.json").await.unwrap(),
+        Some(b"{}".to_vec())
+    );
+    storage.delete_object("exports/a b+c.json").await.unwrap();
+    assert!(bucket.lock().unwrap().is_empty());
+    assert_eq!(
+        storage.get_object("exports/a b+c.json").await.unwrap(),
+        None
+    );
+
+    let mut wrong_secret = config.storage.clone();
+    wrong_secret.s3_secret_key = Some("wrong-secret-key".to_string());
+    assert!(
+        init_storage(&wrong_secret)
+            .unwrap()
+            .put_object("exports/denied.json", Vec::new(), "application/json")
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn exports_stay_out_of_the_public_storage_and_are_purged() {
+    let (mut config, bucket) = s3_config().await;
+    config.account.account_deletion_grace_days = 0;
+    let (server, outbox, app_state) = test_app(DBClient::memory(), config).await;
+    let authorization = sign_up(&server, &outbox, "tester@example.com").await;
+
+    let me = server
+        .get("/api/v1/user/me")
+        .add_header("Authorization", authorization.clone())
+        .await
+        .json::<Value>();
+    let user_id: Thing = serde_json::from_value(me["success"]["data"]["id"].clone()).unwrap();
+    let exports = || {
+        app_state
+            .db_client
+            .database
+            .find_data_exports_by_user_id(user_id.clone())
+    };
+    let (first, second) = tokio::join!(
+        server
+            .post("/api/v1/user/export")
+            .add_header("Authorization", authorization.clone()),
+        server
+            .post("/api/v1/user/export")
+            .add_header("Authorization", authorization.clone()),
+    );
+    // Only one of two concurrent requests gets through.
+    let mut statuses = [first.status_code(), second.status_code()];
+    statuses.sort();
+    assert_eq!(
+        statuses,
+        [StatusCode::ACCEPTED, StatusCode::TOO_MANY_REQUESTS]
+    );
+    // The export is written in the background.
+    for _ in 0..50 {
+        if !exports().await.unwrap().is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    let data_exports = exports().await.unwrap();
+    assert_eq!(data_exports.len(), 1);
+    assert!(
+        bucket
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_empty()
+    );
+    let response = server
+        .get(&format!("/api/v1/exports/{}", data_exports[0].token))
+        .await;
+    response.assert_status_ok();
+    assert_eq!(
+        response.json::<Value>()["profile"]["email"],
+        "tester@example.com"
+    );
+
+    server
+        .delete("/api/v1/user/me")
+        .add_header("Authorization", authorization)
+        .json(&json!({ "password": PASSWORD }))
+        .await
+        .assert_status_ok();
+    assert_eq!(
+        app_state
+            .services
+            .user
+            .purge_deleted_accounts()
+            .await
+            .unwrap(),
+        1
+    );
+    assert!(exports().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn hashes_under_a_previous_pepper_verify_and_are_rehashed() {
+    let db_client = DBClient::memory();
+    let server_with_peppers = |pepper: Option<&str>, previous_peppers: &[&str]| {
+        let mut config = AppConfig::load("test").unwrap();
+        config.password_hashing.password_pepper = pepper.map(str::to_string);
+        config.password_hashing.password_previous_peppers =
+            previous_peppers.iter().map(|p| p.to_string()).collect();
+        test_server_with(db_client.clone(), config)
+    };
+    let (server, outbox) = server_with_peppers(Some("old-pepper"), &[]).await;
+    sign_up(&server, &outbox, "tester@example.com").await;
+
+    let (server, _outbox) = server_with_peppers(Some("new-pepper"), &["old-pepper"]).await;
+    login(&server, "tester@example.com", PASSWORD)
+        .await
+        .assert_status_ok();
+    // The login above rehashed the password under the new pepper.
+    let (server, _outbox) = server_with_peppers(Some("new-pepper"), &[]).await;
+    login(&server, "tester@example.com", PASSWORD)
+        .await
+        .assert_status_ok();
+    let (server, _outbox) = server_with_peppers(None, &[]).await;
+    login(&server, "tester@example.com", PASSWORD)
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    // Removing the pepper works the same way.
+    let (server, _outbox) = server_with_peppers(None, &["new-pepper"]).await;
+    login(&server, "tester@example.com", PASSWORD)
+        .await
+        .assert_status_ok();
+}
+
+/// Href of the first link in the newest email.
+fn last_email_link(outbox: &Outbox) -> String {
+    let outbox = outbox.lock().unwrap_or_else(PoisonError::into_inner);
+    let html = outbox.last().expect("an email was sent");
+    let start = html.find("href=\"").expect("the email holds a link") + "href=\"".len();
+    let end = start + html[start..].find('"').unwrap();
+    html[start..end].to_string()
+}
+
+#[tokio::test]
+async fn login_alert_escapes_the_device_and_reporting_needs_a_post() {
+    let db_client = DBClient::memory();
+    let (server, outbox) =
+        test_server_with(db_client.clone(), AppConfig::load("test").unwrap()).await;
+    sign_up(&server, &outbox, "tester@example.com").await;
+
+    // Another device, whose User-Agent carries markup, signs in with the leaked password.
+    let (mut attacker, attacker_outbox) =
+        test_server_with(db_client, AppConfig::load("test").unwrap()).await;
+    attacker.clear_headers();
+    attacker.add_header(
+        "User-Agent",
+        r#"Mozilla/5.0 (Linux; Android 14; <a href="https://evil.example">Pixel</a> Build/UQ1A) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0.0.0 Mobile Safari/537.36"#,
+    );
+    login(&attacker, "tester@example.com", PASSWORD)
+        .await
+        .assert_status_ok();
+    attacker
+        .post("/api/v1/auth/verify-email")
+        .json(&json!({
+            "email": "tester@example.com",
+            "email_token": last_email_token(&attacker_outbox),
+        }))
+        .await
+        .assert_status_ok();
+    // The alert is sent in the background.
+    for _ in 0..50 {
+        if attacker_outbox.lock().unwrap().len() > 1 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    let alert = attacker_outbox.lock().unwrap().last().unwrap().clone();
+    assert!(alert.contains("New Sign-in To Your Account"));
+    assert!(!alert.contains("evil.example\">"));
+    assert!(alert.contains("&lt;a href=&quot;https://evil.example&quot;&gt;"));
+
+    let not_me_url = last_email_link(&attacker_outbox);
+    let path = &not_me_url[not_me_url.find("/api/v1/").unwrap()..];
+    // Opening the link, as a prefetcher would, changes nothing.
+    let page = server.get(path).await;
+    page.assert_status_ok();
+    assert!(page.text().contains(r#"<form method="post">"#));
+    server.post(path).await.assert_status_ok();
+    server.post(path).await.assert_status_not_ok();
+}
+
+#[tokio::test]
+async fn counters_expire_after_a_fixed_window() {
+    let store = MemoryCacheClient::default();
+    assert_eq!(store.incr_ex("counter", 1).await.unwrap(), 1);
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    // Later increments keep the expiry set by the first one.
+    assert_eq!(store.incr_ex("counter", 1).await.unwrap(), 2);
+    assert_eq!(store.incr_ex("other", 1).await.unwrap(), 1);
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    assert!(!store.exists("counter").await.unwrap());
+    assert_eq!(store.incr_ex("counter", 1).await.unwrap(), 1);
+    assert_eq!(store.incr_ex("other", 1).await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn circuit_opens_on_timeouts_and_closes_after_a_successful_probe() {
+    let mut config = AppConfig::load("test").unwrap().database;
+    config.db_query_timeout_ms = 50;
+    config.circuit_breaker_failure_threshold = 2;
+    config.circuit_breaker_reset_seconds = 1;
+    let breaker = CircuitBreaker::new("test", &config);
+    let hang = || async {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        Ok::<_, redis::RedisError>(())
+    };
+    let rejected = || async {
+        Err::<(), _>(redis::RedisError::from((
+            redis::ErrorKind::ResponseError,
+            "rejected",
+        )))
+    };
+
+    // Errors the server answers with don't count as failures.
+    for _ in 0..3 {
+        assert!(breaker.call(rejected(), |_| false).await.is_err());
+    }
+    assert_eq!(breaker.state(), CircuitState::Closed);
+
+    assert!(breaker.call(hang(), |_| true).await.is_err());
+    assert_eq!(breaker.state(), CircuitState::Closed);
+    assert!(breaker.call(hang(), |_| true).await.is_err());
+    assert_eq!(breaker.state(), CircuitState::Open);
+
+    // Open, queries fail without running.
+    let started_at = std::time::Instant::now();
+    let error = breaker
+        .call(async { Ok::<_, redis::RedisError>(()) }, |_| true)
+        .await
+        .unwrap_err();
+    assert!(started_at.elapsed() < Duration::from_millis(50));
+    assert_eq!(
+        error.into_response().status(),
+        StatusCode::SERVICE_UNAVAILABLE
+    );
+
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert_eq!(breaker.state(), CircuitState::HalfOpen);
+    // A failed probe opens it again right away, a successful one closes it.
+    assert!(breaker.call(hang(), |_| true).await.is_err());
+    assert_eq!(breaker.state(), CircuitState::Open);
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    breaker
+        .call(async { Ok::<_, redis::RedisError>(()) }, |_| true)
+        .await
+        .unwrap();
+    assert_eq!(breaker.state(), CircuitState::Closed);
+}
+
+#[tokio::test]
+async fn migrations_run_once_and_stop_when_an_applied_one_changed() {
+    let root = std::env::temp_dir().join(format!("backend-e2e-migrations-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(root.join("schemas")).unwrap();
+    std::fs::create_dir_all(root.join("migrations")).unwrap();
+    let write = |path: &str, sql: &str| std::fs::write(root.join(path), sql).unwrap();
+    // Applied migrations are recorded in the real `script_migration` table.
+    std::fs::copy(
+        "schemas/script_migration.surql",
+        root.join("schemas/script_migration.surql"),
+    )
+    .unwrap();
+    write(
+        "schemas/things.surql",
+        "DEFINE TABLE OVERWRITE things SCHEMALESS;",
+    );
+    write("migrations/0001_first.surql", "CREATE things:first;");
+    write("migrations/0002_second.surql", "CREATE things:second;");
+    let mut config = AppConfig::load("test").unwrap();
+    config.migration.migration_dir = root.display().to_string();
+    let db_client = DBClient::new(config.clone()).await.unwrap();
+    let database = db_client.database.as_ref();
+    let runner = MigrationRunner::new(&config.migration);
+    let states = || async {
+        runner
+            .status(database)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|status| (status.name, status.state))
+            .collect::<Vec<_>>()
+    };
+
+    // A dry run lists what would be applied and writes nothing.
+    assert_eq!(
+        runner.up(database, true).await.unwrap(),
+        ["0001_first", "0002_second"]
+    );
+    assert!(database.find_script_migrations().await.unwrap().is_empty());
+    assert_eq!(
+        states().await,
+        [
+            ("0001_first".to_string(), MigrationState::Pending),
+            ("0002_second".to_string(), MigrationState::Pending),
+        ]
+    );
+
+    assert_eq!(
+        runner.up(database, false).await.unwrap(),
+        ["0001_first", "0002_second"]
+    );
+    assert!(runner.up(database, false).await.unwrap().is_empty());
+    write("migrations/0003_third.surql", "CREATE things:third;");
+    assert_eq!(runner.up(database, true).await.unwrap(), ["0003_third"]);
+
+    // Editing an applied migration blocks every run, dry or not, until it's reverted.
+    write("migrations/0001_first.surql", "CREATE things:changed;");
+    assert_eq!(
+        states().await,
+        [
+            ("0001_first".to_string(), MigrationState::Drifted),
+            ("0002_second".to_string(), MigrationState::Applied),
+            ("0003_third".to_string(), MigrationState::Pending),
+        ]
+    );
+    for dry_run in [true, false] {
+        let error = runner.up(database, dry_run).await.unwrap_err();
+        assert!(error.to_string().contains("0001_first was changed"));
+    }
+    assert_eq!(database.find_script_migrations().await.unwrap().len(), 2);
+    write("migrations/0001_first.surql", "CREATE things:first;");
+    assert_eq!(runner.up(database, false).await.unwrap(), ["0003_third"]);
+
+    std::fs::remove_dir_all(root).unwrap();
+}